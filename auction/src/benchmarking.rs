@@ -166,6 +166,21 @@ benchmarks! {
 		assert_eq!(T::NFTExt::get_nft(nft_id).unwrap().state.is_listed, false);
 	}
 
+	cancel_auction_in_progress {
+		let s in 0 .. T::ParallelAuctionLimit::get() - 1;
+		let bench_data = prepare_benchmarks::<T>(Some(AuctionState::InProgress));
+		Auction::<T>::fill_deadline_queue(s, 99u32.into(), 10u32.into()).unwrap();
+		let bob: T::AccountId = get_account::<T>("BOB");
+		let nft_id = bench_data.bob_nft_id;
+		let auction = AuctionsStorage::<T>::get(nft_id).unwrap();
+		let charlie_bid = auction.buy_it_price.unwrap();
+
+		assert_ok!(TernoaAuctions::<T>::add_bid(origin::<T>("CHARLIE"), nft_id, charlie_bid));
+	}: _(RawOrigin::Signed(bob), nft_id)
+	verify {
+		assert_eq!(T::NFTExt::get_nft(nft_id).unwrap().state.is_listed, false);
+	}
+
 	end_auction {
 		let s in 0 .. T::ParallelAuctionLimit::get() - 1;
 		let bench_data = prepare_benchmarks::<T>(Some(AuctionState::Extended));
@@ -203,6 +218,23 @@ benchmarks! {
 		assert!(auction.bidders.list.contains(&(charlie, charlie_bid)))
 	}
 
+	add_max_bid {
+		let s in 0 .. T::BidderListLengthLimit::get() - 1;
+		let bench_data = prepare_benchmarks::<T>(Some(AuctionState::InProgress));
+		let charlie: T::AccountId = get_account::<T>("CHARLIE");
+		let eve: T::AccountId = get_account::<T>("EVE");
+		let nft_id = bench_data.bob_nft_id;
+		Auction::<T>::fill_bidders_list(s, nft_id, eve, 10u32.into()).unwrap();
+
+		let auction = AuctionsStorage::<T>::get(nft_id).unwrap();
+		let charlie_max =  auction.buy_it_price.unwrap();
+
+	}: _(RawOrigin::Signed(charlie.clone()), nft_id, charlie_max)
+	verify {
+		let auction = AuctionsStorage::<T>::get(nft_id).unwrap();
+		assert_eq!(auction.bidders.find_max_bid(&charlie), Some(&charlie_max));
+	}
+
 	remove_bid {
 		let s in 0 .. T::BidderListLengthLimit::get() - 1;
 		let bench_data = prepare_benchmarks::<T>(Some(AuctionState::InProgress));
@@ -252,6 +284,33 @@ benchmarks! {
 	verify {
 		assert_eq!(Claims::<T>::get(charlie), None);
 	}
+
+	settle_auction {
+		let s in 0 .. T::BidderListLengthLimit::get() - 1;
+		let bench_data = prepare_benchmarks::<T>(Some(AuctionState::InProgress));
+		let charlie: T::AccountId = get_account::<T>("CHARLIE");
+		let eve: T::AccountId = get_account::<T>("EVE");
+		let nft_id = bench_data.bob_nft_id;
+		Auction::<T>::fill_bidders_list(s, nft_id, eve, 10u32.into()).unwrap();
+
+		let auction = AuctionsStorage::<T>::get(nft_id).unwrap();
+		let charlie_bid = auction.buy_it_price.unwrap();
+		assert_ok!(TernoaAuctions::<T>::add_bid(origin::<T>("CHARLIE"), nft_id, charlie_bid));
+
+		// The auction is over but was not settled by the hook.
+		System::<T>::set_block_number(auction.end_block);
+	}: _(RawOrigin::Signed(charlie.clone()), nft_id)
+	verify {
+		let nft = T::NFTExt::get_nft(nft_id).unwrap();
+		assert_eq!(nft.state.is_listed, false);
+		assert_eq!(nft.owner, charlie);
+	}
+
+	set_bid_funds_mode {
+	}: _(RawOrigin::Root, BidFundsMode::Reserve)
+	verify {
+		assert_eq!(TernoaAuctions::<T>::current_bid_funds_mode(), BidFundsMode::Reserve);
+	}
 }
 
 impl_benchmark_test_suite!(
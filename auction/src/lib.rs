@@ -17,6 +17,7 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+mod migrations;
 #[cfg(test)]
 mod tests;
 mod types;
@@ -27,10 +28,11 @@ pub use pallet::*;
 use frame_support::{
 	pallet_prelude::*,
 	traits::{
-		Currency,
-		ExistenceRequirement::{AllowDeath, KeepAlive},
-		Get, StorageVersion,
+		BalanceStatus, Currency,
+		ExistenceRequirement::{self, AllowDeath, KeepAlive},
+		Get, NamedReservableCurrency, OnRuntimeUpgrade, StorageVersion,
 	},
+	storage::with_storage_layer,
 	PalletId,
 };
 use frame_system::pallet_prelude::*;
@@ -38,15 +40,22 @@ use primitives::{
 	common::CompoundFee,
 	nfts::{NFTData, NFTId},
 };
-use sp_runtime::traits::{AccountIdConversion, Saturating};
+use sp_runtime::{
+	traits::{AccountIdConversion, Saturating, Zero},
+	Permill,
+};
 use ternoa_common::traits::{MarketplaceExt, NFTExt};
+pub use types::BidFundsMode;
 use types::{AuctionData, BidderList, DeadlineList};
 pub use weights::WeightInfo;
 
 pub type BalanceOf<T> =
 	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
-const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+
+/// Identifier of the reserves holding the bids of the auctions.
+pub const AUCTION_RESERVE_ID: [u8; 8] = *b"auction ";
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -68,7 +77,7 @@ pub mod pallet {
 		type WeightInfo: WeightInfo;
 
 		/// Currency type.
-		type Currency: Currency<Self::AccountId>;
+		type Currency: NamedReservableCurrency<Self::AccountId, ReserveIdentifier = [u8; 8]>;
 
 		/// Link to the NFT pallet.
 		type NFTExt: NFTExt<AccountId = Self::AccountId>;
@@ -116,10 +125,36 @@ pub mod pallet {
 		/// Maximum number of related automatic auction actions in block.
 		#[pallet::constant]
 		type ActionsInBlockLimit: Get<u32>;
+
+		/// Share of the highest bid paid by the seller to the highest bidder when an auction is
+		/// cancelled while in progress.
+		#[pallet::constant]
+		type AuctionCancellationCompensation: Get<Permill>;
+
+		/// Default custody mode for the bids of new auctions.
+		#[pallet::constant]
+		type InitialBidFundsMode: Get<BidFundsMode>;
+
+		/// Amount by which the bid of a bidder with a maximum amount is raised when outbid.
+		#[pallet::constant]
+		type BidIncrement: Get<BalanceOf<Self>>;
 	}
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let mut weight = Weight::zero();
+
+			let version = StorageVersion::get::<Pallet<T>>();
+			if version == StorageVersion::new(1) {
+				weight = <migrations::v2::MigrationV2<T> as OnRuntimeUpgrade>::on_runtime_upgrade();
+
+				StorageVersion::put::<Pallet<T>>(&StorageVersion::new(2));
+			}
+
+			weight
+		}
+
 		/// Weight: see `begin_block`.
 		fn on_initialize(now: T::BlockNumber) -> Weight {
 			let mut read = 1u64;
@@ -132,46 +167,26 @@ pub mod pallet {
 
 			// As long as we have deadlines (or we hit the wall) to finish we should complete them
 			while let Some(nft_id) = deadlines.pop_next(now) {
-				let mut auction = match Auctions::<T>::get(nft_id) {
-					Some(x) => x,
-					None => continue,
-				};
-				let mut nft = match T::NFTExt::get_nft(nft_id) {
-					Some(x) => x,
+				let bidders = match Auctions::<T>::get(nft_id) {
+					Some(x) => x.get_bidders().len() as u64,
 					None => continue,
 				};
 
-				let highest_bid = auction.pop_highest_bid();
-				if let Some((new_owner, paid)) = highest_bid {
-					// Pay the fee
-					let cut = match Self::pay_for_nft(nft_id, &Self::account_id(), paid, &nft, &auction) {
-						Ok(x) => x,
-						Err(_x) => continue,
-					};
-
-					// Handle bidders
-					read += auction.get_bidders().iter().count() as u64;
-					auction.for_each_bidder(&|(owner, amount)| Self::add_claim(owner, *amount));
-
-					// Change the owner
-					nft.owner = new_owner.clone();
-
-					Self::emit_auction_completed_event(
-						nft_id,
-						Some(new_owner),
-						Some(paid),
-						Some(cut),
-					)
-				} else {
-					Self::emit_auction_completed_event(nft_id, None, None, None);
+				// Failed settlements are kept aside so that they can be retried with
+				// `settle_auction`. They count towards the limit of parallel auctions so there is
+				// always room for them.
+				if let Err(error) = Self::settle(nft_id) {
+					let kept = FailedSettlements::<T>::mutate(|x| {
+						x.contains(&nft_id) || x.try_push(nft_id).is_ok()
+					});
+					if !kept {
+						log::error!("Failed settlement of auction {} could not be kept", nft_id);
+					}
+					Self::deposit_event(Event::AuctionSettlementFailed { nft_id, error });
 				}
 
-				nft.state.is_listed = false;
-				_ = T::NFTExt::set_nft(nft_id, nft);
-				Auctions::<T>::remove(nft_id);
-
-				read += 3;
-				write += 2;
+				read += 5 + bidders;
+				write += 3 + bidders;
 				actions += 1;
 
 				if actions >= max_actions {
@@ -212,6 +227,24 @@ pub mod pallet {
 	pub type Deadlines<T: Config> =
 		StorageValue<_, DeadlineList<T::BlockNumber, T::ParallelAuctionLimit>, ValueQuery>;
 
+	/// Custody mode given to the bids of newly created auctions
+	#[pallet::storage]
+	#[pallet::getter(fn current_bid_funds_mode)]
+	pub type CurrentBidFundsMode<T: Config> =
+		StorageValue<_, BidFundsMode, ValueQuery, T::InitialBidFundsMode>;
+
+	/// Custody mode of the bids of each auction, auctions without an entry use transfers
+	#[pallet::storage]
+	#[pallet::getter(fn auction_bid_funds_mode)]
+	pub type AuctionBidFundsMode<T: Config> =
+		StorageMap<_, Blake2_128Concat, NFTId, BidFundsMode, OptionQuery>;
+
+	/// Auctions that could not be settled at their deadline
+	#[pallet::storage]
+	#[pallet::getter(fn failed_settlements)]
+	pub type FailedSettlements<T: Config> =
+		StorageValue<_, BoundedVec<NFTId, T::ParallelAuctionLimit>, ValueQuery>;
+
 	/// Holds the balance that user can claim
 	#[pallet::storage]
 	#[pallet::getter(fn claims)]
@@ -233,6 +266,12 @@ pub mod pallet {
 		},
 		/// An existing auction was cancelled.
 		AuctionCancelled { nft_id: NFTId },
+		/// An auction in progress was cancelled and the highest bidder was compensated.
+		AuctionCancelledInProgress {
+			nft_id: NFTId,
+			compensated_bidder: Option<T::AccountId>,
+			compensation: Option<BalanceOf<T>>,
+		},
 		/// An auction has completed and no more bids can be placed.
 		AuctionCompleted {
 			nft_id: NFTId,
@@ -250,8 +289,20 @@ pub mod pallet {
 		BidUpdated { nft_id: NFTId, bidder: T::AccountId, amount: BalanceOf<T> },
 		/// An existing bid was dropped.
 		BidDropped { nft_id: NFTId, bidder: T::AccountId, amount: BalanceOf<T> },
+		/// A bid with a maximum amount was created.
+		MaxBidAdded {
+			nft_id: NFTId,
+			bidder: T::AccountId,
+			amount: BalanceOf<T>,
+			max_amount: BalanceOf<T>,
+		},
 		/// Balance claimed.
 		BalanceClaimed { account: T::AccountId, amount: BalanceOf<T> },
+		/// The custody mode for the bids of new auctions was changed.
+		BidFundsModeSet { mode: BidFundsMode },
+		/// An auction could not be settled at its deadline, it can be settled with
+		/// `settle_auction`.
+		AuctionSettlementFailed { nft_id: NFTId, error: DispatchError },
 	}
 
 	// Errors inform users that something went wrong.
@@ -285,6 +336,10 @@ pub mod pallet {
 		CannotBuyItNowToYourOwnAuctions,
 		/// Auction cannot be canceled if the auction has started.
 		CannotCancelAuctionInProgress,
+		/// Auction cannot be canceled if the auction has not started (use `cancel_auction`).
+		CannotCancelAuctionNotStarted,
+		/// Auction cannot be canceled if the auction is soon to end.
+		CannotCancelAuctionAtTheEndOfAuction,
 		/// Cannot add a bid that is less than the current highest bid.
 		CannotBidLessThanTheHighestBid,
 		/// Cannot add a bid that is less than the current starting price.
@@ -333,6 +388,14 @@ pub mod pallet {
 		CannotListNFTsInTransmission,
 		/// The provided buy it now price does not match the real one.
 		PriceDoesNotMatch,
+		/// A new maximum amount must be higher than the amount already held for the bid.
+		CannotDecreaseMaxBid,
+		/// Operation not allowed because the auction has not ended yet.
+		AuctionNotEnded,
+		/// Operation not allowed because the auction has ended.
+		AuctionHasEnded,
+		/// The reserved funds of a bid do not cover the payment.
+		NotEnoughReservedFunds,
 	}
 
 	#[pallet::call]
@@ -406,8 +469,13 @@ pub mod pallet {
 				}
 			}
 
-			// Add NFT ID to deadlines
+			// Add NFT ID to deadlines, auctions waiting for a settlement retry take a slot too.
 			Deadlines::<T>::try_mutate(|x| -> DispatchResult {
+				let failed = FailedSettlements::<T>::decode_len().unwrap_or(0);
+				ensure!(
+					x.len() + failed < T::ParallelAuctionLimit::get() as usize,
+					Error::<T>::MaximumAuctionsLimitReached
+				);
 				x.insert(nft_id, end_block)
 					.map_err(|_| Error::<T>::MaximumAuctionsLimitReached)?;
 				Ok(())
@@ -430,6 +498,7 @@ pub mod pallet {
 			};
 
 			Auctions::<T>::insert(nft_id, auction_data);
+			AuctionBidFundsMode::<T>::insert(nft_id, CurrentBidFundsMode::<T>::get());
 
 			// Emit AuctionCreated event.
 			let event = Event::AuctionCreated {
@@ -459,12 +528,12 @@ pub mod pallet {
 			ensure!(!auction.has_started(now), Error::<T>::CannotCancelAuctionInProgress);
 
 			// Remove bidders
-			auction.for_each_bidder(&|(owner, amount)| Self::add_claim(owner, *amount));
+			let mode = Self::bid_funds_mode_of(nft_id);
+			auction.for_each_held_bid(&|(owner, amount)| Self::release_bid(mode, owner, *amount));
 
 			nft.state.is_listed = false;
 			T::NFTExt::set_nft(nft_id, nft)?;
-			Auctions::<T>::remove(nft_id);
-			Deadlines::<T>::mutate(|x| x.remove(nft_id));
+			Self::remove_auction(nft_id);
 
 			Self::deposit_event(Event::AuctionCancelled { nft_id });
 
@@ -476,27 +545,15 @@ pub mod pallet {
 		pub fn end_auction(origin: OriginFor<T>, nft_id: NFTId) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 
-			let mut nft = T::NFTExt::get_nft(nft_id).ok_or(Error::<T>::NFTNotFound)?;
-			let mut auction = Auctions::<T>::get(nft_id).ok_or(Error::<T>::AuctionDoesNotExist)?;
+			T::NFTExt::get_nft(nft_id).ok_or(Error::<T>::NFTNotFound)?;
+			let auction = Auctions::<T>::get(nft_id).ok_or(Error::<T>::AuctionDoesNotExist)?;
 
 			ensure!(auction.is_creator(&who), Error::<T>::NotTheAuctionCreator);
 			ensure!(auction.is_extended, Error::<T>::CannotEndAuctionThatWasNotExtended);
 
-			let (new_owner, paid) =
-				auction.pop_highest_bid().ok_or(Error::<T>::CannotEndAuctionWithoutBids)?;
-
-			let cut = Self::pay_for_nft(nft_id.clone(), &Self::account_id(), paid, &nft, &auction)?;
-			auction.for_each_bidder(&|(owner, amount)| Self::add_claim(owner, *amount));
-
-			// Change the owner
-			nft.owner = new_owner.clone();
-			nft.state.is_listed = false;
-
-			T::NFTExt::set_nft(nft_id, nft)?;
-			Auctions::<T>::remove(nft_id);
-			Deadlines::<T>::mutate(|x| x.remove(nft_id));
+			ensure!(auction.get_highest_bid().is_some(), Error::<T>::CannotEndAuctionWithoutBids);
 
-			Self::emit_auction_completed_event(nft_id, Some(new_owner), Some(paid), Some(cut));
+			Self::settle(nft_id)?;
 
 			Ok(().into())
 		}
@@ -523,6 +580,7 @@ pub mod pallet {
 
 				ensure!(!auction.is_creator(&who), Error::<T>::CannotAddBidToYourOwnAuctions);
 				ensure!(auction.has_started(now), Error::<T>::AuctionNotStarted);
+				ensure!(!auction.has_ended(now), Error::<T>::AuctionHasEnded);
 				ensure!(amount > T::ExistentialDeposit::get(), Error::<T>::AmountTooLow);
 
 				// ensure the bid is larger than the current highest bid.
@@ -535,23 +593,32 @@ pub mod pallet {
 					);
 				}
 
+				let mode = Self::bid_funds_mode_of(nft_id);
 				if let Some(existing_bid) = auction.find_bid(&who) {
-					let amount_difference = amount.saturating_sub(existing_bid.1);
-					T::Currency::transfer(&who, &Self::account_id(), amount_difference, KeepAlive)?;
+					// a maximum amount is replaced by the new bid.
+					let held = auction.bidders.held_amount(existing_bid);
+					if amount > held {
+						Self::hold_bid(mode, &who, amount.saturating_sub(held))?;
+					} else {
+						Self::release_bid(mode, &who, held.saturating_sub(amount));
+					}
 
 					auction.remove_bid(&who);
 				} else {
-					// transfer funds from caller.
-					T::Currency::transfer(&who, &Self::account_id(), amount, KeepAlive)?;
+					// take custody of the funds of the caller.
+					Self::hold_bid(mode, &who, amount)?;
 				}
 
 				// replace top bidder with caller.
 				// if bidder has been removed, refund removed user.
 				if let Some(bid) = auction.insert_new_bid(who.clone(), amount) {
-					Self::add_claim(&bid.0, bid.1);
+					Self::release_bid(mode, &bid.0, bid.1);
 					Self::deposit_event(Event::BidDropped { nft_id, bidder: bid.0, amount: bid.1 });
 				}
 
+				// let the bidders with a maximum amount outbid the caller.
+				Self::resolve_max_bids(nft_id, auction);
+
 				// extend auction by grace period if in ending period.
 				let grace_period = T::AuctionGracePeriod::get();
 				if let Some(new_end_block) = auction.extend_if_necessary(now, grace_period) {
@@ -590,7 +657,14 @@ pub mod pallet {
 				);
 
 				let bid = auction.find_bid(&who).ok_or(Error::<T>::BidDoesNotExist)?.clone();
-				T::Currency::transfer(&Self::account_id(), &bid.0, bid.1, AllowDeath)?;
+				let held = auction.bidders.held_amount(&bid);
+				match Self::bid_funds_mode_of(nft_id) {
+					BidFundsMode::Transfer =>
+						T::Currency::transfer(&Self::account_id(), &bid.0, held, AllowDeath)?,
+					BidFundsMode::Reserve => {
+						T::Currency::unreserve_named(&AUCTION_RESERVE_ID, &bid.0, held);
+					},
+				}
 
 				auction.remove_bid(&who);
 				Self::deposit_event(Event::BidRemoved { nft_id, bidder: who, amount: bid.1 });
@@ -619,21 +693,29 @@ pub mod pallet {
 			ensure!(paid_amount == signed_price, Error::<T>::PriceDoesNotMatch);
 			ensure!(!auction.is_creator(&who), Error::<T>::CannotBuyItNowToYourOwnAuctions);
 			ensure!(auction.has_started(now), Error::<T>::AuctionNotStarted);
+			ensure!(!auction.has_ended(now), Error::<T>::AuctionHasEnded);
 			if let Some(bid) = auction.get_highest_bid() {
 				ensure!(paid_amount > bid.1, Error::<T>::CannotBuyItWhenABidIsHigherThanBuyItPrice);
 			}
 
 			// Pay for NFT
-			let cut = Self::pay_for_nft(nft_id.clone(), &who, paid_amount, &nft, &auction)?;
+			let cut = Self::pay_for_nft(
+				nft_id.clone(),
+				&who,
+				paid_amount,
+				&nft,
+				&auction,
+				BidFundsMode::Transfer,
+			)?;
 			// Handle Bidders
-			auction.for_each_bidder(&|(owner, amount)| Self::add_claim(owner, *amount));
+			let mode = Self::bid_funds_mode_of(nft_id);
+			auction.for_each_held_bid(&|(owner, amount)| Self::release_bid(mode, owner, *amount));
 
 			nft.owner = who.clone();
 			nft.state.is_listed = false;
 
 			T::NFTExt::set_nft(nft_id, nft)?;
-			Auctions::<T>::remove(nft_id);
-			Deadlines::<T>::mutate(|x| x.remove(nft_id));
+			Self::remove_auction(nft_id);
 
 			Self::emit_auction_completed_event(nft_id, Some(who), Some(paid_amount), Some(cut));
 
@@ -654,6 +736,191 @@ pub mod pallet {
 
 			Ok(().into())
 		}
+
+		#[pallet::call_index(7)]
+		#[pallet::weight(T::WeightInfo::cancel_auction_in_progress(Deadlines::<T>::get().len() as u32))]
+		pub fn cancel_auction_in_progress(
+			origin: OriginFor<T>,
+			nft_id: NFTId,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let now = frame_system::Pallet::<T>::block_number();
+
+			let mut nft = T::NFTExt::get_nft(nft_id).ok_or(Error::<T>::NFTNotFound)?;
+			let auction = Auctions::<T>::get(nft_id).ok_or(Error::<T>::AuctionDoesNotExist)?;
+
+			ensure!(auction.is_creator(&who), Error::<T>::NotTheAuctionCreator);
+			ensure!(auction.has_started(now), Error::<T>::CannotCancelAuctionNotStarted);
+
+			let remaining_blocks = auction.end_block.saturating_sub(now);
+			ensure!(
+				remaining_blocks > T::AuctionEndingPeriod::get(),
+				Error::<T>::CannotCancelAuctionAtTheEndOfAuction
+			);
+
+			// The seller compensates the highest bidder
+			let compensation = match auction.get_highest_bid() {
+				Some((bidder, amount)) => {
+					let compensation = T::AuctionCancellationCompensation::get() * *amount;
+					T::Currency::transfer(&who, bidder, compensation, KeepAlive)?;
+					Some((bidder.clone(), compensation))
+				},
+				None => None,
+			};
+
+			// Refund bidders
+			let mode = Self::bid_funds_mode_of(nft_id);
+			auction.for_each_held_bid(&|(owner, amount)| Self::release_bid(mode, owner, *amount));
+
+			nft.state.is_listed = false;
+			T::NFTExt::set_nft(nft_id, nft)?;
+			Self::remove_auction(nft_id);
+
+			let (compensated_bidder, compensation) = compensation.unzip();
+			Self::deposit_event(Event::AuctionCancelledInProgress {
+				nft_id,
+				compensated_bidder,
+				compensation,
+			});
+
+			Ok(().into())
+		}
+
+		/// Set the custody mode for the bids of new auctions if the caller is root. Running
+		/// auctions keep the mode they were created with.
+		#[pallet::call_index(8)]
+		#[pallet::weight(T::WeightInfo::set_bid_funds_mode())]
+		pub fn set_bid_funds_mode(
+			origin: OriginFor<T>,
+			mode: BidFundsMode,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+			CurrentBidFundsMode::<T>::put(mode);
+			Self::deposit_event(Event::BidFundsModeSet { mode });
+
+			Ok(().into())
+		}
+
+		/// Let the chain bid for the caller up to `max_amount`. The maximum is held and the
+		/// visible bid of the caller is raised by `BidIncrement` whenever it gets outbid.
+		#[pallet::call_index(9)]
+		#[pallet::weight((
+            {
+				let s = Auctions::<T>::get(nft_id).map_or_else(|| 0, |x| x.get_bidders().len());
+				T::WeightInfo::add_max_bid(s as u32)
+            },
+			DispatchClass::Normal
+        ))]
+		pub fn add_max_bid(
+			origin: OriginFor<T>,
+			nft_id: NFTId,
+			max_amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let now = frame_system::Pallet::<T>::block_number();
+
+			let amount = Auctions::<T>::try_mutate(
+				nft_id,
+				|maybe_auction| -> Result<BalanceOf<T>, DispatchError> {
+					let auction = maybe_auction.as_mut().ok_or(Error::<T>::AuctionDoesNotExist)?;
+
+					ensure!(!auction.is_creator(&who), Error::<T>::CannotAddBidToYourOwnAuctions);
+					ensure!(auction.has_started(now), Error::<T>::AuctionNotStarted);
+					ensure!(!auction.has_ended(now), Error::<T>::AuctionHasEnded);
+					ensure!(max_amount > T::ExistentialDeposit::get(), Error::<T>::AmountTooLow);
+
+					// ensure the maximum is larger than the current highest bid.
+					let highest_bid = auction.get_highest_bid().cloned();
+					let floor = match &highest_bid {
+						Some(highest_bid) => {
+							ensure!(
+								max_amount > highest_bid.1,
+								Error::<T>::CannotBidLessThanTheHighestBid
+							);
+							highest_bid.1
+						},
+						None => {
+							ensure!(
+								max_amount > auction.start_price,
+								Error::<T>::CannotBidLessThanTheStartingPrice
+							);
+							auction.start_price
+						},
+					};
+
+					// the caller keeps its bid if it is already the highest one.
+					let amount = match highest_bid {
+						Some((bidder, amount)) if bidder == who => amount,
+						_ => {
+							let amount = floor.saturating_add(T::BidIncrement::get());
+							if amount > max_amount {
+								max_amount
+							} else {
+								amount
+							}
+						},
+					};
+
+					let mode = Self::bid_funds_mode_of(nft_id);
+					if let Some(existing_bid) = auction.find_bid(&who) {
+						let held = auction.bidders.held_amount(existing_bid);
+						ensure!(max_amount > held, Error::<T>::CannotDecreaseMaxBid);
+						Self::hold_bid(mode, &who, max_amount.saturating_sub(held))?;
+
+						auction.remove_bid(&who);
+					} else {
+						Self::hold_bid(mode, &who, max_amount)?;
+					}
+
+					if let Some(bid) = auction.insert_new_bid(who.clone(), amount) {
+						Self::release_bid(mode, &bid.0, bid.1);
+						Self::deposit_event(Event::BidDropped {
+							nft_id,
+							bidder: bid.0,
+							amount: bid.1,
+						});
+					}
+					auction.bidders.set_max_bid(who.clone(), max_amount);
+
+					Self::resolve_max_bids(nft_id, auction);
+
+					// extend auction by grace period if in ending period.
+					let grace_period = T::AuctionGracePeriod::get();
+					if let Some(new_end_block) = auction.extend_if_necessary(now, grace_period) {
+						Deadlines::<T>::mutate(|x| x.update(nft_id, new_end_block));
+					}
+
+					Ok(auction.find_bid(&who).map_or(amount, |x| x.1))
+				},
+			)?;
+
+			Self::deposit_event(Event::MaxBidAdded { nft_id, bidder: who, amount, max_amount });
+
+			Ok(().into())
+		}
+
+		/// Settle an auction that has ended but was not settled yet, either because its
+		/// settlement failed or because too many auctions ended in the same block. Anyone can
+		/// call this.
+		#[pallet::call_index(10)]
+		#[pallet::weight((
+            {
+				let s = Auctions::<T>::get(nft_id).map_or_else(|| 0, |x| x.get_bidders().len());
+				T::WeightInfo::settle_auction(s as u32)
+            },
+			DispatchClass::Normal
+        ))]
+		pub fn settle_auction(origin: OriginFor<T>, nft_id: NFTId) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			let now = frame_system::Pallet::<T>::block_number();
+
+			let auction = Auctions::<T>::get(nft_id).ok_or(Error::<T>::AuctionDoesNotExist)?;
+			ensure!(auction.end_block <= now, Error::<T>::AuctionNotEnded);
+
+			Self::settle(nft_id)?;
+
+			Ok(().into())
+		}
 	}
 }
 
@@ -669,6 +936,7 @@ impl<T: Config> Pallet<T> {
 		amount: BalanceOf<T>,
 		nft: &NFTData<T::AccountId, <<T as Config>::NFTExt as NFTExt>::NFTOffchainDataLimit>,
 		auction: &AuctionData<T::AccountId, T::BlockNumber, BalanceOf<T>, T::BidderListLengthLimit>,
+		mode: BidFundsMode,
 	) -> Result<(BalanceOf<T>, BalanceOf<T>, BalanceOf<T>), DispatchError> {
 		let nft_creator = &nft.creator;
 		let nft_royalty = nft.royalty;
@@ -694,13 +962,138 @@ impl<T: Config> Pallet<T> {
 			amount.saturating_sub(to_marketplace).saturating_sub(to_nft_creator);
 
 		let exist = if from == &Self::account_id() { AllowDeath } else { KeepAlive };
-		T::Currency::transfer(from, &marketplace.owner, to_marketplace, exist)?;
-		T::Currency::transfer(from, nft_creator, to_nft_creator, exist)?;
-		T::Currency::transfer(from, auction_creator, to_auction_creator, exist)?;
+		Self::pay_cut(mode, from, &marketplace.owner, to_marketplace, exist)?;
+		Self::pay_cut(mode, from, nft_creator, to_nft_creator, exist)?;
+		Self::pay_cut(mode, from, auction_creator, to_auction_creator, exist)?;
 
 		Ok((to_marketplace, to_nft_creator, to_auction_creator))
 	}
 
+	/// Move a part of a payment either from the free balance or from the reserved balance.
+	fn pay_cut(
+		mode: BidFundsMode,
+		from: &T::AccountId,
+		to: &T::AccountId,
+		amount: BalanceOf<T>,
+		exist: ExistenceRequirement,
+	) -> DispatchResult {
+		match mode {
+			BidFundsMode::Transfer => T::Currency::transfer(from, to, amount, exist),
+			BidFundsMode::Reserve => {
+				let remainder = T::Currency::repatriate_reserved_named(
+					&AUCTION_RESERVE_ID,
+					from,
+					to,
+					amount,
+					BalanceStatus::Free,
+				)?;
+				ensure!(remainder.is_zero(), Error::<T>::NotEnoughReservedFunds);
+				Ok(())
+			},
+		}
+	}
+
+	/// Raise the bids of the bidders with a maximum amount and emit an event for each of them.
+	pub fn resolve_max_bids(
+		nft_id: NFTId,
+		auction: &mut AuctionData<
+			T::AccountId,
+			T::BlockNumber,
+			BalanceOf<T>,
+			T::BidderListLengthLimit,
+		>,
+	) {
+		let raised = auction.bidders.resolve_max_bids(T::BidIncrement::get());
+		for (bidder, amount) in raised {
+			Self::deposit_event(Event::BidUpdated { nft_id, bidder, amount });
+		}
+	}
+
+	/// Returns the custody mode of the bids of an auction.
+	pub fn bid_funds_mode_of(nft_id: NFTId) -> BidFundsMode {
+		AuctionBidFundsMode::<T>::get(nft_id).unwrap_or(BidFundsMode::Transfer)
+	}
+
+	/// Returns the account holding the funds of a bid.
+	pub fn bid_payer(mode: BidFundsMode, bidder: &T::AccountId) -> T::AccountId {
+		match mode {
+			BidFundsMode::Transfer => Self::account_id(),
+			BidFundsMode::Reserve => bidder.clone(),
+		}
+	}
+
+	/// Take custody of the funds of a bid.
+	pub fn hold_bid(
+		mode: BidFundsMode,
+		account: &T::AccountId,
+		amount: BalanceOf<T>,
+	) -> DispatchResult {
+		match mode {
+			BidFundsMode::Transfer =>
+				T::Currency::transfer(account, &Self::account_id(), amount, KeepAlive),
+			BidFundsMode::Reserve =>
+				T::Currency::reserve_named(&AUCTION_RESERVE_ID, account, amount),
+		}
+	}
+
+	/// Give back the funds of a bid, transferred funds have to be claimed by the bidder.
+	pub fn release_bid(mode: BidFundsMode, account: &T::AccountId, amount: BalanceOf<T>) {
+		match mode {
+			BidFundsMode::Transfer => Self::add_claim(account, amount),
+			BidFundsMode::Reserve => {
+				T::Currency::unreserve_named(&AUCTION_RESERVE_ID, account, amount);
+			},
+		}
+	}
+
+	/// Complete an auction: the highest bidder pays for the NFT and becomes its owner and the
+	/// other bids are given back. Nothing is changed if the settlement fails.
+	pub fn settle(nft_id: NFTId) -> DispatchResult {
+		with_storage_layer(|| -> DispatchResult {
+			let mut auction = Auctions::<T>::get(nft_id).ok_or(Error::<T>::AuctionDoesNotExist)?;
+			let mut nft = T::NFTExt::get_nft(nft_id).ok_or(Error::<T>::NFTNotFound)?;
+			let mode = Self::bid_funds_mode_of(nft_id);
+
+			let highest_bid = auction.pop_highest_bid();
+			let (new_owner, paid, cut) = match highest_bid {
+				Some((new_owner, paid)) => {
+					// Pay the fee
+					let payer = Self::bid_payer(mode, &new_owner);
+					let cut = Self::pay_for_nft(nft_id, &payer, paid, &nft, &auction, mode)?;
+
+					// Handle bidders
+					if let Some(max) = auction.bidders.remove_max_bid(&new_owner) {
+						Self::release_bid(mode, &new_owner, max.saturating_sub(paid));
+					}
+					auction.for_each_held_bid(&|(owner, amount)| {
+						Self::release_bid(mode, owner, *amount)
+					});
+
+					// Change the owner
+					nft.owner = new_owner.clone();
+					(Some(new_owner), Some(paid), Some(cut))
+				},
+				None => (None, None, None),
+			};
+
+			nft.state.is_listed = false;
+			T::NFTExt::set_nft(nft_id, nft)?;
+			Self::remove_auction(nft_id);
+
+			Self::emit_auction_completed_event(nft_id, new_owner, paid, cut);
+
+			Ok(())
+		})
+	}
+
+	/// Remove an auction along with its bid custody mode, its deadline and its failed settlement.
+	pub fn remove_auction(nft_id: NFTId) {
+		Auctions::<T>::remove(nft_id);
+		AuctionBidFundsMode::<T>::remove(nft_id);
+		Deadlines::<T>::mutate(|x| x.remove(nft_id));
+		FailedSettlements::<T>::mutate(|x| x.retain(|id| *id != nft_id));
+	}
+
 	pub fn add_claim(account: &T::AccountId, amount: BalanceOf<T>) {
 		Claims::<T>::mutate(account, |x| {
 			*x = Some(x.unwrap_or(0u32.into()).saturating_add(amount));
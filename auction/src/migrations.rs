@@ -0,0 +1,96 @@
+// Copyright 2023 Capsule Corp (France) SAS.
+// This file is part of Ternoa.
+
+// Ternoa is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ternoa is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ternoa.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+
+pub mod v2 {
+	use super::*;
+	use frame_support::{
+		traits::OnRuntimeUpgrade, CloneNoBound, PartialEqNoBound, RuntimeDebugNoBound,
+	};
+	use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+	use primitives::marketplace::MarketplaceId;
+	use scale_info::TypeInfo;
+	use sp_std::fmt::Debug;
+	#[cfg(feature = "try-runtime")]
+	use sp_std::vec::Vec;
+
+	#[derive(
+		Encode, Decode, CloneNoBound, PartialEqNoBound, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen,
+	)]
+	#[codec(mel_bound(AccountId: MaxEncodedLen, BlockNumber: MaxEncodedLen, Balance: MaxEncodedLen))]
+	#[scale_info(skip_type_params(BidderListLengthLimit))]
+	pub struct OldAuctionData<AccountId, BlockNumber, Balance, BidderListLengthLimit>
+	where
+		AccountId: Clone + PartialEq + Debug,
+		BlockNumber: Clone + PartialEq + Debug,
+		Balance: Clone + PartialEq + Debug,
+		BidderListLengthLimit: Get<u32>,
+	{
+		pub creator: AccountId,
+		pub start_block: BlockNumber,
+		pub end_block: BlockNumber,
+		pub start_price: Balance,
+		pub buy_it_price: Option<Balance>,
+		pub bidders: BoundedVec<(AccountId, Balance), BidderListLengthLimit>,
+		pub marketplace_id: MarketplaceId,
+		pub is_extended: bool,
+	}
+
+	pub struct MigrationV2<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrationV2<T> {
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			log::info!("Pre-upgrade inside MigrationV2");
+			Ok(Vec::new())
+		}
+
+		fn on_runtime_upgrade() -> frame_support::weights::Weight {
+			let mut count = 0u64;
+			Auctions::<T>::translate(
+				|_id,
+				 old: OldAuctionData<
+					T::AccountId,
+					T::BlockNumber,
+					BalanceOf<T>,
+					T::BidderListLengthLimit,
+				>| {
+					count += 1;
+					let bidders = BidderList { list: old.bidders, max_bids: BoundedVec::default() };
+
+					Some(AuctionData {
+						creator: old.creator,
+						start_block: old.start_block,
+						end_block: old.end_block,
+						start_price: old.start_price,
+						buy_it_price: old.buy_it_price,
+						bidders,
+						marketplace_id: old.marketplace_id,
+						is_extended: old.is_extended,
+					})
+				},
+			);
+
+			T::DbWeight::get().reads_writes(count, count)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_: Vec<u8>) -> Result<(), &'static str> {
+			log::info!("Post-upgrade inside MigrationV2");
+			Ok(())
+		}
+	}
+}
@@ -16,7 +16,8 @@
 
 use super::mock::*;
 use frame_support::{
-	assert_noop, assert_ok, bounded_vec, pallet_prelude::DispatchResultWithPostInfo, BoundedVec,
+	assert_noop, assert_ok, bounded_vec, pallet_prelude::DispatchResultWithPostInfo,
+	traits::NamedReservableCurrency, BoundedVec,
 };
 use frame_system::RawOrigin;
 use pallet_balances::Error as BalanceError;
@@ -26,12 +27,14 @@ use primitives::{
 	CompoundFee, ConfigOp,
 };
 use sp_arithmetic::per_things::Permill;
+use sp_runtime::traits::BadOrigin;
 use ternoa_common::traits::{MarketplaceExt, NFTExt};
 
 use crate::{
 	tests::mock,
 	types::{AuctionData, BidderList},
-	Auctions, Claims, Config, Deadlines, Error, Event as AuctionEvent,
+	Auctions, BidFundsMode, Claims, Config, Deadlines, Error, Event as AuctionEvent,
+	FailedSettlements, AUCTION_RESERVE_ID,
 };
 
 const PERCENT_0: Permill = Permill::from_parts(0);
@@ -505,6 +508,34 @@ pub mod create_auction {
 			assert_noop!(ok, Error::<Test>::MaximumAuctionsLimitReached);
 		})
 	}
+
+	#[test]
+	fn failed_settlements_count_towards_limit() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+
+			// An ended auction waits for a settlement retry.
+			FailedSettlements::<Test>::put(BoundedVec::truncate_from(vec![100]));
+
+			let current_count = Auction::deadlines().len() + 1;
+			let limit = <Test as Config>::ParallelAuctionLimit::get() as usize;
+
+			(current_count..limit)
+				.map(|_| {
+					NFT::create_nft(origin(ALICE), BoundedVec::default(), PERCENT_0, None, false)
+						.unwrap();
+					NFT::next_nft_id() - 1
+				})
+				.for_each(|x| {
+					AuctionBuilder::new().nft_id(x).execute().unwrap();
+				});
+
+			NFT::create_nft(origin(ALICE), BoundedVec::default(), PERCENT_0, None, false).unwrap();
+
+			let ok = AuctionBuilder::new().nft_id(NFT::next_nft_id() - 1).execute();
+			assert_noop!(ok, Error::<Test>::MaximumAuctionsLimitReached);
+		})
+	}
 }
 
 pub mod cancel_auction {
@@ -592,6 +623,116 @@ pub mod cancel_auction {
 	}
 }
 
+pub mod cancel_auction_in_progress {
+	pub use super::*;
+
+	#[test]
+	fn cancel_auction_in_progress() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let auction = Auctions::<Test>::get(ALICE_NFT_ID_1).unwrap();
+			run_to_block(auction.start_block);
+
+			let loser_bid = auction.start_price + 10;
+			let highest_bid = loser_bid + 10;
+			assert_ok!(Auction::add_bid(origin(BOB), ALICE_NFT_ID_1, loser_bid));
+			assert_ok!(Auction::add_bid(origin(CHARLIE), ALICE_NFT_ID_1, highest_bid));
+
+			// Before execution
+			let mut deadlines = Deadlines::<Test>::get();
+			let mut nft = NFT::get_nft(ALICE_NFT_ID_1).unwrap();
+			let seller_balance = Balances::free_balance(ALICE);
+			let highest_bidder_balance = Balances::free_balance(CHARLIE);
+
+			// Execution
+			assert_ok!(Auction::cancel_auction_in_progress(origin(ALICE), ALICE_NFT_ID_1));
+
+			// Balance.
+			let compensation =
+				<Test as Config>::AuctionCancellationCompensation::get() * highest_bid;
+			assert_eq!(Balances::free_balance(ALICE), seller_balance - compensation);
+			assert_eq!(Balances::free_balance(CHARLIE), highest_bidder_balance + compensation);
+
+			// Storage.
+			deadlines.remove(ALICE_NFT_ID_1);
+			nft.state.is_listed = false;
+			assert_eq!(NFT::get_nft(ALICE_NFT_ID_1).unwrap(), nft);
+			assert_eq!(Claims::<Test>::get(BOB), Some(loser_bid));
+			assert_eq!(Claims::<Test>::get(CHARLIE), Some(highest_bid));
+			assert_eq!(Auctions::<Test>::get(ALICE_NFT_ID_1), None);
+			assert_eq!(Deadlines::<Test>::get(), deadlines);
+
+			// Check Events.
+			let event = AuctionEvent::AuctionCancelledInProgress {
+				nft_id: ALICE_NFT_ID_1,
+				compensated_bidder: Some(CHARLIE),
+				compensation: Some(compensation),
+			};
+			let event = RuntimeEvent::Auction(event);
+			System::assert_last_event(event);
+		})
+	}
+
+	#[test]
+	fn cancel_auction_in_progress_without_bids() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let auction = Auctions::<Test>::get(ALICE_NFT_ID_1).unwrap();
+			run_to_block(auction.start_block);
+
+			let seller_balance = Balances::free_balance(ALICE);
+			assert_ok!(Auction::cancel_auction_in_progress(origin(ALICE), ALICE_NFT_ID_1));
+
+			assert_eq!(Balances::free_balance(ALICE), seller_balance);
+			assert_eq!(Claims::<Test>::iter().count(), 0);
+			assert_eq!(Auctions::<Test>::get(ALICE_NFT_ID_1), None);
+
+			let event = AuctionEvent::AuctionCancelledInProgress {
+				nft_id: ALICE_NFT_ID_1,
+				compensated_bidder: None,
+				compensation: None,
+			};
+			let event = RuntimeEvent::Auction(event);
+			System::assert_last_event(event);
+		})
+	}
+
+	#[test]
+	fn not_the_auction_creator() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			run_to_block(DEFAULT_STARTBLOCK);
+
+			let err = Auction::cancel_auction_in_progress(origin(BOB), ALICE_NFT_ID_1);
+			assert_noop!(err, Error::<Test>::NotTheAuctionCreator);
+		})
+	}
+
+	#[test]
+	fn cannot_cancel_auction_not_started() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+
+			let err = Auction::cancel_auction_in_progress(origin(ALICE), ALICE_NFT_ID_1);
+			assert_noop!(err, Error::<Test>::CannotCancelAuctionNotStarted);
+		})
+	}
+
+	#[test]
+	fn cannot_cancel_auction_at_the_end_of_auction() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let auction = Auctions::<Test>::get(ALICE_NFT_ID_1).unwrap();
+			let auction_end_period = <Test as Config>::AuctionEndingPeriod::get();
+
+			run_to_block(auction.end_block - auction_end_period);
+
+			let err = Auction::cancel_auction_in_progress(origin(ALICE), ALICE_NFT_ID_1);
+			assert_noop!(err, Error::<Test>::CannotCancelAuctionAtTheEndOfAuction);
+		})
+	}
+}
+
 pub mod end_auction {
 	pub use super::*;
 
@@ -1032,6 +1173,118 @@ pub mod add_bid {
 	}
 }
 
+pub mod add_max_bid {
+	pub use super::*;
+
+	#[test]
+	fn add_max_bid() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let bob_balance = Balances::free_balance(BOB);
+			let auction = Auctions::<Test>::get(ALICE_NFT_ID_1).unwrap();
+			run_to_block(auction.start_block);
+
+			let max_amount = auction.start_price + 100;
+			assert_ok!(Auction::add_max_bid(origin(BOB), ALICE_NFT_ID_1, max_amount));
+
+			// Balance.
+			assert_eq!(Balances::free_balance(BOB), bob_balance - max_amount);
+			assert_eq!(Balances::free_balance(Auction::account_id()), max_amount);
+
+			// Storage.
+			let amount = auction.start_price + <Test as Config>::BidIncrement::get();
+			let auction = Auctions::<Test>::get(ALICE_NFT_ID_1).unwrap();
+			assert_eq!(auction.get_highest_bid(), Some(&(BOB, amount)));
+			assert_eq!(auction.bidders.find_max_bid(&BOB), Some(&max_amount));
+
+			// Check Events.
+			let event =
+				AuctionEvent::MaxBidAdded { nft_id: ALICE_NFT_ID_1, bidder: BOB, amount, max_amount };
+			let event = RuntimeEvent::Auction(event);
+			System::assert_last_event(event);
+		})
+	}
+
+	#[test]
+	fn add_bid_raises_max_bid() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let auction = Auctions::<Test>::get(ALICE_NFT_ID_1).unwrap();
+			run_to_block(auction.start_block);
+
+			let max_amount = auction.start_price + 100;
+			let charlie_bid = auction.start_price + 50;
+			assert_ok!(Auction::add_max_bid(origin(BOB), ALICE_NFT_ID_1, max_amount));
+			assert_ok!(Auction::add_bid(origin(CHARLIE), ALICE_NFT_ID_1, charlie_bid));
+
+			// Storage.
+			let bob_bid = charlie_bid + <Test as Config>::BidIncrement::get();
+			let auction = Auctions::<Test>::get(ALICE_NFT_ID_1).unwrap();
+			assert_eq!(auction.bidders.list.to_vec(), vec![(CHARLIE, charlie_bid), (BOB, bob_bid)]);
+
+			// Check Events.
+			let event =
+				AuctionEvent::BidUpdated { nft_id: ALICE_NFT_ID_1, bidder: BOB, amount: bob_bid };
+			System::assert_has_event(RuntimeEvent::Auction(event));
+		})
+	}
+
+	#[test]
+	fn auction_resolves_at_second_highest_max_bid() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let auction = Auctions::<Test>::get(ALICE_NFT_ID_1).unwrap();
+			run_to_block(auction.start_block);
+
+			let bob_max = auction.start_price + 100;
+			let charlie_max = auction.start_price + 80;
+			assert_ok!(Auction::add_max_bid(origin(BOB), ALICE_NFT_ID_1, bob_max));
+			assert_ok!(Auction::add_max_bid(origin(CHARLIE), ALICE_NFT_ID_1, charlie_max));
+
+			let paid = charlie_max + <Test as Config>::BidIncrement::get();
+			let bidders = Auctions::<Test>::get(ALICE_NFT_ID_1).unwrap().bidders.list.to_vec();
+			assert_eq!(bidders, vec![(CHARLIE, charlie_max), (BOB, paid)]);
+
+			run_to_block(auction.end_block);
+
+			// The winner gets back what is above the paid amount.
+			assert_eq!(NFT::get_nft(ALICE_NFT_ID_1).unwrap().owner, BOB);
+			assert_eq!(Claims::<Test>::get(BOB), Some(bob_max - paid));
+			assert_eq!(Claims::<Test>::get(CHARLIE), Some(charlie_max));
+		})
+	}
+
+	#[test]
+	fn cannot_decrease_max_bid() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let auction = Auctions::<Test>::get(ALICE_NFT_ID_1).unwrap();
+			run_to_block(auction.start_block);
+
+			let max_amount = auction.start_price + 100;
+			assert_ok!(Auction::add_max_bid(origin(BOB), ALICE_NFT_ID_1, max_amount));
+
+			let err = Auction::add_max_bid(origin(BOB), ALICE_NFT_ID_1, max_amount - 10);
+			assert_noop!(err, Error::<Test>::CannotDecreaseMaxBid);
+		})
+	}
+
+	#[test]
+	fn cannot_bid_less_than_the_highest_bid() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let auction = Auctions::<Test>::get(ALICE_NFT_ID_1).unwrap();
+			run_to_block(auction.start_block);
+
+			let bid = auction.start_price + 10;
+			assert_ok!(Auction::add_bid(origin(BOB), ALICE_NFT_ID_1, bid));
+
+			let err = Auction::add_max_bid(origin(CHARLIE), ALICE_NFT_ID_1, bid);
+			assert_noop!(err, Error::<Test>::CannotBidLessThanTheHighestBid);
+		})
+	}
+}
+
 pub mod remove_bid {
 	pub use super::*;
 
@@ -1353,6 +1606,188 @@ pub mod claim {
 	}
 }
 
+pub mod settle_auction {
+	pub use super::*;
+
+	#[test]
+	fn settle_overdue_auction() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let actions_in_block = <Test as Config>::ActionsInBlockLimit::get();
+
+			// More auctions end in the same block than what can be settled.
+			for _ in 0..actions_in_block {
+				NFT::create_nft(origin(ALICE), BoundedVec::default(), PERCENT_0, None, false)
+					.unwrap();
+				let nft_id = NFT::next_nft_id() - 1;
+				AuctionBuilder::new().nft_id(nft_id).end(DEFAULT_ENDBLOCK).execute().unwrap();
+			}
+
+			run_to_block(DEFAULT_ENDBLOCK);
+			let nft_id = Deadlines::<Test>::get().next(DEFAULT_ENDBLOCK).unwrap();
+			assert!(Auctions::<Test>::contains_key(nft_id));
+
+			let ok = Auction::settle_auction(origin(CHARLIE), nft_id);
+			assert_ok!(ok);
+
+			// Storage.
+			assert_eq!(NFT::get_nft(nft_id).unwrap().owner, ALICE);
+			assert_eq!(Auctions::<Test>::get(nft_id), None);
+			assert_eq!(Deadlines::<Test>::get().len(), 0);
+		})
+	}
+
+	#[test]
+	fn auction_does_not_exist() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+
+			let err = Auction::settle_auction(origin(BOB), INVALID_NFT_ID);
+			assert_noop!(err, Error::<Test>::AuctionDoesNotExist);
+		})
+	}
+
+	#[test]
+	fn auction_not_ended() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			run_to_block(DEFAULT_STARTBLOCK);
+
+			let err = Auction::settle_auction(origin(BOB), ALICE_NFT_ID_1);
+			assert_noop!(err, Error::<Test>::AuctionNotEnded);
+		})
+	}
+}
+
+pub mod set_bid_funds_mode {
+	pub use super::*;
+
+	#[test]
+	fn set_bid_funds_mode() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+
+			assert_ok!(Auction::set_bid_funds_mode(RawOrigin::Root.into(), BidFundsMode::Reserve));
+
+			// Storage.
+			assert_eq!(Auction::current_bid_funds_mode(), BidFundsMode::Reserve);
+			// Running auctions keep their mode.
+			assert_eq!(Auction::bid_funds_mode_of(ALICE_NFT_ID_1), BidFundsMode::Transfer);
+
+			// Check Events.
+			let event = AuctionEvent::BidFundsModeSet { mode: BidFundsMode::Reserve };
+			let event = RuntimeEvent::Auction(event);
+			System::assert_last_event(event);
+		})
+	}
+
+	#[test]
+	fn bad_origin() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+
+			let err = Auction::set_bid_funds_mode(origin(ALICE), BidFundsMode::Reserve);
+			assert_noop!(err, BadOrigin);
+		})
+	}
+
+	#[test]
+	fn add_and_remove_bid_with_reserve() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			assert_ok!(Auction::set_bid_funds_mode(RawOrigin::Root.into(), BidFundsMode::Reserve));
+			AuctionBuilder::new().start(DEFAULT_STARTBLOCK).execute().unwrap();
+			assert_eq!(Auction::bid_funds_mode_of(ALICE_NFT_ID_0), BidFundsMode::Reserve);
+
+			run_to_block(DEFAULT_STARTBLOCK);
+
+			let bob_balance = Balances::free_balance(BOB);
+			let bob_bid = DEFAULT_PRICE + 10;
+			let charlie_bid = bob_bid + 10;
+			assert_ok!(Auction::add_bid(origin(BOB), ALICE_NFT_ID_0, bob_bid));
+			assert_ok!(Auction::add_bid(origin(CHARLIE), ALICE_NFT_ID_0, charlie_bid));
+
+			// Funds stay on the accounts of the bidders.
+			assert_eq!(Balances::free_balance(BOB), bob_balance - bob_bid);
+			assert_eq!(Balances::reserved_balance(BOB), bob_bid);
+			assert_eq!(Balances::reserved_balance_named(&AUCTION_RESERVE_ID, &BOB), bob_bid);
+			assert_eq!(Balances::reserved_balance(CHARLIE), charlie_bid);
+			assert_eq!(Balances::free_balance(Auction::account_id()), 0);
+
+			assert_ok!(Auction::remove_bid(origin(BOB), ALICE_NFT_ID_0));
+			assert_eq!(Balances::free_balance(BOB), bob_balance);
+			assert_eq!(Balances::reserved_balance(BOB), 0);
+			assert_eq!(Claims::<Test>::iter().count(), 0);
+		})
+	}
+
+	#[test]
+	fn end_auction_with_reserve() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			assert_ok!(Auction::set_bid_funds_mode(RawOrigin::Root.into(), BidFundsMode::Reserve));
+			let ab = AuctionBuilder::new().start(DEFAULT_STARTBLOCK);
+			let end = ab.end;
+			ab.execute().unwrap();
+
+			run_to_block(DEFAULT_STARTBLOCK);
+
+			let seller_balance = Balances::free_balance(ALICE);
+			let bob_balance = Balances::free_balance(BOB);
+			let bob_bid = DEFAULT_PRICE + 10;
+			let charlie_bid = bob_bid + 10;
+			assert_ok!(Auction::add_bid(origin(BOB), ALICE_NFT_ID_0, bob_bid));
+			assert_ok!(Auction::add_bid(origin(CHARLIE), ALICE_NFT_ID_0, charlie_bid));
+
+			run_to_block(end);
+
+			// The winner pays from the reserve and the others get their funds back.
+			assert_eq!(Balances::reserved_balance(CHARLIE), 0);
+			assert_eq!(Balances::reserved_balance(BOB), 0);
+			assert_eq!(Balances::free_balance(BOB), bob_balance);
+			// Alice is both the marketplace owner and the seller.
+			assert_eq!(Balances::free_balance(ALICE), seller_balance + charlie_bid);
+			assert_eq!(NFT::get_nft(ALICE_NFT_ID_0).unwrap().owner, CHARLIE);
+			assert_eq!(Auction::auction_bid_funds_mode(ALICE_NFT_ID_0), None);
+			assert_eq!(Claims::<Test>::iter().count(), 0);
+		})
+	}
+
+	#[test]
+	fn end_auction_with_missing_reserve() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			assert_ok!(Auction::set_bid_funds_mode(RawOrigin::Root.into(), BidFundsMode::Reserve));
+			let ab = AuctionBuilder::new().start(DEFAULT_STARTBLOCK);
+			let end = ab.end;
+			ab.execute().unwrap();
+
+			run_to_block(DEFAULT_STARTBLOCK);
+
+			let seller_balance = Balances::free_balance(ALICE);
+			let bob_bid = DEFAULT_PRICE + 10;
+			assert_ok!(Auction::add_bid(origin(BOB), ALICE_NFT_ID_0, bob_bid));
+			// Part of the reserve of the bid is taken away before the end of the auction.
+			Balances::slash_reserved_named(&AUCTION_RESERVE_ID, &BOB, 10);
+
+			run_to_block(end);
+
+			// Nothing is paid and the settlement can be retried.
+			assert_eq!(Balances::free_balance(ALICE), seller_balance);
+			assert_eq!(Balances::reserved_balance(BOB), bob_bid - 10);
+			assert_eq!(NFT::get_nft(ALICE_NFT_ID_0).unwrap().owner, ALICE);
+			assert!(Auctions::<Test>::get(ALICE_NFT_ID_0).is_some());
+			assert_eq!(FailedSettlements::<Test>::get().to_vec(), vec![ALICE_NFT_ID_0]);
+
+			// Check Events.
+			let error = Error::<Test>::NotEnoughReservedFunds.into();
+			let event = AuctionEvent::AuctionSettlementFailed { nft_id: ALICE_NFT_ID_0, error };
+			let event = RuntimeEvent::Auction(event);
+			System::assert_last_event(event);
+		})
+	}
+}
+
 pub struct AuctionBuilder {
 	pub origin: mock::RuntimeOrigin,
 	pub nft_id: NFTId,
@@ -15,15 +15,16 @@
 // along with Ternoa.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::mock::*;
-use frame_support::{assert_ok, bounded_vec, BoundedVec};
+use frame_support::{assert_noop, assert_ok, bounded_vec, BoundedVec};
 use frame_system::RawOrigin;
-use primitives::{marketplace::MarketplaceType, nfts::NFTId};
+use primitives::{marketplace::MarketplaceType, nfts::NFTId, CompoundFee};
 use sp_runtime::Permill;
 
 use crate::{
 	tests::{extrinsics::AuctionBuilder, mock},
 	types::{AuctionData, BidderList, DeadlineList},
-	Auctions as AuctionsStorage, Config, Deadlines,
+	AuctionCommissionFee, Auctions as AuctionsStorage, Config, Deadlines, Error,
+	Event as AuctionEvent, FailedSettlements,
 };
 
 const PERCENT_0: Permill = Permill::from_parts(0);
@@ -162,3 +163,95 @@ fn auctions_in_block() {
 		assert_eq!(AuctionsStorage::<Test>::iter().count(), 0);
 	})
 }
+
+#[test]
+fn failed_settlement_is_kept_for_retry() {
+	ExtBuilder::new_build(None).execute_with(|| {
+		let alice: mock::RuntimeOrigin = origin(ALICE);
+
+		NFT::create_nft(alice.clone(), BoundedVec::default(), PERCENT_0, None, false).unwrap();
+		Marketplace::create_marketplace(alice, MarketplaceType::Public).unwrap();
+
+		let auction = AuctionBuilder::new();
+		let end = auction.end;
+		auction.execute().unwrap();
+		assert_ok!(Auction::add_bid(origin(BOB), ALICE_NFT_ID_0, 200));
+
+		// Break the settlement by removing the commission of the auction.
+		AuctionCommissionFee::<Test>::remove(ALICE_NFT_ID_0);
+		run_to_block(end);
+
+		assert_eq!(Deadlines::<Test>::get().len(), 0);
+		assert!(AuctionsStorage::<Test>::contains_key(ALICE_NFT_ID_0));
+		assert_eq!(FailedSettlements::<Test>::get().to_vec(), vec![ALICE_NFT_ID_0]);
+
+		let event = AuctionEvent::AuctionSettlementFailed {
+			nft_id: ALICE_NFT_ID_0,
+			error: Error::<Test>::AuctionCommissionDoesNotExist.into(),
+		};
+		System::assert_last_event(RuntimeEvent::Auction(event));
+
+		// Anyone can settle the auction once it can be paid.
+		AuctionCommissionFee::<Test>::insert(ALICE_NFT_ID_0, None::<CompoundFee<Balance>>);
+		assert_ok!(Auction::settle_auction(origin(CHARLIE), ALICE_NFT_ID_0));
+
+		assert_eq!(NFT::get_nft(ALICE_NFT_ID_0).unwrap().owner, BOB);
+		assert!(!AuctionsStorage::<Test>::contains_key(ALICE_NFT_ID_0));
+		assert_eq!(FailedSettlements::<Test>::get().len(), 0);
+	})
+}
+
+#[test]
+fn failed_settlement_rejects_bids() {
+	ExtBuilder::new_build(None).execute_with(|| {
+		let alice: mock::RuntimeOrigin = origin(ALICE);
+
+		NFT::create_nft(alice.clone(), BoundedVec::default(), PERCENT_0, None, false).unwrap();
+		Marketplace::create_marketplace(alice, MarketplaceType::Public).unwrap();
+
+		let auction = AuctionBuilder::new().now_buy(Some(1000));
+		let end = auction.end;
+		auction.execute().unwrap();
+		assert_ok!(Auction::add_bid(origin(BOB), ALICE_NFT_ID_0, 200));
+
+		AuctionCommissionFee::<Test>::remove(ALICE_NFT_ID_0);
+		run_to_block(end);
+		assert_eq!(FailedSettlements::<Test>::get().to_vec(), vec![ALICE_NFT_ID_0]);
+
+		// The auction has ended even though it is not settled yet.
+		let err = Auction::add_bid(origin(CHARLIE), ALICE_NFT_ID_0, 300);
+		assert_noop!(err, Error::<Test>::AuctionHasEnded);
+		let err = Auction::add_max_bid(origin(CHARLIE), ALICE_NFT_ID_0, 300);
+		assert_noop!(err, Error::<Test>::AuctionHasEnded);
+		let err = Auction::buy_it_now(origin(CHARLIE), ALICE_NFT_ID_0, 1000);
+		assert_noop!(err, Error::<Test>::AuctionHasEnded);
+	})
+}
+
+#[test]
+fn failed_settlement_ended_by_creator_frees_its_slot() {
+	ExtBuilder::new_build(None).execute_with(|| {
+		let alice: mock::RuntimeOrigin = origin(ALICE);
+
+		NFT::create_nft(alice.clone(), BoundedVec::default(), PERCENT_0, None, false).unwrap();
+		Marketplace::create_marketplace(alice.clone(), MarketplaceType::Public).unwrap();
+
+		let auction = AuctionBuilder::new();
+		let end = auction.end;
+		auction.execute().unwrap();
+		assert_ok!(Auction::add_bid(origin(BOB), ALICE_NFT_ID_0, 200));
+		AuctionsStorage::<Test>::mutate(ALICE_NFT_ID_0, |x| x.as_mut().unwrap().is_extended = true);
+
+		AuctionCommissionFee::<Test>::remove(ALICE_NFT_ID_0);
+		run_to_block(end);
+		assert_eq!(FailedSettlements::<Test>::get().to_vec(), vec![ALICE_NFT_ID_0]);
+
+		// The creator ends the auction instead of retrying the settlement.
+		AuctionCommissionFee::<Test>::insert(ALICE_NFT_ID_0, None::<CompoundFee<Balance>>);
+		assert_ok!(Auction::end_auction(alice, ALICE_NFT_ID_0));
+
+		assert_eq!(NFT::get_nft(ALICE_NFT_ID_0).unwrap().owner, BOB);
+		assert!(!AuctionsStorage::<Test>::contains_key(ALICE_NFT_ID_0));
+		assert_eq!(FailedSettlements::<Test>::get().len(), 0);
+	})
+}
@@ -130,6 +130,76 @@ mod bidder_list {
 		assert_eq!(bidders_list.remove_highest_bid(), Some((11, 12)));
 		assert_eq!(bidders_list.remove_highest_bid(), Some((10, 11)));
 	}
+
+	#[test]
+	fn test_max_bids_resolution() {
+		type MockBalance = u32;
+		type MockAccount = u32;
+
+		let mut bidders_list: BidderList<MockAccount, MockBalance, BidderListLengthLimit> =
+			BidderList::new();
+		let increment = 5u32;
+
+		// a single max bid is not raised.
+		bidders_list.insert_new_bid(1, 10);
+		bidders_list.set_max_bid(1, 50);
+		assert_eq!(bidders_list.resolve_max_bids(increment), vec![]);
+		assert_eq!(bidders_list.list, vec![(1, 10)]);
+		assert_eq!(bidders_list.held_amount(&(1, 10)), 50);
+
+		// an outbid max bid is raised by the increment.
+		bidders_list.insert_new_bid(2, 20);
+		assert_eq!(bidders_list.resolve_max_bids(increment), vec![(1, 25)]);
+		assert_eq!(bidders_list.list, vec![(2, 20), (1, 25)]);
+
+		// the highest max bid stands at the second highest max plus the increment.
+		bidders_list.insert_new_bid(3, 30);
+		bidders_list.set_max_bid(3, 40);
+		assert_eq!(bidders_list.resolve_max_bids(increment), vec![(3, 40), (1, 45)]);
+		assert_eq!(bidders_list.list, vec![(2, 20), (3, 40), (1, 45)]);
+
+		// the earliest bid wins when the maximum is reached.
+		assert_eq!(bidders_list.insert_new_bid(4, 50), Some((2, 20)));
+		assert_eq!(bidders_list.resolve_max_bids(increment), vec![(1, 50)]);
+		assert_eq!(bidders_list.list, vec![(3, 40), (4, 50), (1, 50)]);
+
+		// dropped and removed bids give back the maximum amount.
+		assert_eq!(bidders_list.remove_bid(&1), Some((1, 50)));
+		assert_eq!(bidders_list.find_max_bid(&1), None);
+		bidders_list.set_max_bid(4, 70);
+		bidders_list.insert_new_bid(5, 60);
+		assert_eq!(bidders_list.insert_new_bid(6, 65), Some((3, 40)));
+		assert_eq!(bidders_list.resolve_max_bids(increment), vec![(4, 70)]);
+		assert_eq!(bidders_list.list, vec![(5, 60), (6, 65), (4, 70)]);
+	}
+
+	#[test]
+	fn test_equal_max_bids_resolution() {
+		type MockBalance = u32;
+		type MockAccount = u32;
+
+		let mut bidders_list: BidderList<MockAccount, MockBalance, BidderListLengthLimit> =
+			BidderList::new();
+		let increment = 5u32;
+
+		// the maximum set first wins, whatever the position of the bids in the list.
+		bidders_list.insert_new_bid(1, 10);
+		bidders_list.insert_new_bid(2, 20);
+		bidders_list.set_max_bid(2, 50);
+		bidders_list.set_max_bid(1, 50);
+		assert_eq!(bidders_list.resolve_max_bids(increment), vec![(1, 50), (2, 50)]);
+		assert_eq!(bidders_list.list, vec![(1, 50), (2, 50)]);
+		assert_eq!(bidders_list.get_highest_bid(), Some(&(2, 50)));
+
+		// a maximum wins over a plain bid of the same amount.
+		let mut bidders_list: BidderList<MockAccount, MockBalance, BidderListLengthLimit> =
+			BidderList::new();
+		bidders_list.insert_new_bid(1, 10);
+		bidders_list.set_max_bid(1, 50);
+		bidders_list.insert_new_bid(2, 50);
+		assert_eq!(bidders_list.resolve_max_bids(increment), vec![(1, 50)]);
+		assert_eq!(bidders_list.get_highest_bid(), Some(&(1, 50)));
+	}
 }
 
 mod deadline_list {
@@ -14,12 +14,23 @@
 // You should have received a copy of the GNU General Public License
 // along with Ternoa.  If not, see <http://www.gnu.org/licenses/>.
 
-use frame_support::{traits::Get, BoundedVec, CloneNoBound, PartialEqNoBound, RuntimeDebugNoBound};
+use frame_support::{
+	traits::Get, BoundedVec, CloneNoBound, PartialEqNoBound, RuntimeDebug, RuntimeDebugNoBound,
+};
 use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
 use primitives::{marketplace::MarketplaceId, nfts::NFTId};
 use scale_info::TypeInfo;
 use sp_std::{fmt::Debug, prelude::*, vec::Vec};
 
+#[derive(Encode, Decode, Copy, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+/// Custody of the funds of the bids
+pub enum BidFundsMode {
+	/// Bids are transferred to the pallet account and refunds have to be claimed
+	Transfer,
+	/// Bids are reserved on the account of the bidder and refunds are unreserved
+	Reserve,
+}
+
 #[derive(
 	Encode, Decode, CloneNoBound, PartialEqNoBound, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen,
 )]
@@ -77,6 +88,10 @@ where
 		now >= self.start_block
 	}
 
+	pub fn has_ended(&self, now: BlockNumber) -> bool {
+		now >= self.end_block
+	}
+
 	pub fn is_creator(&self, account_id: &AccountId) -> bool {
 		self.creator == *account_id
 	}
@@ -85,6 +100,11 @@ where
 		self.bidders.list.iter().for_each(f);
 	}
 
+	/// Call `f` with every bidder and the amount held for its bid
+	pub fn for_each_held_bid(&self, f: &dyn Fn(&(AccountId, Balance))) {
+		self.bidders.list.iter().for_each(|x| f(&(x.0.clone(), self.bidders.held_amount(x))));
+	}
+
 	/// Remove a specific bid from `account_id` from list if it exists
 	pub fn remove_bid(&mut self, account_id: &AccountId) -> Option<(AccountId, Balance)> {
 		self.bidders.remove_bid(account_id)
@@ -137,7 +157,10 @@ where
 	Balance: Clone + PartialEq + Debug + sp_std::cmp::PartialOrd,
 	BidderListLengthLimit: Get<u32>,
 {
+	/// Visible bids sorted by amount
 	pub list: BoundedVec<(AccountId, Balance), BidderListLengthLimit>,
+	/// Maximum amounts of the bidders that let the chain bid for them
+	pub max_bids: BoundedVec<(AccountId, Balance), BidderListLengthLimit>,
 }
 
 impl<AccountId, Balance, BidderListLengthLimit>
@@ -149,10 +172,11 @@ where
 {
 	/// Create a new empty bidders list
 	pub fn new() -> Self {
-		Self { list: BoundedVec::default() }
+		Self { list: BoundedVec::default(), max_bids: BoundedVec::default() }
 	}
 
 	/// Insert a new bid to the list
+	/// Returns the dropped bid with the amount that was held for it
 	pub fn insert_new_bid(
 		&mut self,
 		account_id: AccountId,
@@ -160,7 +184,10 @@ where
 	) -> Option<(AccountId, Balance)> {
 		// If list is at max capacity, remove lowest bid
 		if self.list.is_full() {
-			let removed_bid = self.list.remove(0);
+			let mut removed_bid = self.list.remove(0);
+			if let Some(max) = self.remove_max_bid(&removed_bid.0) {
+				removed_bid.1 = max;
+			}
 			self.list.try_push((account_id, value)).expect("Cannot happen.");
 			// return removed bid
 			Some(removed_bid)
@@ -198,14 +225,100 @@ where
 		}
 	}
 
-	/// Remove a specific bid from `account_id` from list if it exists
+	/// Remove a specific bid from `account_id` from list if it exists, with its maximum amount
 	pub fn remove_bid(&mut self, account_id: &AccountId) -> Option<(AccountId, Balance)> {
+		self.remove_max_bid(account_id);
 		match self.list.iter().position(|x| x.0 == *account_id) {
 			Some(index) => Some(self.list.remove(index)),
 			None => None,
 		}
 	}
 
+	/// Return the maximum amount of `account_id` if it exists
+	pub fn find_max_bid(&self, account_id: &AccountId) -> Option<&Balance> {
+		self.max_bids.iter().find(|x| x.0 == *account_id).map(|x| &x.1)
+	}
+
+	/// Set the maximum amount of `account_id`, the account must already have a bid in the list
+	pub fn set_max_bid(&mut self, account_id: AccountId, max: Balance) {
+		self.remove_max_bid(&account_id);
+		self.max_bids.try_push((account_id, max)).expect("Cannot happen.");
+	}
+
+	/// Remove the maximum amount of `account_id` if it exists
+	pub fn remove_max_bid(&mut self, account_id: &AccountId) -> Option<Balance> {
+		match self.max_bids.iter().position(|x| x.0 == *account_id) {
+			Some(index) => Some(self.max_bids.remove(index).1),
+			None => None,
+		}
+	}
+
+	/// Return the amount held for a bid: its maximum amount if any, its visible amount otherwise
+	pub fn held_amount(&self, bid: &(AccountId, Balance)) -> Balance {
+		self.find_max_bid(&bid.0).unwrap_or(&bid.1).clone()
+	}
+
+	/// Raise the visible bids of the bidders with a maximum amount. The bid with the highest
+	/// maximum stands at the second highest maximum plus `increment` (capped by its own maximum),
+	/// the other bidders with a maximum are raised to it.
+	/// Returns the bids that were raised.
+	pub fn resolve_max_bids(&mut self, increment: Balance) -> Vec<(AccountId, Balance)>
+	where
+		Balance: sp_runtime::traits::Saturating + Copy,
+	{
+		if self.max_bids.is_empty() {
+			return Vec::new()
+		}
+
+		// The earliest bid wins when two bids hold the same amount. `max_bids` is ordered by the
+		// time the maximums were set and a plain bid can only match a maximum set before it.
+		let max_index = |account: &AccountId| self.max_bids.iter().position(|x| x.0 == *account);
+		let mut top_index = 0;
+		for (index, bid) in self.list.iter().enumerate() {
+			let top = &self.list[top_index];
+			let (held, top_held) = (self.held_amount(bid), self.held_amount(top));
+			let is_earlier = match (max_index(&bid.0), max_index(&top.0)) {
+				(Some(x), Some(y)) => x < y,
+				(Some(_), None) => true,
+				_ => false,
+			};
+			if held > top_held || (held == top_held && is_earlier) {
+				top_index = index;
+			}
+		}
+
+		let mut bids = self.list.to_vec();
+		let top = bids.remove(top_index);
+		let mut raised = Vec::new();
+		let mut second: Option<Balance> = None;
+		for bid in bids.iter_mut() {
+			let held = self.held_amount(bid);
+			if second.map_or(true, |x| held > x) {
+				second = Some(held);
+			}
+			if held > bid.1 {
+				bid.1 = held;
+				raised.push(bid.clone());
+			}
+		}
+
+		let mut top_amount = top.1;
+		if let (Some(max), Some(second)) = (self.find_max_bid(&top.0), second) {
+			let target = second.saturating_add(increment);
+			let target = if target > *max { *max } else { target };
+			if target > top_amount {
+				top_amount = target;
+				raised.push((top.0.clone(), top_amount));
+			}
+		}
+
+		bids.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(sp_std::cmp::Ordering::Equal));
+		bids.push((top.0, top_amount));
+		self.list = BoundedVec::try_from(bids).expect("Cannot happen.");
+
+		raised
+	}
+
 	/// Return the bid of `account_id` if it exists
 	pub fn find_bid(&self, account_id: &AccountId) -> Option<&(AccountId, Balance)> {
 		// this is not optimal since we traverse the entire link, but we cannot use binary search
@@ -220,7 +333,7 @@ where
 
 	pub fn from_raw(raw: Vec<(AccountId, Balance)>) -> Self {
 		let list = BoundedVec::try_from(raw).expect("It will never happen.");
-		Self { list }
+		Self { list, max_bids: BoundedVec::default() }
 	}
 
 	/// Benchmark bulk insert bids
@@ -24,6 +24,10 @@ pub trait WeightInfo {
 	fn remove_bid(s: u32) -> Weight;
 	fn buy_it_now(_s: u32) -> Weight;
 	fn claim() -> Weight;
+	fn cancel_auction_in_progress(s: u32) -> Weight;
+	fn set_bid_funds_mode() -> Weight;
+	fn add_max_bid(s: u32) -> Weight;
+	fn settle_auction(s: u32) -> Weight;
 }
 
 /// Weight functions for `ternoa_auctions`.
@@ -49,4 +53,16 @@ impl WeightInfo for () {
 	fn claim() -> Weight {
 		Weight::from_parts(10_000_000, 0)
 	}
+	fn cancel_auction_in_progress(_s: u32) -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
+	fn set_bid_funds_mode() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
+	fn add_max_bid(_s: u32) -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
+	fn settle_auction(_s: u32) -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
 }
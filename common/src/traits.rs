@@ -16,17 +16,22 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use frame_support::{dispatch::DispatchResult, traits::Get, BoundedVec};
+use frame_support::{
+	dispatch::{DispatchError, DispatchResult},
+	traits::Get,
+	BoundedVec,
+};
 use primitives::{
 	marketplace::{MarketplaceData, MarketplaceId},
-	nfts::{CollectionId, NFTData, NFTId, NFTState},
-	tee::ClusterId,
+	nfts::{Collection, CollectionId, NFTData, NFTId, NFTState, NFTUser, UsageScope},
+	tee::{ClusterId, SlotId},
 };
 use sp_runtime::Permill;
 use sp_std::fmt::Debug;
 
 pub trait NFTExt {
 	type AccountId: Clone + PartialEq + Debug;
+	type BlockNumber: Clone + PartialEq + Debug + sp_std::cmp::PartialOrd;
 	type NFTOffchainDataLimit: Get<u32>;
 	type CollectionSizeLimit: Get<u32>;
 	type CollectionOffchainDataLimit: Get<u32>;
@@ -61,6 +66,17 @@ pub trait NFTExt {
 		is_soulbound: bool,
 	) -> Result<NFTId, DispatchResult>;
 
+	/// Mint an NFT of a creator for another owner, who pays the mint fee. The NFT is added to
+	/// the collection of the creator if one is specified.
+	fn mint_nft(
+		owner: Self::AccountId,
+		creator: Self::AccountId,
+		offchain_data: BoundedVec<u8, Self::NFTOffchainDataLimit>,
+		royalty: Permill,
+		collection_id: Option<CollectionId>,
+		is_soulbound: bool,
+	) -> Result<NFTId, DispatchError>;
+
 	fn mutate_nft<
 		R,
 		E,
@@ -71,6 +87,25 @@ pub trait NFTExt {
 	) -> Result<R, E>;
 
 	fn exists(id: NFTId) -> bool;
+
+	/// Returns a collection corresponding to its id.
+	fn get_collection(
+		id: CollectionId,
+	) -> Option<
+		Collection<Self::AccountId, Self::CollectionOffchainDataLimit, Self::CollectionSizeLimit>,
+	>;
+
+	/// Close a collection so that no NFT can be added to it anymore.
+	fn close_collection(id: CollectionId) -> DispatchResult;
+
+	/// Set or remove the user allowed to use an NFT without owning it.
+	fn set_nft_user(
+		id: NFTId,
+		user: Option<NFTUser<Self::AccountId, Self::BlockNumber>>,
+	) -> DispatchResult;
+
+	/// Returns the user of an NFT if its usage right has not expired.
+	fn current_nft_user(id: NFTId) -> Option<Self::AccountId>;
 }
 
 pub trait MarketplaceExt {
@@ -112,6 +147,12 @@ pub trait TEEExt {
 	/// Returns operator address and cluster id for a given enclave address
 	fn ensure_enclave(account: Self::AccountId) -> Option<(ClusterId, Self::AccountId)>;
 
+	/// Returns false if the enclave of the operator stopped sending heartbeats
+	fn is_enclave_active(operator_address: &Self::AccountId) -> bool;
+
+	/// Returns the slot served by the enclave of an operator in its cluster
+	fn enclave_slot(operator_address: &Self::AccountId) -> Option<SlotId>;
+
 	/// Register and assign an enclave
 	fn register_and_assign_enclave(
 		operator_address: Self::AccountId,
@@ -122,3 +163,35 @@ pub trait TEEExt {
 	fn fill_unregistration_list(address: Self::AccountId, number: u8) -> DispatchResult;
 
 }
+
+/// Notified when the enclave serving a slot of a cluster changes. The notifications are not
+/// weighed by their callers so implementations must only do a constant amount of work.
+pub trait EnclaveSlotHandler {
+	/// The enclave serving the slot left the cluster.
+	fn on_slot_vacated(cluster_id: ClusterId, slot_id: SlotId);
+
+	/// The enclave serving the `from` slot now serves the `to` slot of the same cluster.
+	fn on_slot_moved(cluster_id: ClusterId, from: SlotId, to: SlotId);
+
+	/// An enclave got assigned to the slot.
+	fn on_slot_filled(cluster_id: ClusterId, slot_id: SlotId);
+}
+
+impl EnclaveSlotHandler for () {
+	fn on_slot_vacated(_cluster_id: ClusterId, _slot_id: SlotId) {}
+
+	fn on_slot_moved(_cluster_id: ClusterId, _from: SlotId, _to: SlotId) {}
+
+	fn on_slot_filled(_cluster_id: ClusterId, _slot_id: SlotId) {}
+}
+
+pub trait RentExt<AccountId> {
+	/// Returns the usage scope granted to an account that uses a rented NFT, if any.
+	fn usage_scope(nft_id: NFTId, account: &AccountId) -> Option<UsageScope>;
+}
+
+impl<AccountId> RentExt<AccountId> for () {
+	fn usage_scope(_nft_id: NFTId, _account: &AccountId) -> Option<UsageScope> {
+		None
+	}
+}
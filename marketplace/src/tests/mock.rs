@@ -245,6 +245,16 @@ impl pallet_staking::Config for Test {
 parameter_types! {
 	pub const ClusterSize: u32 = 2;
 	pub const MaxUriLen: u32 = 12;
+	pub const MaxQuoteLen: u32 = 256;
+	pub const SlashDeferDuration: u32 = 10;
+	pub const MaxSlashesPerBlock: u32 = 2;
+	pub const MaxMetrics: u32 = 10;
+	pub const MaxMetricNameLen: u32 = 32;
+	pub const InitialReportQuorum: u32 = 1;
+	pub const OutlierThreshold: u8 = 20;
+	pub const MaxNominators: u32 = 4;
+	pub const MinNominationBond: Balance = 10;
+	pub const MaxMissedHeartbeats: u32 = 3;
 	pub const ListSizeLimit: u32 = 10;
 	pub const TeeBondingDuration: u32 = 10;
 	pub const InitialStakingAmount: Balance = 20;
@@ -264,6 +274,19 @@ impl ternoa_tee::Config for Test {
 	type InitalDailyRewardPool = InitalDailyRewardPool;
 	type PalletId = TeePalletId;
 	type TeeHistoryDepth = TeeHistoryDepth;
+	type MaxQuoteLen = MaxQuoteLen;
+	type AttestationVerifier = ();
+	type Slash = ();
+	type SlashDeferDuration = SlashDeferDuration;
+	type MaxSlashesPerBlock = MaxSlashesPerBlock;
+	type MaxMetrics = MaxMetrics;
+	type MaxMetricNameLen = MaxMetricNameLen;
+	type InitialReportQuorum = InitialReportQuorum;
+	type OutlierThreshold = OutlierThreshold;
+	type MaxNominators = MaxNominators;
+	type MinNominationBond = MinNominationBond;
+	type MaxMissedHeartbeats = MaxMissedHeartbeats;
+	type EnclaveSlotHandler = ();
 }
 
 parameter_types! {
@@ -274,6 +297,7 @@ parameter_types! {
 	pub const CollectionSizeLimit: u32 = 10;
 	pub const InitialSecretMintFee: Balance = SECRET_NFT_MINT_FEE;
 	pub const ShardsNumber: u32 = 5;
+	pub const MaxResharesPerBlock: u32 = 10;
 	pub const InitialCapsuleMintFee: Balance = CAPSULE_MINT_FEE;
 	// Marketplace parameter types
 	pub const MarketplaceInitialMintFee: Balance = MARKETPLACE_MINT_FEE;
@@ -293,7 +317,9 @@ impl ternoa_nft::Config for Test {
 	type CollectionSizeLimit = CollectionSizeLimit;
 	type InitialSecretMintFee = InitialSecretMintFee;
 	type ShardsNumber = ShardsNumber;
+	type MaxResharesPerBlock = MaxResharesPerBlock;
 	type TEEExt = TEE;
+	type RentExt = ();
 	type InitialCapsuleMintFee = InitialCapsuleMintFee;
 }
 
@@ -29,7 +29,7 @@ pub mod weights;
 pub use pallet::*;
 
 use frame_support::{
-	dispatch::DispatchResult,
+	dispatch::{DispatchError, DispatchResult},
 	ensure,
 	traits::{
 		Currency, ExistenceRequirement::KeepAlive, Get, OnUnbalanced, StorageVersion,
@@ -39,14 +39,17 @@ use frame_support::{
 };
 use frame_system::pallet_prelude::*;
 use primitives::{
-	nfts::{Collection, CollectionId, NFTData, NFTId, NFTState},
-	tee::ClusterId,
+	nfts::{Collection, CollectionId, NFTData, NFTId, NFTState, NFTUser, ShardKind, ShardSetId},
+	tee::{ClusterId, SlotId},
 	U8BoundedVec,
 };
 use sp_arithmetic::per_things::Permill;
 use sp_runtime::traits::{CheckedSub, StaticLookup};
 use sp_std::{prelude::*, vec};
-use ternoa_common::{traits, traits::TEEExt};
+use ternoa_common::{
+	traits,
+	traits::{EnclaveSlotHandler, RentExt, TEEExt},
+};
 
 pub use weights::WeightInfo;
 
@@ -84,6 +87,9 @@ pub mod pallet {
 		/// Link to the TEE pallet.
 		type TEEExt: TEEExt<AccountId = Self::AccountId>;
 
+		/// Link to the rent pallet.
+		type RentExt: RentExt<Self::AccountId>;
+
 		// Constants
 		/// Default fee for minting NFTs.
 		#[pallet::constant]
@@ -112,6 +118,10 @@ pub mod pallet {
 		/// Default fee for minting secret NFTs.
 		#[pallet::constant]
 		type InitialCapsuleMintFee: Get<BalanceOf<Self>>;
+
+		/// Maximum number of shards lost by leaving enclaves that are processed in a block.
+		#[pallet::constant]
+		type MaxResharesPerBlock: Get<u32>;
 	}
 
 	/// How much does it cost to mint a NFT (extra fee on top of the tx fees).
@@ -158,6 +168,12 @@ pub mod pallet {
 	pub type DelegatedNFTs<T: Config> =
 		StorageMap<_, Blake2_128Concat, NFTId, T::AccountId, OptionQuery>;
 
+	/// Host a map of NFTs and the account allowed to use them until a given block.
+	#[pallet::storage]
+	#[pallet::getter(fn nft_users)]
+	pub type NFTUsers<T: Config> =
+		StorageMap<_, Blake2_128Concat, NFTId, NFTUser<T::AccountId, T::BlockNumber>, OptionQuery>;
+
 	/// How much does it cost to mint a secret NFT (extra fee on top of the tx fees and basic NFT
 	/// fee).
 	#[pallet::storage]
@@ -206,6 +222,69 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	/// Host the shard set of each cluster slot. Shards added before the shard sets existed are
+	/// not tracked and are not re-synced when their enclave leaves.
+	#[pallet::storage]
+	#[pallet::getter(fn slot_shard_sets)]
+	pub type SlotShardSets<T: Config> =
+		StorageMap<_, Blake2_128Concat, (ClusterId, SlotId), ShardSetId, OptionQuery>;
+
+	/// Host the next shard set id.
+	#[pallet::storage]
+	#[pallet::getter(fn next_shard_set_id)]
+	pub type NextShardSetId<T: Config> = StorageValue<_, ShardSetId, ValueQuery>;
+
+	/// Host the shards of secret NFTs and capsules held by the enclave of each shard set.
+	#[pallet::storage]
+	#[pallet::getter(fn shard_holders)]
+	pub type ShardHolders<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		ShardSetId,
+		Blake2_128Concat,
+		(ShardKind, NFTId),
+		(),
+		OptionQuery,
+	>;
+
+	/// Host the shards lost by an enclave leaving its slot, to be re-synced by the enclave
+	/// serving the shard set.
+	#[pallet::storage]
+	#[pallet::getter(fn pending_reshares)]
+	pub type PendingReshares<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		ShardSetId,
+		Blake2_128Concat,
+		(ShardKind, NFTId),
+		(),
+		OptionQuery,
+	>;
+
+	/// Host the shard sets left by enclaves and the shard set their shards are moved to. The
+	/// shards are moved by `on_initialize`.
+	#[pallet::storage]
+	#[pallet::getter(fn vacated_shard_sets)]
+	pub type VacatedShardSets<T: Config> =
+		StorageMap<_, Blake2_128Concat, ShardSetId, ShardSetId, OptionQuery>;
+
+	/// Host the shard sets holding a shard of secret NFTs and capsules.
+	#[pallet::storage]
+	#[pallet::getter(fn nft_shard_sets)]
+	pub type NFTShardSets<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		(ShardKind, NFTId),
+		BoundedVec<ShardSetId, T::ShardsNumber>,
+		ValueQuery,
+	>;
+
+	/// Host the number of lost shards of secret NFTs and capsules that are not re-synced yet.
+	#[pallet::storage]
+	#[pallet::getter(fn reshares_count)]
+	pub type ResharesCount<T: Config> =
+		StorageMap<_, Blake2_128Concat, (ShardKind, NFTId), u32, ValueQuery>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -225,6 +304,8 @@ pub mod pallet {
 		NFTTransferred { nft_id: NFTId, sender: T::AccountId, recipient: T::AccountId },
 		/// An NFT was delegated to someone else.
 		NFTDelegated { nft_id: NFTId, recipient: Option<T::AccountId> },
+		/// The user of an NFT was set or removed.
+		NFTUserSet { nft_id: NFTId, user: Option<T::AccountId>, expires: Option<T::BlockNumber> },
 		/// Royalty has been changed for an NFT.
 		NFTRoyaltySet { nft_id: NFTId, royalty: Permill },
 		/// NFT mint fee changed.
@@ -277,6 +358,15 @@ pub mod pallet {
 			collection_id: CollectionId,
 			offchain_data: U8BoundedVec<T::CollectionOffchainDataLimit>,
 		},
+		/// The enclave assigned to a cluster slot has to re-sync the shards pending in its shard set
+		ReshareRequested { cluster_id: ClusterId, slot_id: SlotId, shard_set_id: ShardSetId },
+		/// Shards lost by a leaving enclave are pending in the shard set
+		ShardsLost {
+			shard_set_id: ShardSetId,
+			shards: BoundedVec<(ShardKind, NFTId), T::MaxResharesPerBlock>,
+		},
+		/// A lost shard was re-synced by an enclave
+		ShardReshared { nft_id: NFTId, kind: ShardKind, enclave: T::AccountId },
 	}
 
 	#[pallet::error]
@@ -344,6 +434,12 @@ pub mod pallet {
 		CannotAddMoreNFTsToCollection,
 		/// Operation is not permitted because caller is not a registered TEE enclave.
 		NotARegisteredEnclave,
+		/// Operation is not permitted because the TEE enclave stopped sending heartbeats.
+		EnclaveIsInactive,
+		/// Operation is not allowed because the NFT only waits for lost shards to be re-synced.
+		NotAPendingReshare,
+		/// The NFT has shards in too many shard sets.
+		TooManyShardSets,
 		/// Operation is not permitted because NFT is not a secret.
 		NFTIsNotSecret,
 		/// Operation is not permitted because NFT secret is already synced.
@@ -431,10 +527,45 @@ pub mod pallet {
 		CannotChangeKeyForSyncingCapsules,
 		/// Operation is not permitted because the NFT is in transmission
 		CannotChangeKeyForNFTsInTransmission,
+		/// Operation is not permitted by the usage scope of the rent contract
+		NotAllowedByUsageScope,
 	}
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Weight: see `begin_block`
+		fn on_initialize(_now: T::BlockNumber) -> Weight {
+			let mut read = 1u64;
+			let mut write = 0u64;
+			let mut budget = T::MaxResharesPerBlock::get();
+
+			// Move the shards of vacated shard sets to the shard set of their slot
+			while budget > 0 {
+				let (source, target) = match VacatedShardSets::<T>::iter().next() {
+					Some(x) => x,
+					None => break,
+				};
+				let (target, hops) = Self::resolve_shard_set(target);
+				read += 1 + hops;
+
+				let (processed, lost, is_empty) = Self::move_vacated_shards(source, target, budget);
+				budget = budget.saturating_sub(processed.max(1));
+				read += 5 * processed as u64 + 2;
+				write += 6 * processed as u64;
+
+				if is_empty {
+					VacatedShardSets::<T>::remove(source);
+					write += 1;
+				}
+				if !lost.is_empty() {
+					let shards = BoundedVec::truncate_from(lost);
+					Self::deposit_event(Event::ShardsLost { shard_set_id: target, shards });
+				}
+			}
+
+			T::DbWeight::get().reads_writes(read, write)
+		}
+	}
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
@@ -467,7 +598,14 @@ pub mod pallet {
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 
-			Self::create_nft_helper(who, offchain_data, royalty, collection_id, is_soulbound)?;
+			Self::create_nft_helper(
+				who.clone(),
+				who,
+				offchain_data,
+				royalty,
+				collection_id,
+				is_soulbound,
+			)?;
 
 			Ok(().into())
 		}
@@ -530,6 +668,7 @@ pub mod pallet {
 				if nft.state.is_syncing_secret {
 					SecretNftsShardsCount::<T>::remove(nft_id);
 				}
+				Self::clear_shard_sets((ShardKind::Secret, nft_id));
 			}
 
 			// Check for capsule to remove capsule offchain data and capsule shards count.
@@ -538,6 +677,7 @@ pub mod pallet {
 				if nft.state.is_syncing_capsule {
 					CapsulesShardsCount::<T>::remove(nft_id);
 				}
+				Self::clear_shard_sets((ShardKind::Capsule, nft_id));
 			}
 
 			// Execute
@@ -930,14 +1070,14 @@ pub mod pallet {
 			);
 
 			// Create NFT
-			Self::create_nft_helper(
+			let nft_id = Self::create_nft_helper(
+				who.clone(),
 				who.clone(),
 				offchain_data,
 				royalty,
 				collection_id,
 				is_soulbound,
 			)?;
-			let nft_id = NextNFTId::<T>::get() - 1;
 
 			// Add a secret to the NFT
 			Self::add_secret_helper(who, nft_id, secret_offchain_data)?;
@@ -954,6 +1094,18 @@ pub mod pallet {
 
 			let (cluster_id, operator_address) =
 				T::TEEExt::ensure_enclave(who.clone()).ok_or(Error::<T>::NotARegisteredEnclave)?;
+			ensure!(T::TEEExt::is_enclave_active(&operator_address), Error::<T>::EnclaveIsInactive);
+
+			// Re-sync of a shard lost by the previous enclave of the slot
+			let shard = (ShardKind::Secret, nft_id);
+			let slot_id = T::TEEExt::enclave_slot(&operator_address);
+			let shard_set_id = slot_id.and_then(|x| SlotShardSets::<T>::get((cluster_id, x)));
+			if let Some(shard_set_id) = shard_set_id {
+				if PendingReshares::<T>::contains_key(shard_set_id, shard) {
+					Self::reshare(who, shard, shard_set_id)?;
+					return Ok(Pays::No.into())
+				}
+			}
 
 			let mut has_finished_sync = false;
 
@@ -983,6 +1135,13 @@ pub mod pallet {
 							*maybe_shards = None;
 						}
 					} else {
+						// A new sync replaces the shards held so far.
+						ensure!(
+							ResharesCount::<T>::get(shard) == 0,
+							Error::<T>::NotAPendingReshare
+						);
+						Self::clear_shard_sets(shard);
+
 						let mut shards: BoundedVec<(ClusterId, T::AccountId), T::ShardsNumber> =
 							BoundedVec::default();
 						shards
@@ -1005,6 +1164,10 @@ pub mod pallet {
 				Ok(().into())
 			})?;
 
+			if let Some(slot_id) = slot_id {
+				Self::add_shard_holder(shard, cluster_id, slot_id)?;
+			}
+
 			let event = Event::ShardAdded { nft_id, enclave: who };
 			Self::deposit_event(event);
 
@@ -1083,14 +1246,14 @@ pub mod pallet {
 			);
 
 			// Create NFT
-			Self::create_nft_helper(
+			let nft_id = Self::create_nft_helper(
+				who.clone(),
 				who.clone(),
 				offchain_data,
 				royalty,
 				collection_id,
 				is_soulbound,
 			)?;
-			let nft_id = NextNFTId::<T>::get() - 1;
 
 			// Add a secret to the NFT
 			Self::convert_to_capsule_helper(who, nft_id, capsule_offchain_data)?;
@@ -1146,12 +1309,25 @@ pub mod pallet {
 
 			Nfts::<T>::try_mutate(nft_id, |maybe_nft| -> DispatchResult {
 				let nft = maybe_nft.as_mut().ok_or(Error::<T>::NFTNotFound)?;
+				let is_owner = nft.owner == who;
 
 				// Checks
-				ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
+				if !is_owner {
+					// Only the current user of the NFT can act on behalf of the owner.
+					ensure!(Self::has_access(nft_id, &who), Error::<T>::NotTheNFTOwner);
+					let scope =
+						T::RentExt::usage_scope(nft_id, &who).ok_or(Error::<T>::NotTheNFTOwner)?;
+					ensure!(
+						scope.can_set_capsule_offchain_data(),
+						Error::<T>::NotAllowedByUsageScope
+					);
+				}
 				ensure!(nft.state.is_capsule, Error::<T>::NFTIsNotCapsule);
 				ensure!(!nft.state.is_listed, Error::<T>::CannotSetOffchainDataForListedNFTs);
-				ensure!(!nft.state.is_rented, Error::<T>::CannotSetOffchainDataForRentedNFTs);
+				ensure!(
+					!is_owner || !nft.state.is_rented,
+					Error::<T>::CannotSetOffchainDataForRentedNFTs
+				);
 				ensure!(!nft.state.is_delegated, Error::<T>::CannotSetOffchainDataForDelegatedNFTs);
 				ensure!(
 					!nft.state.is_syncing_secret,
@@ -1200,6 +1376,18 @@ pub mod pallet {
 
 			let (cluster_id, operator_address) =
 				T::TEEExt::ensure_enclave(who.clone()).ok_or(Error::<T>::NotARegisteredEnclave)?;
+			ensure!(T::TEEExt::is_enclave_active(&operator_address), Error::<T>::EnclaveIsInactive);
+
+			// Re-sync of a shard lost by the previous enclave of the slot
+			let shard = (ShardKind::Capsule, nft_id);
+			let slot_id = T::TEEExt::enclave_slot(&operator_address);
+			let shard_set_id = slot_id.and_then(|x| SlotShardSets::<T>::get((cluster_id, x)));
+			if let Some(shard_set_id) = shard_set_id {
+				if PendingReshares::<T>::contains_key(shard_set_id, shard) {
+					Self::reshare(who, shard, shard_set_id)?;
+					return Ok(Pays::No.into())
+				}
+			}
 
 			let mut has_finished_sync = false;
 
@@ -1229,6 +1417,13 @@ pub mod pallet {
 							*maybe_shards = None;
 						}
 					} else {
+						// A new sync replaces the shards held so far.
+						ensure!(
+							ResharesCount::<T>::get(shard) == 0,
+							Error::<T>::NotAPendingReshare
+						);
+						Self::clear_shard_sets(shard);
+
 						let mut shards: BoundedVec<(ClusterId, T::AccountId), T::ShardsNumber> =
 							BoundedVec::default();
 						shards
@@ -1251,6 +1446,10 @@ pub mod pallet {
 				Ok(().into())
 			})?;
 
+			if let Some(slot_id) = slot_id {
+				Self::add_shard_holder(shard, cluster_id, slot_id)?;
+			}
+
 			let event = Event::CapsuleShardAdded { nft_id, enclave: who };
 			Self::deposit_event(event);
 
@@ -1273,12 +1472,22 @@ pub mod pallet {
 
 			Nfts::<T>::try_mutate(nft_id, |maybe_nft| -> DispatchResult {
 				let nft = maybe_nft.as_mut().ok_or(Error::<T>::NFTNotFound)?;
+				let is_owner = nft.owner == who;
 
 				// Checks
-				ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
+				if !is_owner {
+					// Only the current user of the NFT can act on behalf of the owner.
+					ensure!(Self::has_access(nft_id, &who), Error::<T>::NotTheNFTOwner);
+					let scope =
+						T::RentExt::usage_scope(nft_id, &who).ok_or(Error::<T>::NotTheNFTOwner)?;
+					ensure!(scope.can_update_capsule_key(), Error::<T>::NotAllowedByUsageScope);
+				}
 				ensure!(nft.state.is_capsule, Error::<T>::NFTIsNotCapsule);
 				ensure!(!nft.state.is_listed, Error::<T>::CannotChangeKeyForListedNFTs);
-				ensure!(!nft.state.is_rented, Error::<T>::CannotChangeKeyForRentedNFTs);
+				ensure!(
+					!is_owner || !nft.state.is_rented,
+					Error::<T>::CannotChangeKeyForRentedNFTs
+				);
 				ensure!(!nft.state.is_delegated, Error::<T>::CannotChangeKeyForDelegatedNFTs);
 				ensure!(!nft.state.is_syncing_secret, Error::<T>::CannotChangeKeyForSyncingNFTs);
 				ensure!(
@@ -1332,6 +1541,7 @@ pub mod pallet {
 
 impl<T: Config> traits::NFTExt for Pallet<T> {
 	type AccountId = T::AccountId;
+	type BlockNumber = T::BlockNumber;
 	type NFTOffchainDataLimit = T::NFTOffchainDataLimit;
 	type CollectionOffchainDataLimit = T::CollectionOffchainDataLimit;
 	type CollectionSizeLimit = T::CollectionSizeLimit;
@@ -1410,12 +1620,24 @@ impl<T: Config> traits::NFTExt for Pallet<T> {
 			nft_state,
 			collection_id,
 		);
+
 		let nft_id = Self::get_next_nft_id();
 		Nfts::<T>::insert(nft_id, nft);
 
 		Ok(nft_id)
 	}
 
+	fn mint_nft(
+		owner: Self::AccountId,
+		creator: Self::AccountId,
+		offchain_data: BoundedVec<u8, Self::NFTOffchainDataLimit>,
+		royalty: Permill,
+		collection_id: Option<CollectionId>,
+		is_soulbound: bool,
+	) -> Result<NFTId, DispatchError> {
+		Self::create_nft_helper(owner, creator, offchain_data, royalty, collection_id, is_soulbound)
+	}
+
 	fn mutate_nft<
 		R,
 		E,
@@ -1430,6 +1652,84 @@ impl<T: Config> traits::NFTExt for Pallet<T> {
 	fn exists(id: NFTId) -> bool {
 		Nfts::<T>::contains_key(id)
 	}
+
+	fn get_collection(
+		id: CollectionId,
+	) -> Option<
+		Collection<Self::AccountId, Self::CollectionOffchainDataLimit, Self::CollectionSizeLimit>,
+	> {
+		Collections::<T>::get(id)
+	}
+
+	fn close_collection(collection_id: CollectionId) -> DispatchResult {
+		Collections::<T>::try_mutate(collection_id, |x| -> DispatchResult {
+			let collection = x.as_mut().ok_or(Error::<T>::CollectionNotFound)?;
+			collection.is_closed = true;
+
+			Ok(())
+		})?;
+
+		Self::deposit_event(Event::CollectionClosed { collection_id });
+
+		Ok(())
+	}
+
+	fn set_nft_user(
+		nft_id: NFTId,
+		user: Option<NFTUser<Self::AccountId, Self::BlockNumber>>,
+	) -> DispatchResult {
+		let (user, expires) = match user {
+			Some(data) => {
+				ensure!(Nfts::<T>::contains_key(nft_id), Error::<T>::NFTNotFound);
+				NFTUsers::<T>::insert(nft_id, data.clone());
+				(Some(data.user), Some(data.expires))
+			},
+			None => {
+				NFTUsers::<T>::remove(nft_id);
+				(None, None)
+			},
+		};
+
+		Self::deposit_event(Event::NFTUserSet { nft_id, user, expires });
+
+		Ok(())
+	}
+
+	fn current_nft_user(nft_id: NFTId) -> Option<Self::AccountId> {
+		let now = frame_system::Pallet::<T>::block_number();
+		NFTUsers::<T>::get(nft_id).filter(|x| x.is_active(&now)).map(|x| x.user)
+	}
+}
+
+impl<T: Config> EnclaveSlotHandler for Pallet<T> {
+	fn on_slot_vacated(cluster_id: ClusterId, slot_id: SlotId) {
+		// The shards of the leaving enclave are moved to a new shard set of the slot by
+		// `on_initialize`.
+		if let Some(shard_set_id) = SlotShardSets::<T>::get((cluster_id, slot_id)) {
+			let new_shard_set_id = Self::get_next_shard_set_id();
+			SlotShardSets::<T>::insert((cluster_id, slot_id), new_shard_set_id);
+			VacatedShardSets::<T>::insert(shard_set_id, new_shard_set_id);
+		}
+	}
+
+	fn on_slot_moved(cluster_id: ClusterId, from: SlotId, to: SlotId) {
+		// The shards of the enclave follow it, the lost ones stay with the empty slot.
+		let moved = SlotShardSets::<T>::take((cluster_id, from));
+		let left = SlotShardSets::<T>::take((cluster_id, to));
+		if let Some(shard_set_id) = moved {
+			SlotShardSets::<T>::insert((cluster_id, to), shard_set_id);
+		}
+		if let Some(shard_set_id) = left {
+			SlotShardSets::<T>::insert((cluster_id, from), shard_set_id);
+		}
+	}
+
+	fn on_slot_filled(cluster_id: ClusterId, slot_id: SlotId) {
+		// A slot only has a shard set when it was served by a previous enclave.
+		if let Some(shard_set_id) = SlotShardSets::<T>::get((cluster_id, slot_id)) {
+			Self::deposit_event(Event::ReshareRequested { cluster_id, slot_id, shard_set_id });
+		}
+	}
 }
 
 impl<T: Config> Pallet<T> {
@@ -1464,6 +1764,15 @@ impl<T: Config> Pallet<T> {
 		}
 	}
 
+	/// Returns true if the account can access the secret or capsule of an NFT: the current user
+	/// while the NFT is in use, the owner otherwise.
+	pub fn has_access(nft_id: NFTId, account: &T::AccountId) -> bool {
+		match <Self as traits::NFTExt>::current_nft_user(nft_id) {
+			Some(user) => user == *account,
+			None => Nfts::<T>::get(nft_id).map_or(false, |nft| nft.owner == *account),
+		}
+	}
+
 	pub fn add_secret_helper(
 		who: T::AccountId,
 		nft_id: NFTId,
@@ -1504,11 +1813,12 @@ impl<T: Config> Pallet<T> {
 
 	pub fn create_nft_helper(
 		who: T::AccountId,
+		creator: T::AccountId,
 		offchain_data: U8BoundedVec<T::NFTOffchainDataLimit>,
 		royalty: Permill,
 		collection_id: Option<CollectionId>,
 		is_soulbound: bool,
-	) -> DispatchResult {
+	) -> Result<NFTId, DispatchError> {
 		let mut next_nft_id = None;
 
 		// Checks
@@ -1518,14 +1828,14 @@ impl<T: Config> Pallet<T> {
 		let imbalance = T::Currency::withdraw(&who, mint_fee, reason, KeepAlive)?;
 		T::FeesCollector::on_unbalanced(imbalance);
 
-		// Throws an error if specified collection does not exist, signer is not owner,
+		// Throws an error if specified collection does not exist, creator is not owner,
 		// collection is close, collection has reached limit.
 		if let Some(collection_id) = &collection_id {
 			Collections::<T>::try_mutate(collection_id, |x| -> DispatchResult {
 				let collection = x.as_mut().ok_or(Error::<T>::CollectionNotFound)?;
 				let limit =
 					collection.limit.unwrap_or_else(|| T::CollectionSizeLimit::get()) as usize;
-				ensure!(collection.owner == who, Error::<T>::NotTheCollectionOwner);
+				ensure!(collection.owner == creator, Error::<T>::NotTheCollectionOwner);
 				ensure!(!collection.is_closed, Error::<T>::CollectionIsClosed);
 				ensure!(collection.nfts.len() < limit, Error::<T>::CollectionHasReachedLimit);
 
@@ -1540,12 +1850,13 @@ impl<T: Config> Pallet<T> {
 		}
 
 		let nft_id = next_nft_id.unwrap_or_else(|| Self::get_next_nft_id());
-		let nft = NFTData::new_default(
+		let nft = NFTData::new(
 			who.clone(),
+			creator,
 			offchain_data.clone(),
 			royalty,
+			NFTState::new_default(is_soulbound),
 			collection_id.clone(),
-			is_soulbound,
 		);
 		// Execute
 		Nfts::<T>::insert(nft_id, nft);
@@ -1559,7 +1870,7 @@ impl<T: Config> Pallet<T> {
 			mint_fee,
 		};
 		Self::deposit_event(event);
-		Ok(().into())
+		Ok(nft_id)
 	}
 
 	pub fn convert_to_capsule_helper(
@@ -1598,4 +1909,168 @@ impl<T: Config> Pallet<T> {
 		Self::deposit_event(event);
 		Ok(().into())
 	}
+
+	/// Record that an enclave re-synced a shard lost by the previous enclave of its slot. The NFT
+	/// is synced again once all its lost shards are re-synced.
+	fn reshare(
+		enclave: T::AccountId,
+		shard: (ShardKind, NFTId),
+		shard_set_id: ShardSetId,
+	) -> DispatchResult {
+		let (kind, nft_id) = shard;
+		let has_finished_sync =
+			Nfts::<T>::try_mutate(nft_id, |maybe_nft| -> Result<bool, DispatchError> {
+				let nft = maybe_nft.as_mut().ok_or(Error::<T>::NFTNotFound)?;
+
+				PendingReshares::<T>::remove(shard_set_id, shard);
+				ShardHolders::<T>::insert(shard_set_id, shard, ());
+
+				let remaining = ResharesCount::<T>::get(shard).saturating_sub(1);
+				if remaining > 0 {
+					ResharesCount::<T>::insert(shard, remaining);
+					return Ok(false)
+				}
+				ResharesCount::<T>::remove(shard);
+
+				// Shards still missing from the initial sync keep the NFT syncing.
+				let has_finished_sync = match kind {
+					ShardKind::Secret => SecretNftsShardsCount::<T>::get(nft_id).is_none(),
+					ShardKind::Capsule => CapsulesShardsCount::<T>::get(nft_id).is_none(),
+				};
+				if has_finished_sync {
+					match kind {
+						ShardKind::Secret => nft.state.is_syncing_secret = false,
+						ShardKind::Capsule => nft.state.is_syncing_capsule = false,
+					}
+				}
+
+				Ok(has_finished_sync)
+			})?;
+
+		Self::deposit_event(Event::ShardReshared { nft_id, kind, enclave });
+
+		if has_finished_sync {
+			let event = match kind {
+				ShardKind::Secret => Event::SecretNFTSynced { nft_id },
+				ShardKind::Capsule => Event::CapsuleSynced { nft_id },
+			};
+			Self::deposit_event(event);
+		}
+
+		Ok(())
+	}
+
+	fn get_next_shard_set_id() -> ShardSetId {
+		let shard_set_id = NextShardSetId::<T>::get();
+		let next_id = shard_set_id
+			.checked_add(1)
+			.expect("If u32 is not enough we should crash for safety; qed.");
+		NextShardSetId::<T>::put(next_id);
+
+		shard_set_id
+	}
+
+	/// Record that the enclave of a cluster slot holds a shard.
+	fn add_shard_holder(
+		shard: (ShardKind, NFTId),
+		cluster_id: ClusterId,
+		slot_id: SlotId,
+	) -> DispatchResult {
+		let shard_set_id = match SlotShardSets::<T>::get((cluster_id, slot_id)) {
+			Some(x) => x,
+			None => {
+				let shard_set_id = Self::get_next_shard_set_id();
+				SlotShardSets::<T>::insert((cluster_id, slot_id), shard_set_id);
+				shard_set_id
+			},
+		};
+
+		NFTShardSets::<T>::try_mutate(shard, |shard_sets| -> DispatchResult {
+			if !shard_sets.contains(&shard_set_id) {
+				shard_sets.try_push(shard_set_id).map_err(|_| Error::<T>::TooManyShardSets)?;
+			}
+			Ok(())
+		})?;
+		ShardHolders::<T>::insert(shard_set_id, shard, ());
+
+		Ok(())
+	}
+
+	/// Forget every holder and pending re-sync of a shard.
+	fn clear_shard_sets(shard: (ShardKind, NFTId)) {
+		for shard_set_id in NFTShardSets::<T>::take(shard) {
+			ShardHolders::<T>::remove(shard_set_id, shard);
+			PendingReshares::<T>::remove(shard_set_id, shard);
+		}
+		ResharesCount::<T>::remove(shard);
+	}
+
+	/// Returns the shard set the shards of a vacated shard set end up in, along with the number
+	/// of vacated shard sets that were followed.
+	fn resolve_shard_set(mut shard_set_id: ShardSetId) -> (ShardSetId, u64) {
+		let mut hops = 0;
+		while let Some(next) = VacatedShardSets::<T>::get(shard_set_id) {
+			shard_set_id = next;
+			hops += 1;
+		}
+		(shard_set_id, hops)
+	}
+
+	/// Move up to `limit` shards of a vacated shard set to the pending re-syncs of `target`. The
+	/// NFTs of the shards held by the leaving enclave go back to syncing. Returns the number of
+	/// processed shards, the shards now pending in `target` and whether the vacated shard set is
+	/// empty.
+	fn move_vacated_shards(
+		source: ShardSetId,
+		target: ShardSetId,
+		limit: u32,
+	) -> (u32, Vec<(ShardKind, NFTId)>, bool) {
+		let limit = limit as usize;
+		let held: Vec<_> = ShardHolders::<T>::iter_key_prefix(source).take(limit).collect();
+		let pending: Vec<_> =
+			PendingReshares::<T>::iter_key_prefix(source).take(limit - held.len()).collect();
+		let processed = held.len() + pending.len();
+		let mut lost = Vec::new();
+
+		for shard in held {
+			ShardHolders::<T>::remove(source, shard);
+			let (kind, nft_id) = shard;
+			let is_lost = !ShardHolders::<T>::contains_key(target, shard) &&
+				Nfts::<T>::mutate(nft_id, |maybe_nft| match maybe_nft {
+					Some(nft) if kind == ShardKind::Secret && nft.state.is_secret => {
+						nft.state.is_syncing_secret = true;
+						true
+					},
+					Some(nft) if kind == ShardKind::Capsule && nft.state.is_capsule => {
+						nft.state.is_syncing_capsule = true;
+						true
+					},
+					_ => false,
+				});
+			if is_lost {
+				ResharesCount::<T>::mutate(shard, |count| *count = count.saturating_add(1));
+				PendingReshares::<T>::insert(target, shard, ());
+				lost.push(shard);
+			}
+			Self::replace_shard_set(shard, source, is_lost.then_some(target));
+		}
+		for shard in pending {
+			PendingReshares::<T>::remove(source, shard);
+			PendingReshares::<T>::insert(target, shard, ());
+			Self::replace_shard_set(shard, source, Some(target));
+			lost.push(shard);
+		}
+
+		(processed as u32, lost, processed < limit)
+	}
+
+	/// Replace a shard set of a shard by another one, or remove it.
+	fn replace_shard_set(shard: (ShardKind, NFTId), old: ShardSetId, new: Option<ShardSetId>) {
+		NFTShardSets::<T>::mutate(shard, |shard_sets| match new {
+			Some(new) if !shard_sets.contains(&new) => {
+				shard_sets.iter_mut().filter(|x| **x == old).for_each(|x| *x = new)
+			},
+			_ => shard_sets.retain(|x| *x != old),
+		});
+	}
 }
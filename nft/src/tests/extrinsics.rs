@@ -15,14 +15,20 @@
 // along with Ternoa.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::mock::*;
-use crate::{tests::mock, Collection, CollectionId, Error, Event as NFTsEvent, NFTData, NFTId};
-use frame_support::{assert_noop, assert_ok, error::BadOrigin, BoundedVec};
+use crate::{
+	tests::mock, Collection, CollectionId, Error, Event as NFTsEvent, NFTData, NFTId, NFTShardSets,
+	PendingReshares, ResharesCount, ShardHolders, SlotShardSets, VacatedShardSets,
+};
+use frame_support::{assert_noop, assert_ok, error::BadOrigin, traits::Hooks, BoundedVec};
 use frame_system::RawOrigin;
 use pallet_balances::Error as BalanceError;
-use primitives::{nfts::NFTState, tee::ClusterId};
+use primitives::{
+	nfts::{NFTState, ShardKind},
+	tee::ClusterId,
+};
 use sp_arithmetic::per_things::Permill;
 use ternoa_common::traits::NFTExt;
-use ternoa_tee::ClusterType;
+use ternoa_tee::{ClusterType, MeasurementPolicy, MissedHeartbeats};
 
 const ALICE_NFT_ID: NFTId = 0;
 const BOB_NFT_ID: NFTId = 1;
@@ -33,7 +39,7 @@ const PERCENT_100: Permill = Permill::from_parts(1000000);
 const PERCENT_80: Permill = Permill::from_parts(800000);
 const PERCENT_0: Permill = Permill::from_parts(0);
 
-fn origin(account: u64) -> mock::RuntimeOrigin {
+pub fn origin(account: u64) -> mock::RuntimeOrigin {
 	RawOrigin::Signed(account).into()
 }
 
@@ -64,7 +70,7 @@ fn prepare_tests() {
 	assert_eq!(NFT::collections(BOB_COLLECTION_ID).is_some(), true);
 }
 
-fn prepare_tee_for_tests() {
+pub fn prepare_tee_for_tests() {
 	let alice: mock::RuntimeOrigin = origin(ALICE);
 	let bob: mock::RuntimeOrigin = origin(BOB);
 	let charlie: mock::RuntimeOrigin = origin(CHARLIE);
@@ -72,9 +78,26 @@ fn prepare_tee_for_tests() {
 	let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
 	start_active_era(1);
 
-	assert_ok!(TEE::register_enclave(alice.clone(), ALICE_ENCLAVE, api_uri.clone()));
-	assert_ok!(TEE::register_enclave(bob.clone(), BOB_ENCLAVE, api_uri.clone()));
-	assert_ok!(TEE::register_enclave(charlie.clone(), CHARLIE_ENCLAVE, api_uri));
+	let policy = MeasurementPolicy::MrEnclave(APPROVED_MEASUREMENT.mr_enclave);
+	assert_ok!(TEE::approve_measurement(root(), policy));
+	assert_ok!(TEE::register_enclave(
+		alice.clone(),
+		ALICE_ENCLAVE,
+		api_uri.clone(),
+		attestation(ALICE_ENCLAVE)
+	));
+	assert_ok!(TEE::register_enclave(
+		bob.clone(),
+		BOB_ENCLAVE,
+		api_uri.clone(),
+		attestation(BOB_ENCLAVE)
+	));
+	assert_ok!(TEE::register_enclave(
+		charlie.clone(),
+		CHARLIE_ENCLAVE,
+		api_uri,
+		attestation(CHARLIE_ENCLAVE)
+	));
 
 	let cluster_id: ClusterId = 0;
 	let second_cluster_id: ClusterId = 1;
@@ -91,6 +114,18 @@ fn prepare_tee_for_tests() {
 	// Change current block.
 }
 
+/// Removes Alice's enclave from the first cluster and registers Dave to take its place.
+pub fn replace_alice_enclave() {
+	let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
+	assert_ok!(TEE::force_remove_enclave(root(), ALICE));
+	assert_ok!(TEE::register_enclave(
+		origin(DAVE),
+		DAVE_ENCLAVE,
+		api_uri,
+		attestation(DAVE_ENCLAVE)
+	));
+}
+
 mod create_nft {
 	use super::*;
 
@@ -425,6 +460,37 @@ mod burn_nft {
 		)
 	}
 
+	#[test]
+	fn burn_reshared_secret_nft() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000), (DAVE, 1000)])
+			.execute_with(|| {
+				prepare_tests();
+				prepare_tee_for_tests();
+				let alice: mock::RuntimeOrigin = origin(ALICE);
+				let shard = (ShardKind::Secret, ALICE_NFT_ID);
+				NFT::add_secret(alice.clone(), ALICE_NFT_ID, BoundedVec::default()).unwrap();
+				NFT::add_secret_shard(origin(ALICE_ENCLAVE), ALICE_NFT_ID).unwrap();
+				NFT::add_secret_shard(origin(BOB_ENCLAVE), ALICE_NFT_ID).unwrap();
+				let bob_set = SlotShardSets::<Test>::get((0, 1)).unwrap();
+
+				// Alice's shard is lost and waits to be re-synced.
+				replace_alice_enclave();
+				NFT::on_initialize(System::block_number() + 1);
+				let new_set = SlotShardSets::<Test>::get((0, 1)).unwrap();
+				assert!(PendingReshares::<Test>::contains_key(new_set, shard));
+
+				// Burning the nft.
+				let ok = NFT::burn_nft(alice, ALICE_NFT_ID);
+				assert_ok!(ok);
+
+				// Final state checks.
+				assert!(!ShardHolders::<Test>::contains_key(bob_set, shard));
+				assert!(!PendingReshares::<Test>::contains_key(new_set, shard));
+				assert!(NFTShardSets::<Test>::get(shard).is_empty());
+				assert_eq!(ResharesCount::<Test>::get(shard), 0);
+			});
+	}
+
 	#[test]
 	fn burn_synced_capsule() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
@@ -1867,6 +1933,89 @@ mod add_secret_shard {
 		})
 	}
 
+	#[test]
+	fn enclave_is_inactive() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				prepare_tee_for_tests();
+				let alice: mock::RuntimeOrigin = origin(ALICE);
+				let alice_enclave: mock::RuntimeOrigin = origin(ALICE_ENCLAVE);
+				NFT::add_secret(alice, ALICE_NFT_ID, BoundedVec::default()).unwrap();
+
+				// Alice's enclave missed as many heartbeats as allowed.
+				MissedHeartbeats::<Test>::insert(ALICE, 3);
+
+				let err = NFT::add_secret_shard(alice_enclave, ALICE_NFT_ID);
+				assert_noop!(err, Error::<Test>::EnclaveIsInactive);
+			},
+		)
+	}
+
+	#[test]
+	fn reshare_after_enclave_removal() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000), (DAVE, 1000)])
+			.execute_with(|| {
+				prepare_tests();
+				prepare_tee_for_tests();
+				let alice: mock::RuntimeOrigin = origin(ALICE);
+				let shard = (ShardKind::Secret, ALICE_NFT_ID);
+				NFT::add_secret(alice, ALICE_NFT_ID, BoundedVec::default()).unwrap();
+				NFT::add_secret_shard(origin(ALICE_ENCLAVE), ALICE_NFT_ID).unwrap();
+				NFT::add_secret_shard(origin(BOB_ENCLAVE), ALICE_NFT_ID).unwrap();
+				let alice_set = SlotShardSets::<Test>::get((0, 0)).unwrap();
+				let bob_set = SlotShardSets::<Test>::get((0, 1)).unwrap();
+				assert!(ShardHolders::<Test>::contains_key(alice_set, shard));
+				assert!(ShardHolders::<Test>::contains_key(bob_set, shard));
+
+				// Bob's enclave takes the slot of Alice's, its own slot gets a new shard set.
+				replace_alice_enclave();
+				let new_set = SlotShardSets::<Test>::get((0, 1)).unwrap();
+				assert_eq!(SlotShardSets::<Test>::get((0, 0)), Some(bob_set));
+				assert_eq!(VacatedShardSets::<Test>::get(alice_set), Some(new_set));
+				assert!(!NFT::nfts(ALICE_NFT_ID).unwrap().state.is_syncing_secret);
+
+				// The shards of Alice's enclave are moved in the next block.
+				NFT::on_initialize(System::block_number() + 1);
+				assert!(NFT::nfts(ALICE_NFT_ID).unwrap().state.is_syncing_secret);
+				assert!(ShardHolders::<Test>::contains_key(bob_set, shard));
+				assert!(!ShardHolders::<Test>::contains_key(alice_set, shard));
+				assert!(PendingReshares::<Test>::contains_key(new_set, shard));
+				assert_eq!(ResharesCount::<Test>::get(shard), 1);
+				assert_eq!(VacatedShardSets::<Test>::get(alice_set), None);
+				let shards = BoundedVec::truncate_from(vec![shard]);
+				let event = NFTsEvent::ShardsLost { shard_set_id: new_set, shards };
+				System::assert_last_event(RuntimeEvent::NFT(event));
+
+				assert_ok!(TEE::auto_assign_enclave(root(), DAVE, ClusterType::Public));
+				let event = NFTsEvent::ReshareRequested {
+					cluster_id: 0,
+					slot_id: 1,
+					shard_set_id: new_set,
+				};
+				System::assert_has_event(RuntimeEvent::NFT(event));
+
+				NFT::add_secret_shard(origin(DAVE_ENCLAVE), ALICE_NFT_ID).unwrap();
+
+				// Final state checks.
+				assert!(!NFT::nfts(ALICE_NFT_ID).unwrap().state.is_syncing_secret);
+				assert!(ShardHolders::<Test>::contains_key(new_set, shard));
+				assert!(!PendingReshares::<Test>::contains_key(new_set, shard));
+				assert_eq!(ResharesCount::<Test>::get(shard), 0);
+				assert_eq!(NFTShardSets::<Test>::get(shard).into_inner(), vec![bob_set, new_set]);
+
+				// Events checks.
+				let event = NFTsEvent::ShardReshared {
+					nft_id: ALICE_NFT_ID,
+					kind: ShardKind::Secret,
+					enclave: DAVE_ENCLAVE,
+				};
+				System::assert_has_event(RuntimeEvent::NFT(event));
+				let event = NFTsEvent::SecretNFTSynced { nft_id: ALICE_NFT_ID };
+				System::assert_last_event(RuntimeEvent::NFT(event));
+			});
+	}
+
 	#[test]
 	fn nft_not_found() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
@@ -2709,6 +2858,56 @@ mod add_capsule_shard {
 		})
 	}
 
+	#[test]
+	fn enclave_is_inactive() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
+			|| {
+				prepare_tests();
+				prepare_tee_for_tests();
+				let alice: mock::RuntimeOrigin = origin(ALICE);
+				let alice_enclave: mock::RuntimeOrigin = origin(ALICE_ENCLAVE);
+				NFT::convert_to_capsule(alice, ALICE_NFT_ID, BoundedVec::default()).unwrap();
+
+				// Alice's enclave missed as many heartbeats as allowed.
+				MissedHeartbeats::<Test>::insert(ALICE, 3);
+
+				let err = NFT::add_capsule_shard(alice_enclave, ALICE_NFT_ID);
+				assert_noop!(err, Error::<Test>::EnclaveIsInactive);
+			},
+		)
+	}
+
+	#[test]
+	fn reshare_after_enclave_removal() {
+		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000), (DAVE, 1000)])
+			.execute_with(|| {
+				prepare_tests();
+				prepare_tee_for_tests();
+				let alice: mock::RuntimeOrigin = origin(ALICE);
+				let shard = (ShardKind::Capsule, ALICE_NFT_ID);
+				NFT::convert_to_capsule(alice, ALICE_NFT_ID, BoundedVec::default()).unwrap();
+				NFT::add_capsule_shard(origin(ALICE_ENCLAVE), ALICE_NFT_ID).unwrap();
+				NFT::add_capsule_shard(origin(BOB_ENCLAVE), ALICE_NFT_ID).unwrap();
+
+				replace_alice_enclave();
+				NFT::on_initialize(System::block_number() + 1);
+				let new_set = SlotShardSets::<Test>::get((0, 1)).unwrap();
+				assert!(NFT::nfts(ALICE_NFT_ID).unwrap().state.is_syncing_capsule);
+				assert!(PendingReshares::<Test>::contains_key(new_set, shard));
+
+				assert_ok!(TEE::assign_enclave(root(), DAVE, 0, 1));
+				NFT::add_capsule_shard(origin(DAVE_ENCLAVE), ALICE_NFT_ID).unwrap();
+
+				// Final state checks.
+				assert!(!NFT::nfts(ALICE_NFT_ID).unwrap().state.is_syncing_capsule);
+				assert!(ShardHolders::<Test>::contains_key(new_set, shard));
+
+				// Events checks.
+				let event = NFTsEvent::CapsuleSynced { nft_id: ALICE_NFT_ID };
+				System::assert_last_event(RuntimeEvent::NFT(event));
+			});
+	}
+
 	#[test]
 	fn nft_not_found() {
 		ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)]).execute_with(
@@ -0,0 +1,62 @@
+// Copyright 2023 Capsule Corp (France) SAS.
+// This file is part of Ternoa.
+
+// Ternoa is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ternoa is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ternoa.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{extrinsics::*, mock::*};
+use crate::{Event as NFTsEvent, PendingReshares, SlotShardSets, VacatedShardSets};
+use frame_support::{
+	traits::{Get, Hooks},
+	BoundedVec,
+};
+use sp_arithmetic::per_things::Permill;
+
+#[test]
+fn on_initialize() {
+	ExtBuilder::new_build(vec![(ALICE, 10000), (BOB, 1000), (CHARLIE, 1000), (DAVE, 1000)])
+		.execute_with(|| {
+			prepare_tee_for_tests();
+			let alice: RuntimeOrigin = origin(ALICE);
+			let limit = MaxResharesPerBlock::get();
+			for nft_id in 0..=limit {
+				NFT::create_nft(alice.clone(), BoundedVec::default(), Permill::zero(), None, false)
+					.unwrap();
+				NFT::add_secret(alice.clone(), nft_id, BoundedVec::default()).unwrap();
+				NFT::add_secret_shard(origin(ALICE_ENCLAVE), nft_id).unwrap();
+				NFT::add_secret_shard(origin(BOB_ENCLAVE), nft_id).unwrap();
+			}
+			let alice_set = SlotShardSets::<Test>::get((0, 0)).unwrap();
+
+			replace_alice_enclave();
+			let new_set = SlotShardSets::<Test>::get((0, 1)).unwrap();
+
+			// Only a limited number of shards are moved in a block.
+			NFT::on_initialize(System::block_number() + 1);
+			assert_eq!(PendingReshares::<Test>::iter_prefix(new_set).count(), limit as usize);
+			assert_eq!(VacatedShardSets::<Test>::get(alice_set), Some(new_set));
+
+			// The remaining shards are moved in the following block.
+			NFT::on_initialize(System::block_number() + 2);
+			assert_eq!(PendingReshares::<Test>::iter_prefix(new_set).count(), limit as usize + 1);
+			assert_eq!(VacatedShardSets::<Test>::get(alice_set), None);
+
+			// Events checks.
+			let event = &System::events().last().unwrap().event;
+			assert!(matches!(
+				event,
+				RuntimeEvent::NFT(NFTsEvent::ShardsLost { shard_set_id, shards })
+					if *shard_set_id == new_set && shards.len() == 1
+			));
+		});
+}
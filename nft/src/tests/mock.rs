@@ -20,7 +20,8 @@ use frame_support::{
 	traits::{ConstU32, ConstU64, Contains, Currency, Get, Hooks},
 	PalletId,
 };
-use sp_core::H256;
+use parity_scale_codec::{Decode, Encode};
+use sp_core::{hashing::blake2_256, H256};
 use sp_runtime::{
 	curve::PiecewiseLinear,
 	testing::Header,
@@ -30,6 +31,10 @@ use sp_runtime::{
 use sp_staking::{EraIndex, SessionIndex};
 
 use crate::{self as ternoa_nft, Config, NegativeImbalanceOf};
+use ternoa_tee::{
+	AttestationQuote, AttestationVerifier, EnclaveMeasurement, QuoteVerification, TeeKind,
+	VerifiedQuote,
+};
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
 type AccountId = u64;
@@ -45,6 +50,8 @@ pub const CHARLIE: u64 = 3;
 pub const ALICE_ENCLAVE: u64 = 4;
 pub const BOB_ENCLAVE: u64 = 5;
 pub const CHARLIE_ENCLAVE: u64 = 6;
+pub const DAVE: u64 = 7;
+pub const DAVE_ENCLAVE: u64 = 8;
 pub const COLLECTOR: u64 = 99;
 pub const NFT_MINT_FEE: Balance = 10;
 pub const SECRET_NFT_MINT_FEE: Balance = 75;
@@ -247,6 +254,16 @@ impl pallet_staking::Config for Test {
 parameter_types! {
 	pub const ClusterSize: u32 = 2;
 	pub const MaxUriLen: u32 = 12;
+	pub const MaxQuoteLen: u32 = 256;
+	pub const SlashDeferDuration: u32 = 10;
+	pub const MaxSlashesPerBlock: u32 = 2;
+	pub const MaxMetrics: u32 = 10;
+	pub const MaxMetricNameLen: u32 = 32;
+	pub const InitialReportQuorum: u32 = 1;
+	pub const OutlierThreshold: u8 = 20;
+	pub const MaxNominators: u32 = 4;
+	pub const MinNominationBond: Balance = 10;
+	pub const MaxMissedHeartbeats: u32 = 3;
 	pub const ListSizeLimit: u32 = 10;
 	pub const TeeBondingDuration: u32 = 10;
 	pub const InitialStakingAmount: Balance = 20;
@@ -266,6 +283,47 @@ impl ternoa_tee::Config for Test {
 	type InitalDailyRewardPool = InitalDailyRewardPool;
 	type PalletId = TeePalletId;
 	type TeeHistoryDepth = TeeHistoryDepth;
+	type MaxQuoteLen = MaxQuoteLen;
+	type AttestationVerifier = MockAttestationVerifier;
+	type Slash = ();
+	type SlashDeferDuration = SlashDeferDuration;
+	type MaxSlashesPerBlock = MaxSlashesPerBlock;
+	type MaxMetrics = MaxMetrics;
+	type MaxMetricNameLen = MaxMetricNameLen;
+	type InitialReportQuorum = InitialReportQuorum;
+	type OutlierThreshold = OutlierThreshold;
+	type MaxNominators = MaxNominators;
+	type MinNominationBond = MinNominationBond;
+	type MaxMissedHeartbeats = MaxMissedHeartbeats;
+	type EnclaveSlotHandler = NFT;
+}
+
+pub const APPROVED_MEASUREMENT: EnclaveMeasurement =
+	EnclaveMeasurement { mr_enclave: [1; 32], mr_signer: [2; 32] };
+
+/// Test quotes are a SCALE encoded `VerifiedQuote` trusted as is.
+pub struct MockAttestationVerifier;
+impl AttestationVerifier for MockAttestationVerifier {
+	fn verify_quote(_kind: &TeeKind, quote: &[u8]) -> QuoteVerification {
+		match VerifiedQuote::decode(&mut &quote[..]) {
+			Ok(verified) => QuoteVerification::Verified(verified),
+			Err(_) => QuoteVerification::Invalid,
+		}
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn benchmark_quote(_kind: &TeeKind, verified: &VerifiedQuote) -> Vec<u8> {
+		verified.encode()
+	}
+}
+
+pub fn attestation(enclave_address: u64) -> AttestationQuote<MaxQuoteLen> {
+	let verified = VerifiedQuote {
+		measurement: APPROVED_MEASUREMENT,
+		report_data: blake2_256(&enclave_address.encode()),
+	};
+	let quote = verified.encode().try_into().unwrap();
+	AttestationQuote::new(TeeKind::Sgx, APPROVED_MEASUREMENT, quote)
 }
 
 parameter_types! {
@@ -275,6 +333,7 @@ parameter_types! {
 	pub const CollectionSizeLimit: u32 = 10;
 	pub const InitialSecretMintFee: Balance = SECRET_NFT_MINT_FEE;
 	pub const ShardsNumber: u32 = 2;
+	pub const MaxResharesPerBlock: u32 = 10;
 	pub const InitialCapsuleMintFee: Balance = CAPSULE_MINT_FEE;
 }
 
@@ -289,7 +348,9 @@ impl Config for Test {
 	type CollectionSizeLimit = CollectionSizeLimit;
 	type InitialSecretMintFee = InitialSecretMintFee;
 	type ShardsNumber = ShardsNumber;
+	type MaxResharesPerBlock = MaxResharesPerBlock;
 	type TEEExt = TEE;
+	type RentExt = ();
 	type InitialCapsuleMintFee = InitialCapsuleMintFee;
 }
 
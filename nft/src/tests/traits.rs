@@ -15,13 +15,13 @@
 // along with Ternoa.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::mock::*;
-use frame_support::BoundedVec;
+use frame_support::{assert_err, BoundedVec};
 use frame_system::RawOrigin;
 use primitives::nfts::NFTState;
 use sp_arithmetic::per_things::Permill;
 use ternoa_common::traits::NFTExt;
 
-use crate::tests::mock;
+use crate::{tests::mock, Error};
 
 const PERCENT_0: Permill = Permill::from_parts(0);
 
@@ -87,3 +87,33 @@ fn create_nft() {
 		assert_eq!(nft.owner, ALICE);
 	})
 }
+
+#[test]
+fn mint_nft() {
+	ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+		let alice: RuntimeOrigin = RawOrigin::Signed(ALICE).into();
+		NFT::create_collection(alice, BoundedVec::default(), None).unwrap();
+
+		let nft_id =
+			<NFT as NFTExt>::mint_nft(BOB, ALICE, BoundedVec::default(), PERCENT_0, Some(0), false)
+				.unwrap();
+
+		let nft = NFT::get_nft(nft_id).unwrap();
+		assert_eq!(nft.owner, BOB);
+		assert_eq!(nft.creator, ALICE);
+		assert_eq!(NFT::collections(0).unwrap().nfts.to_vec(), vec![nft_id]);
+		assert_eq!(Balances::free_balance(BOB), 1000 - NFT_MINT_FEE);
+	})
+}
+
+#[test]
+fn mint_nft_in_collection_of_another_creator() {
+	ExtBuilder::new_build(vec![(ALICE, 1000), (BOB, 1000)]).execute_with(|| {
+		let alice: RuntimeOrigin = RawOrigin::Signed(ALICE).into();
+		NFT::create_collection(alice, BoundedVec::default(), None).unwrap();
+
+		let err =
+			<NFT as NFTExt>::mint_nft(BOB, BOB, BoundedVec::default(), PERCENT_0, Some(0), false);
+		assert_err!(err, Error::<Test>::NotTheCollectionOwner);
+	})
+}
@@ -0,0 +1,136 @@
+// Copyright 2023 Capsule Corp (France) SAS.
+// This file is part of Ternoa.
+
+// Ternoa is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ternoa is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ternoa.  If not, see <http://www.gnu.org/licenses/>.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use crate::Pallet as OpenEdition;
+use frame_benchmarking::{account as benchmark_account, benchmarks, impl_benchmark_test_suite};
+use frame_support::assert_ok;
+use frame_system::{pallet_prelude::OriginFor, Pallet as System, RawOrigin};
+use primitives::marketplace::{MarketplaceData, MarketplaceType};
+use sp_runtime::traits::Bounded;
+use sp_std::prelude::*;
+
+pub struct BenchmarkData {
+	pub collection_id: CollectionId,
+	pub marketplace_id: MarketplaceId,
+}
+
+const PERCENT_0: Permill = Permill::from_parts(0);
+const PERCENT_20: Permill = Permill::from_parts(200000);
+
+pub fn prepare_benchmarks<T: Config>(open_sale: bool) -> BenchmarkData {
+	// Get accounts
+	let alice: T::AccountId = get_account::<T>("ALICE");
+	let bob: T::AccountId = get_account::<T>("BOB");
+	let charlie: T::AccountId = get_account::<T>("CHARLIE");
+
+	// Give them enough caps
+	T::Currency::make_free_balance_be(&alice, BalanceOf::<T>::max_value() / 5u32.into());
+	T::Currency::make_free_balance_be(&bob, BalanceOf::<T>::max_value() / 5u32.into());
+	T::Currency::make_free_balance_be(&charlie, BalanceOf::<T>::max_value() / 5u32.into());
+
+	// Create Alice's marketplace
+	let marketplace_id = 0u32;
+	let marketplace_data = MarketplaceData::new(
+		alice,
+		MarketplaceType::Public,
+		Some(CompoundFee::Percentage(PERCENT_20)),
+		None,
+		None,
+		None,
+		None,
+	);
+	T::MarketplaceExt::set_marketplace(marketplace_id, marketplace_data).unwrap();
+
+	// Create Bob's empty collection
+	let collection_id = 0u32;
+	T::NFTExt::create_filled_collection(bob, collection_id, 0, 0).unwrap();
+
+	// Open the sale
+	if open_sale {
+		let start_block = System::<T>::block_number();
+		let end_block = start_block + T::MinSaleDuration::get();
+		let price = BalanceOf::<T>::max_value() / 1_000_000u32.into();
+
+		assert_ok!(OpenEdition::<T>::create_sale(
+			origin::<T>("BOB"),
+			collection_id,
+			marketplace_id,
+			start_block,
+			end_block,
+			price,
+			T::MaxMintQuantity::get(),
+			BoundedVec::default(),
+			PERCENT_0,
+		));
+	}
+
+	BenchmarkData { collection_id, marketplace_id }
+}
+
+pub fn get_account<T: Config>(name: &'static str) -> T::AccountId {
+	let account: T::AccountId = benchmark_account(name, 0, 0);
+	account
+}
+
+pub fn origin<T: Config>(name: &'static str) -> OriginFor<T> {
+	RawOrigin::Signed(get_account::<T>(name)).into()
+}
+
+benchmarks! {
+	create_sale {
+		let s in 0 .. T::ParallelSaleLimit::get() - 1;
+		let bench_data = prepare_benchmarks::<T>(false);
+		let bob: T::AccountId = get_account::<T>("BOB");
+		let collection_id = bench_data.collection_id;
+		Deadlines::<T>::mutate(|x| x.bulk_insert(collection_id + 1, 99u32.into(), s)).unwrap();
+
+		let start_block = System::<T>::block_number();
+		let end_block = start_block + T::MinSaleDuration::get();
+		let price = BalanceOf::<T>::max_value() / 1_000_000u32.into();
+	}: _(
+		RawOrigin::Signed(bob),
+		collection_id,
+		bench_data.marketplace_id,
+		start_block,
+		end_block,
+		price,
+		1,
+		BoundedVec::default(),
+		PERCENT_0
+	)
+	verify {
+		assert!(Sales::<T>::contains_key(collection_id));
+	}
+
+	mint {
+		let s in 1 .. T::MaxMintQuantity::get();
+		let bench_data = prepare_benchmarks::<T>(true);
+		let charlie: T::AccountId = get_account::<T>("CHARLIE");
+		let collection_id = bench_data.collection_id;
+	}: _(RawOrigin::Signed(charlie.clone()), collection_id, s)
+	verify {
+		assert_eq!(MintedBy::<T>::get(collection_id, charlie), s);
+	}
+}
+
+impl_benchmark_test_suite!(
+	OpenEdition,
+	crate::tests::mock::new_test_ext(),
+	crate::tests::mock::Test
+);
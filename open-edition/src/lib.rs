@@ -0,0 +1,413 @@
+// Copyright 2023 Capsule Corp (France) SAS.
+// This file is part of Ternoa.
+
+// Ternoa is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ternoa is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ternoa.  If not, see <http://www.gnu.org/licenses/>.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod tests;
+mod types;
+mod weights;
+
+pub use pallet::*;
+
+use frame_support::{
+	pallet_prelude::*,
+	traits::{Currency, ExistenceRequirement::KeepAlive, Get, StorageVersion},
+};
+use frame_system::pallet_prelude::*;
+use primitives::{
+	common::CompoundFee,
+	marketplace::MarketplaceId,
+	nfts::{CollectionId, NFTId},
+	U8BoundedVec,
+};
+use sp_runtime::{traits::Saturating, Permill};
+use ternoa_common::traits::{MarketplaceExt, NFTExt};
+pub use types::{DeadlineList, SaleData};
+pub use weights::WeightInfo;
+
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+pub type NFTOffchainDataLimitOf<T> = <<T as Config>::NFTExt as NFTExt>::NFTOffchainDataLimit;
+
+const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::dispatch::DispatchResultWithPostInfo;
+
+	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
+	pub struct Pallet<T>(_);
+
+	/// Configure the pallet by specifying the parameters and types on which it depends.
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// Currency used to handle transactions and pay for the minted NFTs.
+		type Currency: Currency<Self::AccountId>;
+
+		/// Link to the NFT pallet.
+		type NFTExt: NFTExt<AccountId = Self::AccountId>;
+
+		/// Link to the Marketplace pallet.
+		type MarketplaceExt: MarketplaceExt<AccountId = Self::AccountId, Balance = BalanceOf<Self>>;
+
+		/// Weight information for pallet.
+		type WeightInfo: WeightInfo;
+
+		// Constants
+		/// Minimum duration of a sale.
+		#[pallet::constant]
+		type MinSaleDuration: Get<Self::BlockNumber>;
+
+		/// Maximum duration of a sale.
+		#[pallet::constant]
+		type MaxSaleDuration: Get<Self::BlockNumber>;
+
+		/// Maximum amount of blocks between sale creation and sale start.
+		#[pallet::constant]
+		type MaxSaleDelay: Get<Self::BlockNumber>;
+
+		/// Maximum number of NFTs that can be minted in a single call.
+		#[pallet::constant]
+		type MaxMintQuantity: Get<u32>;
+
+		/// Maximum number of sales that can be open at the same time.
+		#[pallet::constant]
+		type ParallelSaleLimit: Get<u32>;
+
+		/// Maximum number of actions in one block.
+		#[pallet::constant]
+		type ActionsInBlockLimit: Get<u32>;
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Weight: see `begin_block`.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let mut read = 1u64;
+			let mut write = 0u64;
+
+			let mut deadlines = Deadlines::<T>::get();
+			let max_actions = T::ActionsInBlockLimit::get();
+			let mut actions = 0;
+
+			while let Some(collection_id) = deadlines.pop_next(now) {
+				let minted = Self::end_sale(collection_id);
+
+				read += 2;
+				write += 3 + minted as u64;
+				actions += 1;
+
+				if actions >= max_actions {
+					break
+				}
+			}
+
+			if actions > 0 {
+				Deadlines::<T>::set(deadlines);
+				write += 1;
+			}
+
+			T::DbWeight::get().reads_writes(read, write)
+		}
+	}
+
+	/// Data related to open edition sales
+	#[pallet::storage]
+	#[pallet::getter(fn sales)]
+	pub type Sales<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		CollectionId,
+		SaleData<T::AccountId, T::BlockNumber, BalanceOf<T>, NFTOffchainDataLimitOf<T>>,
+		OptionQuery,
+	>;
+
+	/// Number of NFTs minted by each account in a sale
+	#[pallet::storage]
+	#[pallet::getter(fn minted_by)]
+	pub type MintedBy<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		CollectionId,
+		Blake2_128Concat,
+		T::AccountId,
+		u32,
+		ValueQuery,
+	>;
+
+	/// Sorted lists of block deadlines
+	#[pallet::storage]
+	#[pallet::getter(fn deadlines)]
+	pub type Deadlines<T: Config> =
+		StorageValue<_, DeadlineList<T::BlockNumber, T::ParallelSaleLimit>, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A new open edition sale was created.
+		SaleCreated {
+			collection_id: CollectionId,
+			marketplace_id: MarketplaceId,
+			creator: T::AccountId,
+			start_block: T::BlockNumber,
+			end_block: T::BlockNumber,
+			price: BalanceOf<T>,
+			wallet_limit: u32,
+			offchain_data: U8BoundedVec<NFTOffchainDataLimitOf<T>>,
+			royalty: Permill,
+		},
+		/// NFTs were minted and paid in a sale.
+		NFTsMinted {
+			collection_id: CollectionId,
+			buyer: T::AccountId,
+			nft_ids: BoundedVec<NFTId, T::MaxMintQuantity>,
+			amount: BalanceOf<T>,
+			marketplace_cut: BalanceOf<T>,
+			creator_cut: BalanceOf<T>,
+		},
+		/// A sale has ended and its collection was closed.
+		SaleEnded { collection_id: CollectionId, minted: u32 },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Operation is not allowed because the collection was not found.
+		CollectionNotFound,
+		/// Operation is not allowed because the caller is not the owner of the collection.
+		NotTheCollectionOwner,
+		/// Operation is not allowed because the collection is closed.
+		CollectionIsClosed,
+		/// Operation is not allowed because the collection is already on sale.
+		SaleAlreadyExists,
+		/// Operation is not allowed because the sale does not exist.
+		SaleDoesNotExist,
+		/// Sale start block cannot be lower than current block.
+		SaleCannotStartInThePast,
+		/// Sale end block cannot be lower than start block.
+		SaleCannotEndBeforeItHasStarted,
+		/// Sale duration exceeds the maximum allowed duration.
+		SaleDurationIsTooLong,
+		/// Sale duration is lower than the minimum allowed duration.
+		SaleDurationIsTooShort,
+		/// Sale start block cannot be exceed the maximum allowed start delay.
+		SaleStartIsTooFarAway,
+		/// The wallet limit must be greater than zero.
+		InvalidWalletLimit,
+		/// The marketplace was not found.
+		MarketplaceNotFound,
+		/// Not allowed to list on that marketplace.
+		NotAllowedToList,
+		/// Price of a copy must cover the marketplace fee.
+		PriceCannotCoverMarketplaceFee,
+		/// Maximum number of sales has been reached.
+		MaximumSalesLimitReached,
+		/// Operation is not allowed because the sale has not started.
+		SaleNotStarted,
+		/// Operation is not allowed because the sale has ended.
+		SaleHasEnded,
+		/// The quantity must be greater than zero and not exceed the maximum mint quantity.
+		InvalidQuantity,
+		/// Operation is not allowed because the account would exceed the wallet limit.
+		WalletLimitReached,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Open a timed sale during which anyone can mint copies of the same NFT into the
+		/// collection. The collection is closed at the end of the sale.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::create_sale(Deadlines::<T>::get().len() as u32))]
+		pub fn create_sale(
+			origin: OriginFor<T>,
+			collection_id: CollectionId,
+			marketplace_id: MarketplaceId,
+			start_block: T::BlockNumber,
+			end_block: T::BlockNumber,
+			price: BalanceOf<T>,
+			wallet_limit: u32,
+			offchain_data: U8BoundedVec<NFTOffchainDataLimitOf<T>>,
+			royalty: Permill,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let now = frame_system::Pallet::<T>::block_number();
+
+			ensure!(start_block >= now, Error::<T>::SaleCannotStartInThePast);
+			ensure!(start_block < end_block, Error::<T>::SaleCannotEndBeforeItHasStarted);
+
+			let duration = end_block.saturating_sub(start_block);
+			let buffer = start_block.saturating_sub(now);
+
+			ensure!(duration <= T::MaxSaleDuration::get(), Error::<T>::SaleDurationIsTooLong);
+			ensure!(duration >= T::MinSaleDuration::get(), Error::<T>::SaleDurationIsTooShort);
+			ensure!(buffer <= T::MaxSaleDelay::get(), Error::<T>::SaleStartIsTooFarAway);
+			ensure!(wallet_limit > 0, Error::<T>::InvalidWalletLimit);
+
+			let collection =
+				T::NFTExt::get_collection(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+			ensure!(collection.owner == who, Error::<T>::NotTheCollectionOwner);
+			ensure!(!collection.is_closed, Error::<T>::CollectionIsClosed);
+			ensure!(!Sales::<T>::contains_key(collection_id), Error::<T>::SaleAlreadyExists);
+
+			let marketplace = T::MarketplaceExt::get_marketplace(marketplace_id)
+				.ok_or(Error::<T>::MarketplaceNotFound)?;
+
+			marketplace
+				.allowed_to_list(&who, Some(collection_id))
+				.ok_or(Error::<T>::NotAllowedToList)?;
+
+			// Check if the price can cover the marketplace commission_fee if it exists.
+			if let Some(CompoundFee::Flat(flat_commission)) = &marketplace.commission_fee {
+				ensure!(price >= *flat_commission, Error::<T>::PriceCannotCoverMarketplaceFee);
+			}
+
+			// Add collection ID to deadlines
+			Deadlines::<T>::try_mutate(|x| -> DispatchResult {
+				x.insert(collection_id, end_block)
+					.map_err(|_| Error::<T>::MaximumSalesLimitReached)?;
+				Ok(())
+			})?;
+
+			let sale = SaleData {
+				creator: who.clone(),
+				marketplace_id,
+				start_block,
+				end_block,
+				price,
+				wallet_limit,
+				offchain_data: offchain_data.clone(),
+				royalty,
+				commission_fee: marketplace.commission_fee,
+				minted: 0,
+			};
+			Sales::<T>::insert(collection_id, sale);
+
+			let event = Event::SaleCreated {
+				collection_id,
+				marketplace_id,
+				creator: who,
+				start_block,
+				end_block,
+				price,
+				wallet_limit,
+				offchain_data,
+				royalty,
+			};
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Mint `quantity` copies of the NFT of a sale. The creator of the sale is paid minus the
+		/// marketplace commission.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::mint(*quantity))]
+		pub fn mint(
+			origin: OriginFor<T>,
+			collection_id: CollectionId,
+			quantity: u32,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let now = frame_system::Pallet::<T>::block_number();
+
+			let mut sale = Sales::<T>::get(collection_id).ok_or(Error::<T>::SaleDoesNotExist)?;
+			ensure!(sale.start_block <= now, Error::<T>::SaleNotStarted);
+			ensure!(sale.is_open(now), Error::<T>::SaleHasEnded);
+			ensure!(
+				quantity > 0 && quantity <= T::MaxMintQuantity::get(),
+				Error::<T>::InvalidQuantity
+			);
+
+			let minted_by = MintedBy::<T>::get(collection_id, &who).saturating_add(quantity);
+			ensure!(minted_by <= sale.wallet_limit, Error::<T>::WalletLimitReached);
+
+			// Pay the marketplace and the creator.
+			let marketplace = T::MarketplaceExt::get_marketplace(sale.marketplace_id)
+				.ok_or(Error::<T>::MarketplaceNotFound)?;
+
+			let amount = sale.price.saturating_mul(quantity.into());
+			let marketplace_cut = sale.commission_fee.as_ref().map_or_else(
+				|| 0u32.into(),
+				|x| match x {
+					CompoundFee::Flat(x) => (*x).saturating_mul(quantity.into()),
+					CompoundFee::Percentage(x) => *x * amount,
+				},
+			);
+			let creator_cut = amount.saturating_sub(marketplace_cut);
+
+			T::Currency::transfer(&who, &marketplace.owner, marketplace_cut, KeepAlive)?;
+			T::Currency::transfer(&who, &sale.creator, creator_cut, KeepAlive)?;
+
+			// Mint the copies, the creator of the sale stays the creator of the NFTs.
+			let mut nft_ids: BoundedVec<NFTId, T::MaxMintQuantity> = BoundedVec::default();
+			for _ in 0..quantity {
+				let nft_id = T::NFTExt::mint_nft(
+					who.clone(),
+					sale.creator.clone(),
+					sale.offchain_data.clone(),
+					sale.royalty,
+					Some(collection_id),
+					false,
+				)?;
+
+				nft_ids.try_push(nft_id).map_err(|_| Error::<T>::InvalidQuantity)?;
+			}
+
+			sale.minted = sale.minted.saturating_add(quantity);
+			Sales::<T>::insert(collection_id, sale);
+			MintedBy::<T>::insert(collection_id, &who, minted_by);
+
+			let event = Event::NFTsMinted {
+				collection_id,
+				buyer: who,
+				nft_ids,
+				amount,
+				marketplace_cut,
+				creator_cut,
+			};
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Remove a sale and close its collection. Returns the number of minted NFTs.
+	pub fn end_sale(collection_id: CollectionId) -> u32 {
+		let minted = match Sales::<T>::take(collection_id) {
+			Some(sale) => sale.minted,
+			None => return 0,
+		};
+
+		// Every account has minted at least one NFT.
+		let _ = MintedBy::<T>::clear_prefix(collection_id, minted, None);
+
+		// The collection can have been burned if nothing was minted.
+		let _ = T::NFTExt::close_collection(collection_id);
+
+		Self::deposit_event(Event::SaleEnded { collection_id, minted });
+
+		minted
+	}
+}
@@ -0,0 +1,458 @@
+// Copyright 2023 Capsule Corp (France) SAS.
+// This file is part of Ternoa.
+
+// Ternoa is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ternoa is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ternoa.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::mock::*;
+use frame_support::{assert_noop, assert_ok, BoundedVec};
+use frame_system::RawOrigin;
+use primitives::{
+	marketplace::{MarketplaceId, MarketplaceType},
+	nfts::{CollectionId, NFTId},
+	CompoundFee, ConfigOp,
+};
+use sp_arithmetic::per_things::Permill;
+
+use crate::{
+	tests::mock, types::SaleData, Deadlines, Error, Event as OpenEditionEvent, MintedBy, Sales,
+};
+
+const PERCENT_10: Permill = Permill::from_parts(100000);
+const PERCENT_20: Permill = Permill::from_parts(200000);
+const ALICE_COLLECTION_ID: CollectionId = 0;
+const BOB_COLLECTION_ID: CollectionId = 1;
+const INVALID_COLLECTION_ID: CollectionId = 99;
+const DAVE_MARKETPLACE_ID: MarketplaceId = 0;
+const INVALID_MARKETPLACE_ID: MarketplaceId = 99;
+const DEFAULT_STARTBLOCK: BlockNumber = 10;
+const DEFAULT_ENDBLOCK: BlockNumber = 200;
+const DEFAULT_PRICE: u128 = 50;
+const DEFAULT_WALLET_LIMIT: u32 = 3;
+
+fn origin(account: u64) -> mock::RuntimeOrigin {
+	RawOrigin::Signed(account).into()
+}
+
+pub fn prepare_tests() {
+	let alice: mock::RuntimeOrigin = origin(ALICE);
+	let bob: mock::RuntimeOrigin = origin(BOB);
+	let dave: mock::RuntimeOrigin = origin(DAVE);
+
+	//Create collections.
+	NFT::create_collection(alice, BoundedVec::default(), None).unwrap();
+	NFT::create_collection(bob, BoundedVec::default(), None).unwrap();
+
+	//Create marketplace.
+	Marketplace::create_marketplace(dave.clone(), MarketplaceType::Public).unwrap();
+	Marketplace::set_marketplace_configuration(
+		dave,
+		DAVE_MARKETPLACE_ID,
+		ConfigOp::Set(CompoundFee::Percentage(PERCENT_20)),
+		ConfigOp::Noop,
+		ConfigOp::Noop,
+		ConfigOp::Noop,
+		ConfigOp::Noop,
+	)
+	.unwrap();
+
+	//Check existence.
+	assert!(NFT::collections(ALICE_COLLECTION_ID).is_some());
+	assert!(NFT::collections(BOB_COLLECTION_ID).is_some());
+	assert!(Marketplace::marketplaces(DAVE_MARKETPLACE_ID).is_some());
+}
+
+pub fn create_default_sale() {
+	OpenEdition::create_sale(
+		origin(ALICE),
+		ALICE_COLLECTION_ID,
+		DAVE_MARKETPLACE_ID,
+		DEFAULT_STARTBLOCK,
+		DEFAULT_ENDBLOCK,
+		DEFAULT_PRICE,
+		DEFAULT_WALLET_LIMIT,
+		BoundedVec::default(),
+		PERCENT_10,
+	)
+	.unwrap();
+}
+
+mod create_sale {
+	use super::*;
+
+	#[test]
+	fn create_sale() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+
+			let ok = OpenEdition::create_sale(
+				origin(ALICE),
+				ALICE_COLLECTION_ID,
+				DAVE_MARKETPLACE_ID,
+				DEFAULT_STARTBLOCK,
+				DEFAULT_ENDBLOCK,
+				DEFAULT_PRICE,
+				DEFAULT_WALLET_LIMIT,
+				BoundedVec::default(),
+				PERCENT_10,
+			);
+			assert_ok!(ok);
+
+			// Storage.
+			let sale = SaleData {
+				creator: ALICE,
+				marketplace_id: DAVE_MARKETPLACE_ID,
+				start_block: DEFAULT_STARTBLOCK,
+				end_block: DEFAULT_ENDBLOCK,
+				price: DEFAULT_PRICE,
+				wallet_limit: DEFAULT_WALLET_LIMIT,
+				offchain_data: BoundedVec::default(),
+				royalty: PERCENT_10,
+				commission_fee: Some(CompoundFee::Percentage(PERCENT_20)),
+				minted: 0,
+			};
+			assert_eq!(Sales::<Test>::get(ALICE_COLLECTION_ID), Some(sale));
+			assert_eq!(Deadlines::<Test>::get().next(DEFAULT_ENDBLOCK), Some(ALICE_COLLECTION_ID));
+
+			// Events.
+			let event = OpenEditionEvent::SaleCreated {
+				collection_id: ALICE_COLLECTION_ID,
+				marketplace_id: DAVE_MARKETPLACE_ID,
+				creator: ALICE,
+				start_block: DEFAULT_STARTBLOCK,
+				end_block: DEFAULT_ENDBLOCK,
+				price: DEFAULT_PRICE,
+				wallet_limit: DEFAULT_WALLET_LIMIT,
+				offchain_data: BoundedVec::default(),
+				royalty: PERCENT_10,
+			};
+			System::assert_last_event(RuntimeEvent::OpenEdition(event));
+		})
+	}
+
+	#[test]
+	fn collection_not_found() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+
+			let err = OpenEdition::create_sale(
+				origin(ALICE),
+				INVALID_COLLECTION_ID,
+				DAVE_MARKETPLACE_ID,
+				DEFAULT_STARTBLOCK,
+				DEFAULT_ENDBLOCK,
+				DEFAULT_PRICE,
+				DEFAULT_WALLET_LIMIT,
+				BoundedVec::default(),
+				PERCENT_10,
+			);
+			assert_noop!(err, Error::<Test>::CollectionNotFound);
+		})
+	}
+
+	#[test]
+	fn not_the_collection_owner() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+
+			let err = OpenEdition::create_sale(
+				origin(ALICE),
+				BOB_COLLECTION_ID,
+				DAVE_MARKETPLACE_ID,
+				DEFAULT_STARTBLOCK,
+				DEFAULT_ENDBLOCK,
+				DEFAULT_PRICE,
+				DEFAULT_WALLET_LIMIT,
+				BoundedVec::default(),
+				PERCENT_10,
+			);
+			assert_noop!(err, Error::<Test>::NotTheCollectionOwner);
+		})
+	}
+
+	#[test]
+	fn collection_is_closed() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			NFT::close_collection(origin(ALICE), ALICE_COLLECTION_ID).unwrap();
+
+			let err = OpenEdition::create_sale(
+				origin(ALICE),
+				ALICE_COLLECTION_ID,
+				DAVE_MARKETPLACE_ID,
+				DEFAULT_STARTBLOCK,
+				DEFAULT_ENDBLOCK,
+				DEFAULT_PRICE,
+				DEFAULT_WALLET_LIMIT,
+				BoundedVec::default(),
+				PERCENT_10,
+			);
+			assert_noop!(err, Error::<Test>::CollectionIsClosed);
+		})
+	}
+
+	#[test]
+	fn sale_already_exists() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			create_default_sale();
+
+			let err = OpenEdition::create_sale(
+				origin(ALICE),
+				ALICE_COLLECTION_ID,
+				DAVE_MARKETPLACE_ID,
+				DEFAULT_STARTBLOCK,
+				DEFAULT_ENDBLOCK,
+				DEFAULT_PRICE,
+				DEFAULT_WALLET_LIMIT,
+				BoundedVec::default(),
+				PERCENT_10,
+			);
+			assert_noop!(err, Error::<Test>::SaleAlreadyExists);
+		})
+	}
+
+	#[test]
+	fn sale_duration_is_too_short() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+
+			let err = OpenEdition::create_sale(
+				origin(ALICE),
+				ALICE_COLLECTION_ID,
+				DAVE_MARKETPLACE_ID,
+				DEFAULT_STARTBLOCK,
+				DEFAULT_STARTBLOCK + MIN_SALE_DURATION - 1,
+				DEFAULT_PRICE,
+				DEFAULT_WALLET_LIMIT,
+				BoundedVec::default(),
+				PERCENT_10,
+			);
+			assert_noop!(err, Error::<Test>::SaleDurationIsTooShort);
+		})
+	}
+
+	#[test]
+	fn invalid_wallet_limit() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+
+			let err = OpenEdition::create_sale(
+				origin(ALICE),
+				ALICE_COLLECTION_ID,
+				DAVE_MARKETPLACE_ID,
+				DEFAULT_STARTBLOCK,
+				DEFAULT_ENDBLOCK,
+				DEFAULT_PRICE,
+				0,
+				BoundedVec::default(),
+				PERCENT_10,
+			);
+			assert_noop!(err, Error::<Test>::InvalidWalletLimit);
+		})
+	}
+
+	#[test]
+	fn marketplace_not_found() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+
+			let err = OpenEdition::create_sale(
+				origin(ALICE),
+				ALICE_COLLECTION_ID,
+				INVALID_MARKETPLACE_ID,
+				DEFAULT_STARTBLOCK,
+				DEFAULT_ENDBLOCK,
+				DEFAULT_PRICE,
+				DEFAULT_WALLET_LIMIT,
+				BoundedVec::default(),
+				PERCENT_10,
+			);
+			assert_noop!(err, Error::<Test>::MarketplaceNotFound);
+		})
+	}
+
+	#[test]
+	fn price_cannot_cover_marketplace_fee() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			Marketplace::set_marketplace_configuration(
+				origin(DAVE),
+				DAVE_MARKETPLACE_ID,
+				ConfigOp::Set(CompoundFee::Flat(DEFAULT_PRICE + 1)),
+				ConfigOp::Noop,
+				ConfigOp::Noop,
+				ConfigOp::Noop,
+				ConfigOp::Noop,
+			)
+			.unwrap();
+
+			let err = OpenEdition::create_sale(
+				origin(ALICE),
+				ALICE_COLLECTION_ID,
+				DAVE_MARKETPLACE_ID,
+				DEFAULT_STARTBLOCK,
+				DEFAULT_ENDBLOCK,
+				DEFAULT_PRICE,
+				DEFAULT_WALLET_LIMIT,
+				BoundedVec::default(),
+				PERCENT_10,
+			);
+			assert_noop!(err, Error::<Test>::PriceCannotCoverMarketplaceFee);
+		})
+	}
+}
+
+mod mint {
+	use super::*;
+
+	#[test]
+	fn mint() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			create_default_sale();
+			run_to_block(DEFAULT_STARTBLOCK);
+
+			let alice_balance = Balances::free_balance(ALICE);
+			let bob_balance = Balances::free_balance(BOB);
+			let dave_balance = Balances::free_balance(DAVE);
+			let first_nft_id: NFTId = NFT::next_nft_id();
+
+			let ok = OpenEdition::mint(origin(BOB), ALICE_COLLECTION_ID, 2);
+			assert_ok!(ok);
+
+			// Storage.
+			let amount = DEFAULT_PRICE * 2;
+			let marketplace_cut = PERCENT_20 * amount;
+			let creator_cut = amount - marketplace_cut;
+			let nft_ids: Vec<NFTId> = vec![first_nft_id, first_nft_id + 1];
+
+			for nft_id in nft_ids.iter() {
+				let nft = NFT::nfts(nft_id).unwrap();
+				assert_eq!(nft.owner, BOB);
+				assert_eq!(nft.creator, ALICE);
+				assert_eq!(nft.royalty, PERCENT_10);
+				assert_eq!(nft.collection_id, Some(ALICE_COLLECTION_ID));
+			}
+			let collection = NFT::collections(ALICE_COLLECTION_ID).unwrap();
+			assert_eq!(collection.nfts.to_vec(), nft_ids);
+			assert_eq!(Sales::<Test>::get(ALICE_COLLECTION_ID).unwrap().minted, 2);
+			assert_eq!(MintedBy::<Test>::get(ALICE_COLLECTION_ID, BOB), 2);
+
+			assert_eq!(Balances::free_balance(ALICE), alice_balance + creator_cut);
+			assert_eq!(Balances::free_balance(BOB), bob_balance - amount - NFT_MINT_FEE * 2);
+			assert_eq!(Balances::free_balance(DAVE), dave_balance + marketplace_cut);
+
+			// Events.
+			let event = ternoa_nft::Event::NFTCreated {
+				nft_id: first_nft_id,
+				owner: BOB,
+				offchain_data: BoundedVec::default(),
+				royalty: PERCENT_10,
+				collection_id: Some(ALICE_COLLECTION_ID),
+				is_soulbound: false,
+				mint_fee: NFT_MINT_FEE,
+			};
+			System::assert_has_event(RuntimeEvent::NFT(event));
+			let event = OpenEditionEvent::NFTsMinted {
+				collection_id: ALICE_COLLECTION_ID,
+				buyer: BOB,
+				nft_ids: BoundedVec::try_from(nft_ids).unwrap(),
+				amount,
+				marketplace_cut,
+				creator_cut,
+			};
+			System::assert_last_event(RuntimeEvent::OpenEdition(event));
+		})
+	}
+
+	#[test]
+	fn sale_does_not_exist() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+
+			let err = OpenEdition::mint(origin(BOB), ALICE_COLLECTION_ID, 1);
+			assert_noop!(err, Error::<Test>::SaleDoesNotExist);
+		})
+	}
+
+	#[test]
+	fn sale_not_started() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			create_default_sale();
+
+			let err = OpenEdition::mint(origin(BOB), ALICE_COLLECTION_ID, 1);
+			assert_noop!(err, Error::<Test>::SaleNotStarted);
+		})
+	}
+
+	#[test]
+	fn sale_has_ended() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			create_default_sale();
+
+			// The sale has not been closed by the hook yet.
+			System::set_block_number(DEFAULT_ENDBLOCK);
+
+			let err = OpenEdition::mint(origin(BOB), ALICE_COLLECTION_ID, 1);
+			assert_noop!(err, Error::<Test>::SaleHasEnded);
+		})
+	}
+
+	#[test]
+	fn invalid_quantity() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			create_default_sale();
+			run_to_block(DEFAULT_STARTBLOCK);
+
+			let err = OpenEdition::mint(origin(BOB), ALICE_COLLECTION_ID, 0);
+			assert_noop!(err, Error::<Test>::InvalidQuantity);
+
+			let err = OpenEdition::mint(origin(BOB), ALICE_COLLECTION_ID, MAX_MINT_QUANTITY + 1);
+			assert_noop!(err, Error::<Test>::InvalidQuantity);
+		})
+	}
+
+	#[test]
+	fn wallet_limit_reached() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			create_default_sale();
+			run_to_block(DEFAULT_STARTBLOCK);
+			OpenEdition::mint(origin(BOB), ALICE_COLLECTION_ID, DEFAULT_WALLET_LIMIT - 1).unwrap();
+
+			let err = OpenEdition::mint(origin(BOB), ALICE_COLLECTION_ID, 2);
+			assert_noop!(err, Error::<Test>::WalletLimitReached);
+
+			// Other accounts are not affected by the limit of Bob.
+			assert_ok!(OpenEdition::mint(origin(CHARLIE), ALICE_COLLECTION_ID, 2));
+		})
+	}
+
+	#[test]
+	fn collection_has_reached_limit() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			NFT::limit_collection(origin(ALICE), ALICE_COLLECTION_ID, 1).unwrap();
+			create_default_sale();
+			run_to_block(DEFAULT_STARTBLOCK);
+			OpenEdition::mint(origin(BOB), ALICE_COLLECTION_ID, 1).unwrap();
+
+			let err = OpenEdition::mint(origin(CHARLIE), ALICE_COLLECTION_ID, 1);
+			assert_noop!(err, ternoa_nft::Error::<Test>::CollectionHasReachedLimit);
+		})
+	}
+}
@@ -0,0 +1,68 @@
+// Copyright 2023 Capsule Corp (France) SAS.
+// This file is part of Ternoa.
+
+// Ternoa is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ternoa is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ternoa.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::mock::*;
+use frame_support::BoundedVec;
+use frame_system::RawOrigin;
+use primitives::{marketplace::MarketplaceType, nfts::CollectionId};
+use sp_arithmetic::per_things::Permill;
+
+use crate::{tests::mock, Deadlines, Event as OpenEditionEvent, MintedBy, Sales};
+
+const PERCENT_0: Permill = Permill::from_parts(0);
+const ALICE_COLLECTION_ID: CollectionId = 0;
+const BOB_MARKETPLACE_ID: u32 = 0;
+
+fn origin(account: u64) -> mock::RuntimeOrigin {
+	RawOrigin::Signed(account).into()
+}
+
+#[test]
+fn sale_ends_and_closes_collection() {
+	ExtBuilder::new_build(None).execute_with(|| {
+		let start_block = System::block_number();
+		let end_block = start_block + MIN_SALE_DURATION;
+
+		NFT::create_collection(origin(ALICE), BoundedVec::default(), None).unwrap();
+		Marketplace::create_marketplace(origin(BOB), MarketplaceType::Public).unwrap();
+		OpenEdition::create_sale(
+			origin(ALICE),
+			ALICE_COLLECTION_ID,
+			BOB_MARKETPLACE_ID,
+			start_block,
+			end_block,
+			10,
+			2,
+			BoundedVec::default(),
+			PERCENT_0,
+		)
+		.unwrap();
+		OpenEdition::mint(origin(CHARLIE), ALICE_COLLECTION_ID, 2).unwrap();
+
+		run_to_block(end_block - 1);
+		assert!(Sales::<Test>::contains_key(ALICE_COLLECTION_ID));
+		assert!(!NFT::collections(ALICE_COLLECTION_ID).unwrap().is_closed);
+
+		run_to_block(end_block);
+		assert_eq!(Sales::<Test>::get(ALICE_COLLECTION_ID), None);
+		assert_eq!(Deadlines::<Test>::get().len(), 0);
+		assert_eq!(MintedBy::<Test>::get(ALICE_COLLECTION_ID, CHARLIE), 0);
+		assert!(NFT::collections(ALICE_COLLECTION_ID).unwrap().is_closed);
+
+		let event = OpenEditionEvent::SaleEnded { collection_id: ALICE_COLLECTION_ID, minted: 2 };
+		System::assert_last_event(RuntimeEvent::OpenEdition(event));
+	})
+}
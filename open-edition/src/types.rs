@@ -0,0 +1,147 @@
+// Copyright 2023 Capsule Corp (France) SAS.
+// This file is part of Ternoa.
+
+// Ternoa is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ternoa is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ternoa.  If not, see <http://www.gnu.org/licenses/>.
+
+use frame_support::{traits::Get, BoundedVec, CloneNoBound, PartialEqNoBound, RuntimeDebugNoBound};
+use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+use primitives::{marketplace::MarketplaceId, nfts::CollectionId, CompoundFee, U8BoundedVec};
+use scale_info::TypeInfo;
+use sp_arithmetic::per_things::Permill;
+use sp_std::{fmt::Debug, vec};
+
+#[derive(
+	Encode, Decode, CloneNoBound, PartialEqNoBound, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen,
+)]
+#[codec(mel_bound(AccountId: MaxEncodedLen, BlockNumber: MaxEncodedLen, Balance: MaxEncodedLen))]
+#[scale_info(skip_type_params(OffchainDataLimit))]
+/// Structure to store open edition sale data
+pub struct SaleData<AccountId, BlockNumber, Balance, OffchainDataLimit>
+where
+	AccountId: Clone + PartialEq + Debug,
+	BlockNumber: Clone + PartialEq + Debug + sp_std::cmp::PartialOrd,
+	Balance: Clone + PartialEq + Debug,
+	OffchainDataLimit: Get<u32>,
+{
+	/// The owner of the collection that has opened the sale
+	pub creator: AccountId,
+	/// The marketplace where the sale has been listed
+	pub marketplace_id: MarketplaceId,
+	/// `BlockNumber` at which the sale will accept mints
+	pub start_block: BlockNumber,
+	/// `BlockNumber` at which the sale will no longer accept mints and the collection is closed
+	pub end_block: BlockNumber,
+	/// `Balance` paid for each minted NFT
+	pub price: Balance,
+	/// Maximum number of NFTs that a single account can mint
+	pub wallet_limit: u32,
+	/// Offchain data shared by every minted NFT
+	pub offchain_data: U8BoundedVec<OffchainDataLimit>,
+	/// Royalty of every minted NFT
+	pub royalty: Permill,
+	/// Marketplace commission fee at the time the sale was created
+	pub commission_fee: Option<CompoundFee<Balance>>,
+	/// Number of NFTs minted so far
+	pub minted: u32,
+}
+
+impl<AccountId, BlockNumber, Balance, OffchainDataLimit>
+	SaleData<AccountId, BlockNumber, Balance, OffchainDataLimit>
+where
+	AccountId: Clone + PartialEq + Debug,
+	BlockNumber: Clone + PartialEq + Debug + sp_std::cmp::PartialOrd,
+	Balance: Clone + PartialEq + Debug,
+	OffchainDataLimit: Get<u32>,
+{
+	pub fn is_open(&self, now: BlockNumber) -> bool {
+		self.start_block <= now && now < self.end_block
+	}
+}
+
+#[derive(
+	Encode, Decode, CloneNoBound, PartialEqNoBound, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen,
+)]
+#[codec(mel_bound(BlockNumber: MaxEncodedLen))]
+#[scale_info(skip_type_params(ParallelSaleLimit))]
+/// Sorted list of the blocks at which the sales end
+pub struct DeadlineList<
+	BlockNumber: Clone + PartialEq + Debug + sp_std::cmp::PartialOrd,
+	ParallelSaleLimit: Get<u32>,
+>(pub BoundedVec<(CollectionId, BlockNumber), ParallelSaleLimit>);
+
+impl<BlockNumber, ParallelSaleLimit> DeadlineList<BlockNumber, ParallelSaleLimit>
+where
+	BlockNumber: Clone + PartialEq + Debug + sp_std::cmp::PartialOrd,
+	ParallelSaleLimit: Get<u32>,
+{
+	pub fn insert(
+		&mut self,
+		collection_id: CollectionId,
+		block_number: BlockNumber,
+	) -> Result<(), ()> {
+		let index = self.0.iter().position(|x| x.1 > block_number);
+		let index = index.unwrap_or_else(|| self.0.len());
+
+		self.0.try_insert(index, (collection_id, block_number)).map_err(|_| ())
+	}
+
+	pub fn remove(&mut self, collection_id: CollectionId) -> bool {
+		let index = self.0.iter().position(|x| x.0 == collection_id);
+		if let Some(index) = index {
+			self.0.remove(index);
+			true
+		} else {
+			false
+		}
+	}
+
+	pub fn next(&self, block_number: BlockNumber) -> Option<CollectionId> {
+		let front = self.0.get(0)?;
+		if front.1 <= block_number {
+			Some(front.0)
+		} else {
+			None
+		}
+	}
+
+	pub fn pop_next(&mut self, block_number: BlockNumber) -> Option<CollectionId> {
+		let collection_id = self.next(block_number)?;
+		self.0.remove(0);
+		Some(collection_id)
+	}
+
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+
+	// Benchmark / tests only
+	pub fn bulk_insert(
+		&mut self,
+		collection_id: CollectionId,
+		block_number: BlockNumber,
+		number: u32,
+	) -> Result<(), ()> {
+		self.0.try_extend(vec![(collection_id, block_number); number as usize].into_iter())
+	}
+}
+
+impl<BlockNumber, ParallelSaleLimit> Default for DeadlineList<BlockNumber, ParallelSaleLimit>
+where
+	BlockNumber: Clone + PartialEq + Debug + sp_std::cmp::PartialOrd,
+	ParallelSaleLimit: Get<u32>,
+{
+	fn default() -> Self {
+		Self(BoundedVec::default())
+	}
+}
@@ -269,3 +269,62 @@ where
 		Self { owner, offchain_data, nfts: BoundedVec::default(), limit, is_closed: false }
 	}
 }
+
+/// Account allowed to use an NFT without owning it (ERC-4907 style).
+#[derive(Encode, Decode, Eq, TypeInfo, Clone, PartialEq, RuntimeDebug, MaxEncodedLen)]
+pub struct NFTUser<AccountId, BlockNumber> {
+	/// Current user of the NFT
+	pub user: AccountId,
+	/// Block at which the usage right expires
+	pub expires: BlockNumber,
+}
+
+impl<AccountId, BlockNumber> NFTUser<AccountId, BlockNumber>
+where
+	AccountId: Clone + PartialEq + Debug,
+	BlockNumber: Clone + PartialEq + Debug + sp_std::cmp::PartialOrd,
+{
+	pub fn new(user: AccountId, expires: BlockNumber) -> Self {
+		Self { user, expires }
+	}
+
+	pub fn is_active(&self, now: &BlockNumber) -> bool {
+		*now < self.expires
+	}
+}
+
+/// What the user of a rented NFT is allowed to do with it.
+#[derive(
+	Encode, Decode, Eq, Default, TypeInfo, Clone, Copy, PartialEq, RuntimeDebug, MaxEncodedLen,
+)]
+pub enum UsageScope {
+	/// The user can only read the NFT and its capsule.
+	#[default]
+	ViewOnly,
+	/// The user can also update the capsule offchain data.
+	Use,
+	/// The user can also request the capsule key to be updated.
+	Full,
+}
+
+impl UsageScope {
+	pub fn can_set_capsule_offchain_data(&self) -> bool {
+		matches!(self, UsageScope::Use | UsageScope::Full)
+	}
+
+	pub fn can_update_capsule_key(&self) -> bool {
+		matches!(self, UsageScope::Full)
+	}
+}
+
+/// Identifier of the set of shards held or to be re-synced by the enclave of a cluster slot.
+pub type ShardSetId = u32;
+
+/// Kind of NFT secret a TEE enclave holds a shard of.
+#[derive(Encode, Decode, Eq, TypeInfo, Clone, Copy, PartialEq, RuntimeDebug, MaxEncodedLen)]
+pub enum ShardKind {
+	/// Shard of the secret of a secret NFT.
+	Secret,
+	/// Shard of the key of a capsule.
+	Capsule,
+}
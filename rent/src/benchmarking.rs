@@ -65,16 +65,36 @@ pub fn prepare_benchmarks<T: Config>() -> () {
 			period_length: 2u32.into(),
 			max_duration: Some(10u32.into()),
 			is_changeable: true,
+			grace_period: 0u32.into(),
 		}),
 		AcceptanceType::AutoAcceptance(None),
 		true,
 		RentFee::Tokens(rent_fee),
 		CancellationFee::FixedTokens(cancellation_fee),
 		CancellationFee::FixedTokens(cancellation_fee),
+		false,
+		None,
 	);
 	assert_ok!(ok);
 }
 
+pub fn prepare_running_fixed_contract<T: Config>() -> () {
+	let ok = Rent::<T>::create_contract(
+		origin::<T>("ALICE").into(),
+		NFT_ID_1,
+		DurationInput::Fixed(10u32.into()),
+		AcceptanceType::AutoAcceptance(None),
+		true,
+		RentFee::Tokens(100u32.into()),
+		CancellationFee::None,
+		CancellationFee::None,
+		true,
+		None,
+	);
+	assert_ok!(ok);
+	assert_ok!(Rent::<T>::rent(origin::<T>("BOB").into(), NFT_ID_1, 0u32.into()));
+}
+
 benchmarks! {
 	create_contract {
 		let s in 0 .. T::SimultaneousContractLimit::get();
@@ -93,9 +113,10 @@ benchmarks! {
 			period_length: 2u32.into(),
 			max_duration: Some(10u32.into()),
 			is_changeable: false,
+			grace_period: 0u32.into(),
 		} ;
 
-	}: _(org, NFT_ID_1, DurationInput::Subscription(sub), AcceptanceType::AutoAcceptance(None), true, RentFee::Tokens(1000u32.into()), CancellationFee::FixedTokens(100u32.into()),CancellationFee::FixedTokens(100u32.into()))
+	}: _(org, NFT_ID_1, DurationInput::Subscription(sub), AcceptanceType::AutoAcceptance(None), true, RentFee::Tokens(1000u32.into()), CancellationFee::FixedTokens(100u32.into()),CancellationFee::FixedTokens(100u32.into()), false, None)
 	verify {
 		// Get The contract.
 		let contract = Rent::<T>::contracts(NFT_ID_1).unwrap();
@@ -187,6 +208,8 @@ benchmarks! {
 			RentFee::Tokens(100u32.into()),
 			CancellationFee::None,
 			CancellationFee::None,
+			false,
+			None,
 		);
 		assert_ok!(ok);
 
@@ -195,7 +218,7 @@ benchmarks! {
 	verify {
 		// Get The offer.
 		let offers = Rent::<T>::offers(NFT_ID_1).unwrap();
-		assert!(offers.contains(&bob))
+		assert!(offers.iter().any(|x| x.rentee == bob))
 	}
 
 	accept_rent_offer {
@@ -220,11 +243,13 @@ benchmarks! {
 			RentFee::Tokens(100u32.into()),
 			CancellationFee::None,
 			CancellationFee::None,
+			false,
+			None,
 		);
 		assert_ok!(ok);
 
 		Rent::<T>::benchmark_fill_available_queue(new_contracts_amount, 100u32.into()).unwrap();
-		Rent::<T>::make_rent_offer(origin::<T>("BOB").into(), NFT_ID_1, 0u32.into()).unwrap();
+		Rent::<T>::make_rent_offer(origin::<T>("BOB").into(), NFT_ID_1, 0u32.into(), None, None).unwrap();
 	}: _(origin::<T>("ALICE"), NFT_ID_1, bob.clone())
 	verify {
 		let contract = Rent::<T>::contracts(NFT_ID_1).unwrap();
@@ -253,17 +278,19 @@ benchmarks! {
 			RentFee::Tokens(100u32.into()),
 			CancellationFee::None,
 			CancellationFee::None,
+			false,
+			None,
 		);
 		assert_ok!(ok);
 
 		Rent::<T>::benchmark_fill_offers(new_offer_amount, NFT_ID_1, alice.clone()).unwrap();
-		Rent::<T>::make_rent_offer(origin::<T>("BOB").into(), NFT_ID_1, 0u32.into()).unwrap();
+		Rent::<T>::make_rent_offer(origin::<T>("BOB").into(), NFT_ID_1, 0u32.into(), None, None).unwrap();
 
 	}: _(origin::<T>("BOB"), NFT_ID_1)
 	verify {
 		// Check that offer has been removed
 		let offers = Rent::<T>::offers(NFT_ID_1).unwrap();
-		assert!(!offers.contains(&bob))
+		assert!(!offers.iter().any(|x| x.rentee == bob))
 	}
 
 	change_subscription_terms {
@@ -307,6 +334,116 @@ benchmarks! {
 		let sub = contract.duration.as_subscription().unwrap();
 		assert_eq!(sub.new_terms, false);
 	}
+
+	claim_escrowed_rent_fee {
+		prepare_benchmarks::<T>();
+
+		let ok = Rent::<T>::create_contract(
+			origin::<T>("ALICE").into(),
+			NFT_ID_1,
+			DurationInput::Fixed(10u32.into()),
+			AcceptanceType::AutoAcceptance(None),
+			true,
+			RentFee::EscrowedTokens(1000u32.into()),
+			CancellationFee::None,
+			CancellationFee::None,
+			false,
+			None,
+		);
+		assert_ok!(ok);
+
+		Rent::<T>::rent(origin::<T>("BOB").into(), NFT_ID_1, 0u32.into()).unwrap();
+		frame_system::Pallet::<T>::set_block_number(5u32.into());
+	}: _(origin::<T>("ALICE"), NFT_ID_1)
+	verify {
+		let escrowed = Rent::<T>::escrowed_rent_fees(NFT_ID_1).unwrap();
+		assert!(escrowed < 1000u32.into());
+	}
+
+	create_sublet {
+		prepare_benchmarks::<T>();
+		prepare_running_fixed_contract::<T>();
+
+		let bob: T::AccountId = get_account::<T>("BOB");
+	}: _(origin::<T>("BOB"), NFT_ID_1, 5u32.into(), 100u32.into())
+	verify {
+		let sublet = Rent::<T>::sublets(NFT_ID_1).unwrap();
+		assert_eq!(sublet.renter, bob);
+	}
+
+	rent_sublet {
+		let s in 0 .. T::SimultaneousContractLimit::get() - 1;
+		prepare_benchmarks::<T>();
+		prepare_running_fixed_contract::<T>();
+
+		let charlie: T::AccountId = get_account::<T>("CHARLIE");
+		T::Currency::make_free_balance_be(&charlie, BalanceOf::<T>::max_value() / 2u32.into());
+		Rent::<T>::create_sublet(origin::<T>("BOB").into(), NFT_ID_1, 5u32.into(), 100u32.into()).unwrap();
+		Rent::<T>::benchmark_fill_sublet_queue(s, 100u32.into()).unwrap();
+	}: _(origin::<T>("CHARLIE"), NFT_ID_1)
+	verify {
+		let sublet = Rent::<T>::sublets(NFT_ID_1).unwrap();
+		assert_eq!(sublet.rentee, Some(charlie));
+	}
+
+	cancel_sublet {
+		prepare_benchmarks::<T>();
+		prepare_running_fixed_contract::<T>();
+
+		Rent::<T>::create_sublet(origin::<T>("BOB").into(), NFT_ID_1, 5u32.into(), 100u32.into()).unwrap();
+	}: _(origin::<T>("BOB"), NFT_ID_1)
+	verify {
+		assert!(Rent::<T>::sublets(NFT_ID_1).is_none());
+	}
+
+	propose_extension {
+		prepare_benchmarks::<T>();
+		prepare_running_fixed_contract::<T>();
+
+		let alice: T::AccountId = get_account::<T>("ALICE");
+	}: _(origin::<T>("ALICE"), NFT_ID_1, 5u32.into(), 100u32.into())
+	verify {
+		let proposal = Rent::<T>::extensions(NFT_ID_1).unwrap();
+		assert_eq!(proposal.proposer, alice);
+	}
+
+	accept_extension {
+		let s in 0 .. T::SimultaneousContractLimit::get() - 2;
+		prepare_benchmarks::<T>();
+		prepare_running_fixed_contract::<T>();
+
+		Rent::<T>::benchmark_fill_available_queue(s, 100u32.into()).unwrap();
+		Rent::<T>::propose_extension(origin::<T>("ALICE").into(), NFT_ID_1, 5u32.into(), 100u32.into()).unwrap();
+	}: _(origin::<T>("BOB"), NFT_ID_1, 5u32.into(), 100u32.into())
+	verify {
+		let contract = Rent::<T>::contracts(NFT_ID_1).unwrap();
+		assert_eq!(contract.duration, Duration::Fixed(15u32.into()));
+	}
+
+	bundle_nfts {
+		let s in 1 .. T::BundleSizeLimit::get();
+		prepare_benchmarks::<T>();
+
+		let alice: T::AccountId = get_account::<T>("ALICE");
+		let mut nft_ids: BoundedVec<NFTId, T::BundleSizeLimit> = BoundedVec::default();
+		for _i in 0..s {
+			let data = BoundedVec::default();
+			let nft_id = T::NFTExt::create_nft(alice.clone(), data, PERCENT_100, None, false).unwrap();
+			nft_ids.try_push(nft_id).unwrap();
+		}
+	}: _(origin::<T>("ALICE"), NFT_ID_0, nft_ids)
+	verify {
+		let bundle = Rent::<T>::bundled_nfts(NFT_ID_0).unwrap();
+		assert_eq!(bundle.len(), s as usize);
+	}
+
+	set_usage_scope {
+		prepare_benchmarks::<T>();
+	}: _(origin::<T>("ALICE"), NFT_ID_0, UsageScope::Full)
+	verify {
+		let contract = Rent::<T>::contracts(NFT_ID_0).unwrap();
+		assert_eq!(contract.usage_scope, UsageScope::Full);
+	}
 }
 
 impl_benchmark_test_suite!(Rent, crate::tests::mock::new_test_ext(), crate::tests::mock::Test);
@@ -17,6 +17,7 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+mod migrations;
 #[cfg(test)]
 mod tests;
 mod types;
@@ -34,21 +35,26 @@ use frame_support::{
 	traits::{
 		Currency,
 		ExistenceRequirement::{AllowDeath, KeepAlive},
-		Get, StorageVersion, WithdrawReasons,
+		Get, OnRuntimeUpgrade, ReservableCurrency, StorageVersion, WithdrawReasons,
 	},
 	BoundedVec, PalletId,
 };
 use frame_system::pallet_prelude::*;
-use primitives::nfts::{
-	NFTId,
-	NFTStateModifiers::{self, *},
+use primitives::{
+	marketplace::MarketplaceId,
+	nfts::{
+		NFTId,
+		NFTStateModifiers::{self, *},
+		NFTUser, UsageScope,
+	},
+	CompoundFee,
 };
 use sp_runtime::{
 	traits::{AccountIdConversion, CheckedSub, Saturating},
 	Permill,
 };
 use sp_std::prelude::*;
-use ternoa_common::traits::NFTExt;
+use ternoa_common::traits::{MarketplaceExt, NFTExt, RentExt};
 
 pub type BalanceOf<T> =
 	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
@@ -61,8 +67,23 @@ pub type RentContractDataOf<T> = RentContractData<
 	BalanceOf<T>,
 	<T as Config>::AccountSizeLimit,
 >;
+pub type RentOfferOf<T> = RentOffer<
+	<T as frame_system::Config>::AccountId,
+	<T as frame_system::Config>::BlockNumber,
+	BalanceOf<T>,
+>;
+pub type ExtensionProposalOf<T> = ExtensionProposal<
+	<T as frame_system::Config>::AccountId,
+	<T as frame_system::Config>::BlockNumber,
+	BalanceOf<T>,
+>;
+pub type SubletContractDataOf<T> = SubletContractData<
+	<T as frame_system::Config>::AccountId,
+	<T as frame_system::Config>::BlockNumber,
+	BalanceOf<T>,
+>;
 
-const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -82,10 +103,13 @@ pub mod pallet {
 		type WeightInfo: WeightInfo;
 
 		/// Currency type.
-		type Currency: Currency<Self::AccountId>;
+		type Currency: ReservableCurrency<Self::AccountId>;
 
 		/// Link to the NFT pallet.
-		type NFTExt: NFTExt<AccountId = Self::AccountId>;
+		type NFTExt: NFTExt<AccountId = Self::AccountId, BlockNumber = Self::BlockNumber>;
+
+		/// Link to the Marketplace pallet.
+		type MarketplaceExt: MarketplaceExt<AccountId = Self::AccountId, Balance = BalanceOf<Self>>;
 
 		// Constants
 		/// The minimum amount required to keep an account open.
@@ -115,6 +139,10 @@ pub mod pallet {
 		/// Maximum number of blocks that a contract can last for.
 		#[pallet::constant]
 		type MaximumContractDurationLimit: Get<u32>;
+
+		/// Maximum number of NFTs that can be bundled with the NFT of a rent contract.
+		#[pallet::constant]
+		type BundleSizeLimit: Get<u32>;
 	}
 
 	/// Data related to rent contracts.
@@ -141,10 +169,51 @@ pub mod pallet {
 		_,
 		Blake2_128Concat,
 		NFTId,
-		AccountList<T::AccountId, T::AccountSizeLimit>,
+		BoundedVec<RentOfferOf<T>, T::AccountSizeLimit>,
 		OptionQuery,
 	>;
 
+	/// Pending extension proposals of running fixed contracts.
+	#[pallet::storage]
+	#[pallet::getter(fn extensions)]
+	pub type Extensions<T: Config> =
+		StorageMap<_, Blake2_128Concat, NFTId, ExtensionProposalOf<T>, OptionQuery>;
+
+	/// Data related to sublets of running contracts.
+	#[pallet::storage]
+	#[pallet::getter(fn sublets)]
+	pub type Sublets<T: Config> =
+		StorageMap<_, Blake2_128Concat, NFTId, SubletContractDataOf<T>, OptionQuery>;
+
+	/// Running sublets ordered by end block.
+	#[pallet::storage]
+	#[pallet::getter(fn sublet_queue)]
+	pub type SubletQueue<T: Config> =
+		StorageValue<_, Queue<T::BlockNumber, T::SimultaneousContractLimit>, ValueQuery>;
+
+	/// Escrowed rent fees of running contracts that are not yet released to the renter.
+	#[pallet::storage]
+	#[pallet::getter(fn escrowed_rent_fees)]
+	pub type EscrowedRentFees<T: Config> =
+		StorageMap<_, Blake2_128Concat, NFTId, BalanceOf<T>, OptionQuery>;
+
+	/// Escrowed rent fees of running sublets that are not yet released to the sublet renter.
+	#[pallet::storage]
+	#[pallet::getter(fn escrowed_sublet_fees)]
+	pub type EscrowedSubletFees<T: Config> =
+		StorageMap<_, Blake2_128Concat, NFTId, BalanceOf<T>, OptionQuery>;
+
+	/// NFTs rented together with the NFT of a contract.
+	#[pallet::storage]
+	#[pallet::getter(fn bundled_nfts)]
+	pub type BundledNFTs<T: Config> =
+		StorageMap<_, Blake2_128Concat, NFTId, BoundedVec<NFTId, T::BundleSizeLimit>, OptionQuery>;
+
+	/// Mapping of bundled NFTs to the NFT of the contract they are rented with.
+	#[pallet::storage]
+	#[pallet::getter(fn bundle_parents)]
+	pub type BundleParents<T: Config> = StorageMap<_, Blake2_128Concat, NFTId, NFTId, OptionQuery>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -158,13 +227,20 @@ pub mod pallet {
 			rent_fee: RentFee<BalanceOf<T>>,
 			renter_cancellation_fee: CancellationFee<BalanceOf<T>>,
 			rentee_cancellation_fee: CancellationFee<BalanceOf<T>>,
+			allow_sublet: bool,
+			marketplace_id: Option<MarketplaceId>,
 		},
 		/// Contract was accepted and has started.
-		ContractStarted { nft_id: NFTId, rentee: T::AccountId },
+		ContractStarted { nft_id: NFTId, rentee: T::AccountId, marketplace_cut: BalanceOf<T> },
 		/// Contract was revoked by either renter or rentee.
 		ContractRevoked { nft_id: NFTId, revoked_by: T::AccountId },
 		/// An offer was made for manual acceptance rent contract.
-		ContractOfferCreated { nft_id: NFTId, rentee: T::AccountId },
+		ContractOfferCreated {
+			nft_id: NFTId,
+			rentee: T::AccountId,
+			rent_fee: Option<BalanceOf<T>>,
+			duration: Option<T::BlockNumber>,
+		},
 		/// An offer was retracted for manual acceptance rent contract.
 		ContractOfferRetracted { nft_id: NFTId, rentee: T::AccountId },
 		/// A contract subscription's terms were changed by renter.
@@ -179,12 +255,63 @@ pub mod pallet {
 		ContractSubscriptionTermsAccepted { nft_id: NFTId },
 		/// A contract has ended.
 		ContractEnded { nft_id: NFTId, revoked_by: Option<T::AccountId> },
-		/// A contract's subscription period has started.
-		ContractSubscriptionPeriodStarted { nft_id: NFTId },
+		/// A contract's subscription period has started and was paid.
+		ContractSubscriptionPeriodStarted {
+			nft_id: NFTId,
+			period: u32,
+			marketplace_cut: BalanceOf<T>,
+		},
+		/// A subscription period payment failed and will be retried until the grace period ends.
+		SubscriptionPaymentFailed { nft_id: NFTId, period: u32, grace_end: T::BlockNumber },
+		/// An overdue subscription period payment was successfully retried.
+		SubscriptionPaymentRecovered { nft_id: NFTId, period: u32, marketplace_cut: BalanceOf<T> },
 		/// A contract available for sale was expired before its acceptance.
 		ContractExpired { nft_id: NFTId },
 		/// Contract was canceled.
 		ContractCanceled { nft_id: NFTId },
+		/// Part of an escrowed rent fee was released to the renter.
+		EscrowedRentFeeReleased { nft_id: NFTId, renter: T::AccountId, amount: BalanceOf<T> },
+		/// The unused part of an escrowed rent fee was refunded to the rentee.
+		EscrowedRentFeeRefunded { nft_id: NFTId, rentee: T::AccountId, amount: BalanceOf<T> },
+		/// The rentee of a running contract created a sublet.
+		SubletCreated {
+			nft_id: NFTId,
+			renter: T::AccountId,
+			parent_renter: T::AccountId,
+			duration: T::BlockNumber,
+			rent_fee: BalanceOf<T>,
+		},
+		/// A sublet was rented and has started.
+		SubletStarted { nft_id: NFTId, rentee: T::AccountId },
+		/// A sublet was canceled before being rented.
+		SubletCanceled { nft_id: NFTId },
+		/// A sublet has ended, either on its own or because its parent contract ended.
+		SubletEnded { nft_id: NFTId, parent_ended: bool },
+		/// A sublet reached its end block but the NFT usage could not be given back to the
+		/// rentee of the parent contract.
+		SubletEndFailed { nft_id: NFTId, error: DispatchError },
+		/// The escrowed rent fee of a sublet was released to the sublet renter.
+		SubletRentFeeReleased { nft_id: NFTId, renter: T::AccountId, amount: BalanceOf<T> },
+		/// The unused part of a sublet rent fee was refunded because the parent contract ended.
+		SubletRentFeeRefunded { nft_id: NFTId, rentee: T::AccountId, amount: BalanceOf<T> },
+		/// A contract participant proposed to extend the contract.
+		ExtensionProposed {
+			nft_id: NFTId,
+			proposer: T::AccountId,
+			extra_blocks: T::BlockNumber,
+			extra_fee: BalanceOf<T>,
+		},
+		/// A contract extension was accepted and the contract duration was updated.
+		ExtensionAccepted {
+			nft_id: NFTId,
+			duration: T::BlockNumber,
+			extra_fee: BalanceOf<T>,
+			marketplace_cut: BalanceOf<T>,
+		},
+		/// NFTs were bundled with the NFT of a contract.
+		NFTsBundled { nft_id: NFTId, nft_ids: BoundedVec<NFTId, T::BundleSizeLimit> },
+		/// The usage scope granted to the rentee of a contract was changed.
+		UsageScopeSet { nft_id: NFTId, usage_scope: UsageScope },
 	}
 
 	#[pallet::error]
@@ -275,10 +402,55 @@ pub mod pallet {
 		CannotMakeOfferOnRunningContract,
 		/// Offer from an account ID already exists
 		OfferAlreadyExists,
+		/// The grace period must be shorter than the subscription period.
+		GracePeriodTooLong,
+		/// The contract has no escrowed rent fee.
+		NoEscrowedRentFee,
+		/// The contract does not allow sublets.
+		SubletNotAllowed,
+		/// The contract already has a sublet.
+		SubletAlreadyExists,
+		/// The sublet was not found.
+		SubletNotFound,
+		/// A sublet must end before its parent contract.
+		SubletExceedsContractDuration,
+		/// Only fixed contracts can be extended.
+		ContractIsNotFixed,
+		/// No extension was proposed for that contract.
+		NoExtensionProposed,
+		/// The extension cannot be accepted by the participant who proposed it.
+		CannotAcceptOwnExtension,
+		/// Only token rent fees can be proposed in an offer.
+		OfferedRentFeeNotAllowed,
+		/// Cannot bundle NFTs with a running contract.
+		CannotBundleRunningContract,
+		/// The NFT is already part of the contract.
+		NFTAlreadyBundled,
+		/// The contract cannot hold more NFTs. Maximum limit reached.
+		BundleSizeLimitReached,
+		/// Bundled NFT is not in a valid state.
+		BundledNFTNotInValidState,
+		/// The marketplace was not found.
+		MarketplaceNotFound,
+		/// The caller is not allowed to list on the marketplace.
+		NotAllowedToList,
 	}
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			let mut weight = Weight::zero();
+
+			let version = StorageVersion::get::<Pallet<T>>();
+			if version == StorageVersion::new(2) {
+				weight = <migrations::v3::MigrationV3<T> as OnRuntimeUpgrade>::on_runtime_upgrade();
+
+				StorageVersion::put::<Pallet<T>>(&StorageVersion::new(3));
+			}
+
+			weight
+		}
+
 		// Basic hooks
 		/// Weight: see `begin_block`
 		fn on_initialize(now: T::BlockNumber) -> Weight {
@@ -287,6 +459,20 @@ pub mod pallet {
 			let mut current_actions = 0;
 			let max_actions = T::ActionsInBlockLimit::get();
 
+			// Sublet queue management
+			while let Some(nft_id) = SubletQueue::<T>::get().next(now) {
+				if let Err(error) = Self::end_sublet(nft_id, false) {
+					Self::deposit_event(Event::SubletEndFailed { nft_id, error });
+				}
+
+				read += 3;
+				write += 3;
+				current_actions += 1;
+				if current_actions >= max_actions {
+					break
+				}
+			}
+
 			let mut queues = Queues::<T>::get();
 			// Fixed queue management
 			while let Some(nft_id) = queues.fixed_queue.pop_next(now) {
@@ -304,11 +490,9 @@ pub mod pallet {
 			}
 
 			// Subscription queue management
-			while let Some(nft_id) = queues.subscription_queue.pop_next(now) {
-				if let Some(block_number) = Self::handle_subscription_contract(nft_id, &now) {
+			while let Some((nft_id, due)) = queues.subscription_queue.pop_next_scheduled(now) {
+				if let Some(block_number) = Self::handle_subscription_contract(nft_id, &due, &now) {
 					_ = queues.subscription_queue.insert(nft_id, block_number);
-					let event = Event::ContractSubscriptionPeriodStarted { nft_id };
-					Self::deposit_event(event);
 				} else {
 					_ = Self::handle_finished_or_unused_contract(nft_id);
 					let event = Event::ContractEnded { nft_id, revoked_by: None };
@@ -361,6 +545,8 @@ pub mod pallet {
 			rent_fee: RentFee<BalanceOf<T>>,
 			renter_cancellation_fee: CancellationFee<BalanceOf<T>>,
 			rentee_cancellation_fee: CancellationFee<BalanceOf<T>>,
+			allow_sublet: bool,
+			marketplace_id: Option<MarketplaceId>,
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 			let pallet = Self::account_id();
@@ -376,6 +562,16 @@ pub mod pallet {
 				Error::<T>::ContractNFTNotInAValidState
 			);
 
+			let mut commission_fee = None;
+			if let Some(marketplace_id) = marketplace_id {
+				let marketplace = T::MarketplaceExt::get_marketplace(marketplace_id)
+					.ok_or(Error::<T>::MarketplaceNotFound)?;
+				marketplace
+					.allowed_to_list(&who, nft.collection_id)
+					.ok_or(Error::<T>::NotAllowedToList)?;
+				commission_fee = marketplace.commission_fee;
+			}
+
 			let duration_limit: T::BlockNumber = T::MaximumContractDurationLimit::get().into();
 			let duration = duration.to_duration(duration_limit.clone());
 			let full_duration = *duration.get_full_duration();
@@ -387,6 +583,12 @@ pub mod pallet {
 				Error::<T>::DurationExceedsMaximumLimit
 			);
 			ensure!(block_duration_or_period > 0u32.into(), Error::<T>::DurationInvalid);
+			if let Some(subscription) = duration.as_subscription() {
+				ensure!(
+					subscription.grace_period < subscription.period_length,
+					Error::<T>::GracePeriodTooLong
+				);
+			}
 			duration
 				.allows_rent_fee(&rent_fee)
 				.ok_or(Error::<T>::DurationAndRentFeeMismatch)?;
@@ -456,6 +658,9 @@ pub mod pallet {
 				renter_cancellation_fee.clone(),
 				rentee_cancellation_fee.clone(),
 				now,
+				allow_sublet,
+				marketplace_id,
+				commission_fee,
 			);
 			Contracts::<T>::insert(nft_id, contract);
 
@@ -472,6 +677,8 @@ pub mod pallet {
 				rent_fee,
 				renter_cancellation_fee,
 				rentee_cancellation_fee,
+				allow_sublet,
+				marketplace_id,
 			};
 			Self::deposit_event(event);
 
@@ -491,14 +698,14 @@ pub mod pallet {
 
 			// Storage Activity 📦
 			// 1. Remove Contract From Queue
-			// 2. Remove Offers from Storage
+			// 2. Refund Offers and remove them from Storage
 			// 3. Return Renter Cancellation NFT & Tokens
 			// 4. Remove Contract from Storage
-			// 5. Remove Rented state from NFT
+			// 5. Remove Rented state from NFT and bundled NFTs
 			Queues::<T>::mutate(|x| {
 				x.remove(nft_id, QueueKind::Available);
 			});
-			Offers::<T>::remove(nft_id);
+			Self::refund_offers(nft_id);
 			Self::handle_finished_or_unused_contract(nft_id)?;
 
 			// Event 🎁
@@ -535,9 +742,11 @@ pub mod pallet {
 			// `create_contract`, `rent` or `make_rent_offer` mess up things!!!
 			// 1. Return damaged party cancellation fee
 			// 2. Send the offender cancellation fee to damaged party
-			// 3. Remove Contract from Queue
-			// 4. Remove Contract from Storage
-			// 5. Remove Rented state from NFT
+			// 3. Release the earned escrowed rent fee and refund the unused part to rentee
+			// 4. End the sublet and remove the extension proposal if there are any
+			// 5. Remove Contract from Queue
+			// 6. Remove Contract from Storage
+			// 7. Remove Rented state from NFT and bundled NFTs
 			let (deposited_fee, price_to_pay) = match is_renter {
 				true => ((rentee_cancellation, rentee), (renter_cancellation, rentee)),
 				false => ((renter_cancellation, renter), (rentee_cancellation, renter)),
@@ -550,6 +759,10 @@ pub mod pallet {
 			} else {
 				Self::return_cancellation_fee(price_to_pay.0, price_to_pay.1)?;
 			}
+			Self::release_escrowed_rent_fee(nft_id, &contract, &now)?;
+			Self::refund_escrowed_rent_fee(nft_id, rentee)?;
+			Self::end_sublet(nft_id, true)?;
+			Extensions::<T>::remove(nft_id);
 
 			Queues::<T>::mutate(|queues| {
 				queues.remove(nft_id, contract.duration.queue_kind());
@@ -560,6 +773,8 @@ pub mod pallet {
 				_ = nft.set_state(Rented, false);
 				Ok(())
 			})?;
+			Self::set_contract_user(nft_id, None)?;
+			Self::release_bundled_nfts(nft_id)?;
 
 			// Event 🎁
 			let event = Event::ContractRevoked { nft_id, revoked_by: who };
@@ -579,6 +794,7 @@ pub mod pallet {
 			let who = ensure_signed(origin)?;
 			let pallet = Self::account_id();
 			let now = frame_system::Pallet::<T>::block_number();
+			let mut marketplace_cut: BalanceOf<T> = 0u32.into();
 
 			Contracts::<T>::try_mutate(nft_id, |x| -> DispatchResult {
 				let contract = x.as_mut().ok_or(Error::<T>::ContractNotFound)?;
@@ -601,9 +817,10 @@ pub mod pallet {
 				// Storage Activity 📦
 				// 1. Take Rent and Cancellation Fee from Caller
 				// 2. Move Contract Queue from Available to Active
-				// 3. Remove Offers
+				// 3. Refund Offers and remove them
 				// 4. Set Contract Start Block and Rentee
-				Self::take_rent_and_cancellation_fee(&who, &pallet, &contract)?;
+				marketplace_cut =
+					Self::take_rent_and_cancellation_fee(nft_id, &who, &pallet, &contract)?;
 
 				Queues::<T>::mutate(|queues| -> DispatchResult {
 					queues.remove(nft_id, QueueKind::Available);
@@ -616,28 +833,34 @@ pub mod pallet {
 
 					Ok(())
 				})?;
-				Offers::<T>::remove(nft_id);
+				Self::refund_offers(nft_id);
 
 				contract.rentee = Some(who.clone());
 				contract.start_block = Some(now);
 
+				let expires = now + *contract.duration.get_full_duration();
+				Self::set_contract_user(nft_id, Some(NFTUser::new(who.clone(), expires)))?;
+
 				Ok(())
 			})?;
 
 			// Event 🎁
-			let event = Event::ContractStarted { nft_id, rentee: who };
+			let event = Event::ContractStarted { nft_id, rentee: who, marketplace_cut };
 			Self::deposit_event(event);
 
 			Ok(().into())
 		}
 
-		/// Make a offer.
+		/// Make a offer, optionally proposing a rent fee and a duration. The offered rent fee is
+		/// reserved until the offer is accepted, retracted or refunded.
 		#[pallet::call_index(4)]
 		#[pallet::weight(T::WeightInfo::make_rent_offer(Queues::<T>::get().size() as u32))]
 		pub fn make_rent_offer(
 			origin: OriginFor<T>,
 			nft_id: NFTId,
 			signed_creation_block: T::BlockNumber,
+			rent_fee: Option<BalanceOf<T>>,
+			duration: Option<T::BlockNumber>,
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 
@@ -648,12 +871,11 @@ pub mod pallet {
 
 				let offers = x.as_mut().ok_or(Error::<T>::NoOffersForThisContract)?;
 
-				if offers.iter().any(|offer| offer == &who) {
+				if offers.iter().any(|offer| offer.rentee == who) {
 					return Err(Error::<T>::OfferAlreadyExists.into())
 				}
 
 				let contract = Contracts::<T>::get(nft_id).ok_or(Error::<T>::ContractNotFound)?;
-				let rent_fee = &contract.rent_fee;
 				let cancellation_fee = &contract.rentee_cancellation_fee;
 
 				// Checks ✅
@@ -670,8 +892,23 @@ pub mod pallet {
 				if let Some(list) = contract.acceptance_type.get_allow_list() {
 					ensure!(list.contains(&who), Error::<T>::NotWhitelisted);
 				}
+				if rent_fee.is_some() {
+					ensure!(
+						contract.rent_fee.get_balance().is_some(),
+						Error::<T>::OfferedRentFeeNotAllowed
+					);
+				}
+				if let Some(duration) = duration {
+					let duration_limit: T::BlockNumber =
+						T::MaximumContractDurationLimit::get().into();
+					let is_fixed = matches!(contract.duration, Duration::Fixed(_));
+					ensure!(is_fixed, Error::<T>::ContractIsNotFixed);
+					ensure!(duration <= duration_limit, Error::<T>::DurationExceedsMaximumLimit);
+					ensure!(duration > 0u32.into(), Error::<T>::DurationInvalid);
+				}
 
-				let rent_balance = rent_fee.get_balance().unwrap_or(0u32.into());
+				let rent_balance =
+					rent_fee.or(contract.rent_fee.get_balance()).unwrap_or(0u32.into());
 				let cancel_balance = cancellation_fee.get_balance().unwrap_or(0u32.into());
 				ensure!(
 					Self::balance_check(&who, rent_balance),
@@ -686,7 +923,7 @@ pub mod pallet {
 					Error::<T>::NotEnoughFundsForFees
 				);
 
-				let maybe_rent_nft = rent_fee.get_nft();
+				let maybe_rent_nft = contract.rent_fee.get_nft();
 				let maybe_cancel_nft = cancellation_fee.get_nft();
 				if let Some(nft_id) = &maybe_rent_nft {
 					let nft = T::NFTExt::get_nft(*nft_id).ok_or(Error::<T>::RentNFTNotFound)?;
@@ -706,13 +943,17 @@ pub mod pallet {
 					);
 				}
 
-				offers.try_push(who.clone()).map_err(|_| Error::<T>::MaximumOffersReached)?;
+				T::Currency::reserve(&who, rent_balance)
+					.map_err(|_| Error::<T>::NotEnoughFundsForRentFee)?;
+				let offer =
+					RentOffer { rentee: who.clone(), rent_fee, duration, reserved: rent_balance };
+				offers.try_push(offer).map_err(|_| Error::<T>::MaximumOffersReached)?;
 
 				Ok(())
 			})?;
 
 			// Event 🎁
-			let event = Event::ContractOfferCreated { nft_id, rentee: who };
+			let event = Event::ContractOfferCreated { nft_id, rentee: who, rent_fee, duration };
 			Self::deposit_event(event);
 
 			Ok(().into())
@@ -728,23 +969,28 @@ pub mod pallet {
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 			let now = frame_system::Pallet::<T>::block_number();
+			let mut marketplace_cut: BalanceOf<T> = 0u32.into();
 
 			Contracts::<T>::try_mutate(nft_id, |x| -> DispatchResult {
 				let pallet = Self::account_id();
 				let contract = x.as_mut().ok_or(Error::<T>::ContractNotFound)?;
 				let offers = Offers::<T>::get(nft_id).ok_or(Error::<T>::NoOffersForThisContract)?;
-				let offer_found = offers.contains(&rentee);
+				let offer = offers.iter().find(|x| x.rentee == rentee);
 
 				// Checks ✅
 				ensure!(contract.renter == who, Error::<T>::NotTheContractOwner);
-				ensure!(offer_found, Error::<T>::NoOfferFromThisAddress);
+				let offer = offer.ok_or(Error::<T>::NoOfferFromThisAddress)?;
 
 				// Storage Activity 📦
-				// 1. Take Rent and Cancellation Fee from Caller
-				// 2. Move Contract Queue from Available to Active
-				// 3. Remove Offers
-				// 4. Set Contract Start Block and Rentee
-				Self::take_rent_and_cancellation_fee(&rentee, &pallet, &contract)?;
+				// 1. Apply the Offer Terms to the Contract
+				// 2. Refund all Offers and remove them
+				// 3. Take Rent and Cancellation Fee from Rentee
+				// 4. Move Contract Queue from Available to Active
+				// 5. Set Contract Start Block and Rentee
+				contract.apply_offer(offer);
+				Self::refund_offers(nft_id);
+				marketplace_cut =
+					Self::take_rent_and_cancellation_fee(nft_id, &rentee, &pallet, &contract)?;
 
 				Queues::<T>::mutate(|queues| -> DispatchResult {
 					queues.remove(nft_id, QueueKind::Available);
@@ -756,16 +1002,18 @@ pub mod pallet {
 
 					Ok(())
 				})?;
-				Offers::<T>::remove(nft_id);
 
 				contract.rentee = Some(rentee.clone());
 				contract.start_block = Some(now);
 
+				let expires = now + *contract.duration.get_full_duration();
+				Self::set_contract_user(nft_id, Some(NFTUser::new(rentee.clone(), expires)))?;
+
 				Ok(())
 			})?;
 
 			// Event 🎁
-			let event = Event::ContractStarted { nft_id, rentee };
+			let event = Event::ContractStarted { nft_id, rentee, marketplace_cut };
 			Self::deposit_event(event);
 
 			Ok(().into())
@@ -782,12 +1030,14 @@ pub mod pallet {
 			Offers::<T>::try_mutate(nft_id, |x| -> DispatchResult {
 				// Checks ✅
 				let offers = x.as_mut().ok_or(Error::<T>::NoOffersForThisContract)?;
-				let index = offers.iter().position(|x| *x == who);
+				let index = offers.iter().position(|x| x.rentee == who);
 				let index = index.ok_or(Error::<T>::NoOfferFromThisAddress)?;
 
 				// Storage Activity 📦
 				// 1. Remove offer from Offers
-				offers.remove(index);
+				// 2. Unreserve offered rent fee
+				let offer = offers.remove(index);
+				T::Currency::unreserve(&who, offer.reserved);
 
 				Ok(())
 			})?;
@@ -828,8 +1078,15 @@ pub mod pallet {
 				ensure!(period <= duration_limit, Error::<T>::DurationExceedsMaximumLimit);
 				ensure!(max_duration > 0u32.into(), Error::<T>::DurationInvalid);
 				ensure!(period > 0u32.into(), Error::<T>::DurationInvalid);
+				let subscription = contract
+					.duration
+					.as_subscription()
+					.ok_or(Error::<T>::SubscriptionDataNotFound)?;
+				let grace_period = subscription.grace_period;
+				let overdue_period = subscription.overdue_period;
+				ensure!(grace_period < period, Error::<T>::GracePeriodTooLong);
 				if !contract_active {
-					Offers::<T>::remove(nft_id);
+					Self::refund_offers(nft_id);
 				}
 
 				// Storage Activity 📦
@@ -840,6 +1097,8 @@ pub mod pallet {
 					max_duration,
 					is_changeable,
 					new_terms: contract_active,
+					grace_period,
+					overdue_period,
 				});
 				contract.rent_fee = RentFee::Tokens(rent_fee);
 				contract.duration.set_terms_changed(true);
@@ -907,6 +1166,320 @@ pub mod pallet {
 
 			Ok(().into())
 		}
+
+		/// Release to the renter the part of an escrowed rent fee earned so far.
+		#[pallet::call_index(9)]
+		#[pallet::weight(T::WeightInfo::claim_escrowed_rent_fee())]
+		pub fn claim_escrowed_rent_fee(
+			origin: OriginFor<T>,
+			nft_id: NFTId,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			let contract = Contracts::<T>::get(nft_id).ok_or(Error::<T>::ContractNotFound)?;
+
+			// Checks ✅
+			ensure!(contract.renter == who, Error::<T>::NotTheContractOwner);
+			ensure!(EscrowedRentFees::<T>::contains_key(nft_id), Error::<T>::NoEscrowedRentFee);
+
+			// Storage Activity 📦
+			// 1. Transfer the earned part of the escrowed rent fee to renter
+			Self::release_escrowed_rent_fee(nft_id, &contract, &now)?;
+
+			Ok(().into())
+		}
+
+		/// Create a sublet of a running contract. Only the rentee of a contract allowing sublets
+		/// can create one and it has to end before the contract.
+		#[pallet::call_index(10)]
+		#[pallet::weight(T::WeightInfo::create_sublet())]
+		pub fn create_sublet(
+			origin: OriginFor<T>,
+			nft_id: NFTId,
+			duration: T::BlockNumber,
+			rent_fee: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let now = frame_system::Pallet::<T>::block_number();
+			let contract = Contracts::<T>::get(nft_id).ok_or(Error::<T>::ContractNotFound)?;
+
+			// Checks ✅
+			ensure!(contract.rentee == Some(who.clone()), Error::<T>::NotTheContractRentee);
+			ensure!(contract.allow_sublet, Error::<T>::SubletNotAllowed);
+			ensure!(!Sublets::<T>::contains_key(nft_id), Error::<T>::SubletAlreadyExists);
+			ensure!(duration > 0u32.into(), Error::<T>::DurationInvalid);
+			ensure!(
+				!contract.has_ended(&(now + duration)),
+				Error::<T>::SubletExceedsContractDuration
+			);
+
+			// Storage Activity 📦
+			// 1. Add Sublet to Storage
+			let sublet = SubletContractData::new(who.clone(), duration, rent_fee);
+			Sublets::<T>::insert(nft_id, sublet);
+
+			// Event 🎁
+			let event = Event::SubletCreated {
+				nft_id,
+				renter: who,
+				parent_renter: contract.renter,
+				duration,
+				rent_fee,
+			};
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Rent a sublet.
+		#[pallet::call_index(11)]
+		#[pallet::weight(T::WeightInfo::rent_sublet(SubletQueue::<T>::get().size()))]
+		pub fn rent_sublet(origin: OriginFor<T>, nft_id: NFTId) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let now = frame_system::Pallet::<T>::block_number();
+
+			Sublets::<T>::try_mutate(nft_id, |x| -> DispatchResult {
+				let sublet = x.as_mut().ok_or(Error::<T>::SubletNotFound)?;
+				let contract = Contracts::<T>::get(nft_id).ok_or(Error::<T>::ContractNotFound)?;
+				let end_block = now + sublet.duration;
+
+				// Checks ✅
+				ensure!(sublet.rentee.is_none(), Error::<T>::CannotRentStartedContract);
+				ensure!(sublet.renter != who, Error::<T>::CannotRentOwnContract);
+				ensure!(
+					!contract.has_ended(&end_block),
+					Error::<T>::SubletExceedsContractDuration
+				);
+
+				// Storage Activity 📦
+				// 1. Take Rent Fee from Caller and keep it in escrow until the sublet ends
+				// 2. Add Sublet to the Queue
+				// 3. Set Sublet Start Block and Rentee
+				// 4. Set Caller as NFT User
+				T::Currency::transfer(&who, &Self::account_id(), sublet.rent_fee, KeepAlive)
+					.map_err(|_| Error::<T>::NotEnoughFundsForRentFee)?;
+				EscrowedSubletFees::<T>::insert(nft_id, sublet.rent_fee);
+				SubletQueue::<T>::try_mutate(|queue| queue.insert(nft_id, end_block))
+					.map_err(|_| Error::<T>::MaxSimultaneousContractReached)?;
+
+				sublet.rentee = Some(who.clone());
+				sublet.start_block = Some(now);
+				Self::set_contract_user(nft_id, Some(NFTUser::new(who.clone(), end_block)))?;
+
+				Ok(())
+			})?;
+
+			// Event 🎁
+			let event = Event::SubletStarted { nft_id, rentee: who };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Propose to extend a running fixed contract. The proposal has to be accepted by the
+		/// other participant and replaces any previous proposal.
+		#[pallet::call_index(13)]
+		#[pallet::weight(T::WeightInfo::propose_extension())]
+		pub fn propose_extension(
+			origin: OriginFor<T>,
+			nft_id: NFTId,
+			extra_blocks: T::BlockNumber,
+			extra_fee: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let contract = Contracts::<T>::get(nft_id).ok_or(Error::<T>::ContractNotFound)?;
+
+			// Checks ✅
+			let rentee = contract.rentee.as_ref().ok_or(Error::<T>::ContractIsNotRunning)?;
+			ensure!(contract.renter == who || *rentee == who, Error::<T>::NotAContractParticipant);
+			Self::extended_duration(&contract, extra_blocks)?;
+
+			// Storage Activity 📦
+			// 1. Add Extension Proposal to Storage
+			let proposal = ExtensionProposal { proposer: who.clone(), extra_blocks, extra_fee };
+			Extensions::<T>::insert(nft_id, proposal);
+
+			// Event 🎁
+			let event = Event::ExtensionProposed { nft_id, proposer: who, extra_blocks, extra_fee };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Accept the extension proposed by the other contract participant.
+		#[pallet::call_index(14)]
+		#[pallet::weight(T::WeightInfo::accept_extension(Queues::<T>::get().size() as u32))]
+		pub fn accept_extension(
+			origin: OriginFor<T>,
+			nft_id: NFTId,
+			extra_blocks: T::BlockNumber,
+			extra_fee: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let pallet = Self::account_id();
+			let proposal = Extensions::<T>::get(nft_id).ok_or(Error::<T>::NoExtensionProposed)?;
+			let mut duration: T::BlockNumber = 0u32.into();
+			let mut marketplace_cut: BalanceOf<T> = 0u32.into();
+
+			Contracts::<T>::try_mutate(nft_id, |x| -> DispatchResult {
+				let contract = x.as_mut().ok_or(Error::<T>::ContractNotFound)?;
+
+				// Checks ✅
+				let rentee = contract.rentee.clone().ok_or(Error::<T>::ContractIsNotRunning)?;
+				ensure!(
+					contract.renter == who || rentee == who,
+					Error::<T>::NotAContractParticipant
+				);
+				ensure!(proposal.proposer != who, Error::<T>::CannotAcceptOwnExtension);
+				ensure!(
+					proposal.extra_blocks == extra_blocks && proposal.extra_fee == extra_fee,
+					Error::<T>::ContractTermsDoNotMatch
+				);
+				duration = Self::extended_duration(&contract, extra_blocks)?;
+
+				// Storage Activity 📦
+				// 1. Take Extra Fee from Rentee and the Marketplace Commission from it
+				// 2. Update Contract Duration
+				// 3. Move Contract in the Fixed Queue
+				// 4. Extend NFT usage of the rentee unless it is sublet
+				// 5. Remove Extension Proposal
+				let old_fee = contract.rent_fee.get_balance().unwrap_or(0u32.into());
+				let new_fee = old_fee.saturating_add(extra_fee);
+				let commission = Self::marketplace_commission(&contract, new_fee)
+					.saturating_sub(Self::marketplace_commission(&contract, old_fee));
+				let is_escrowed = contract.rent_fee.is_escrowed();
+				let destination = if is_escrowed { &pallet } else { &contract.renter };
+				marketplace_cut =
+					Self::transfer_rent_fee(&contract, &rentee, destination, extra_fee, commission)
+						.map_err(|_| Error::<T>::NotEnoughFundsForRentFee)?;
+				if let RentFee::EscrowedTokens(amount) = &mut contract.rent_fee {
+					*amount = new_fee;
+					let escrowed = extra_fee.saturating_sub(marketplace_cut);
+					EscrowedRentFees::<T>::mutate(nft_id, |x| {
+						*x = Some(x.unwrap_or(0u32.into()).saturating_add(escrowed));
+					});
+				}
+
+				contract.duration = Duration::Fixed(duration);
+				let end_block = contract.end_block().ok_or(Error::<T>::ContractIsNotRunning)?;
+				Queues::<T>::mutate(|queues| queues.fixed_queue.update(nft_id, end_block));
+
+				let is_sublet = Sublets::<T>::get(nft_id).and_then(|x| x.rentee).is_some();
+				if !is_sublet {
+					Self::set_contract_user(nft_id, Some(NFTUser::new(rentee, end_block)))?;
+				}
+				Extensions::<T>::remove(nft_id);
+
+				Ok(())
+			})?;
+
+			// Event 🎁
+			let event = Event::ExtensionAccepted { nft_id, duration, extra_fee, marketplace_cut };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Cancel a sublet that is not running.
+		#[pallet::call_index(12)]
+		#[pallet::weight(T::WeightInfo::cancel_sublet())]
+		pub fn cancel_sublet(origin: OriginFor<T>, nft_id: NFTId) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let sublet = Sublets::<T>::get(nft_id).ok_or(Error::<T>::SubletNotFound)?;
+
+			// Checks ✅
+			ensure!(sublet.renter == who, Error::<T>::NotTheContractOwner);
+			ensure!(sublet.rentee.is_none(), Error::<T>::CannotCancelRunningContract);
+
+			// Storage Activity 📦
+			// 1. Remove Sublet from Storage
+			Sublets::<T>::remove(nft_id);
+
+			// Event 🎁
+			let event = Event::SubletCanceled { nft_id };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Bundle additional NFTs with the NFT of a contract that is not running. The bundled NFTs
+		/// are rented under the same terms and released together with the contract NFT.
+		#[pallet::call_index(15)]
+		#[pallet::weight(T::WeightInfo::bundle_nfts(nft_ids.len() as u32))]
+		pub fn bundle_nfts(
+			origin: OriginFor<T>,
+			nft_id: NFTId,
+			nft_ids: BoundedVec<NFTId, T::BundleSizeLimit>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let contract = Contracts::<T>::get(nft_id).ok_or(Error::<T>::ContractNotFound)?;
+			let mut bundle = BundledNFTs::<T>::get(nft_id).unwrap_or_default();
+
+			// Checks ✅
+			ensure!(contract.renter == who, Error::<T>::NotTheContractOwner);
+			ensure!(contract.rentee.is_none(), Error::<T>::CannotBundleRunningContract);
+
+			for id in nft_ids.iter() {
+				ensure!(*id != nft_id && !bundle.contains(id), Error::<T>::NFTAlreadyBundled);
+				let nft = T::NFTExt::get_nft(*id).ok_or(Error::<T>::NFTNotFound)?;
+				ensure!(nft.owner == who, Error::<T>::NotTheNFTOwner);
+				ensure!(
+					nft.not_in_state(&Self::invalid_state()).is_ok(),
+					Error::<T>::BundledNFTNotInValidState
+				);
+				bundle.try_push(*id).map_err(|_| Error::<T>::BundleSizeLimitReached)?;
+			}
+
+			// Storage Activity 📦
+			// 1. Set bundled NFTs to Rented State and link them to the contract
+			// 2. Add bundled NFTs to Storage
+			for id in nft_ids.iter() {
+				T::NFTExt::mutate_nft(*id, |x| -> DispatchResult {
+					let nft = x.as_mut().ok_or(Error::<T>::NFTNotFound)?;
+					_ = nft.set_state(Rented, true);
+					Ok(())
+				})?;
+				BundleParents::<T>::insert(id, nft_id);
+			}
+			BundledNFTs::<T>::insert(nft_id, bundle);
+
+			// Event 🎁
+			let event = Event::NFTsBundled { nft_id, nft_ids };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
+
+		/// Set what the rentee of a contract that is not running will be allowed to do with the
+		/// rented NFT.
+		#[pallet::call_index(16)]
+		#[pallet::weight(T::WeightInfo::set_usage_scope())]
+		pub fn set_usage_scope(
+			origin: OriginFor<T>,
+			nft_id: NFTId,
+			usage_scope: UsageScope,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			Contracts::<T>::try_mutate(nft_id, |x| -> DispatchResult {
+				let contract = x.as_mut().ok_or(Error::<T>::ContractNotFound)?;
+
+				// Checks ✅
+				ensure!(contract.renter == who, Error::<T>::NotTheContractOwner);
+				ensure!(contract.rentee.is_none(), Error::<T>::ContractTermsAlreadyAccepted);
+
+				// Storage Activity 📦
+				contract.usage_scope = usage_scope;
+
+				Ok(())
+			})?;
+
+			// Event 🎁
+			let event = Event::UsageScopeSet { nft_id, usage_scope };
+			Self::deposit_event(event);
+
+			Ok(().into())
+		}
 	}
 }
 
@@ -953,34 +1526,80 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
+	/// Bill the first subscription period starting from `due_block`, the block for which the
+	/// contract was queued. The queue can be processed late because of `ActionsInBlockLimit`
+	/// so several periods may have to be caught up: they are billed one per call and the
+	/// returned block is then not after `now`, which makes each of them a separate queue action.
+	/// A failed payment puts the contract in an overdue state and is retried every block until
+	/// the grace period ends. Returns the block at which the contract has to be processed again
+	/// or `None` if the contract has to end.
 	pub fn handle_subscription_contract(
 		nft_id: NFTId,
+		due_block: &T::BlockNumber,
 		now: &T::BlockNumber,
 	) -> Option<T::BlockNumber> {
-		let contract = Contracts::<T>::get(nft_id)?;
-		let rentee = contract.rentee.as_ref()?;
+		let mut contract = Contracts::<T>::get(nft_id)?;
+		let rentee = contract.rentee.clone()?;
 		let rent_fee = contract.rent_fee.get_balance()?;
-
-		let mut cancel_subscription = contract.duration.terms_changed() || contract.has_ended(now);
-		if !cancel_subscription {
-			cancel_subscription =
-				T::Currency::transfer(rentee, &contract.renter, rent_fee, KeepAlive).is_err();
+		let grace_period = contract.duration.as_subscription()?.grace_period;
+		let overdue_period = contract.duration.overdue_period();
+		let (index, period_start) = match overdue_period {
+			Some(index) => (index, contract.subscription_period_start(index)?),
+			None => contract.next_subscription_period(due_block)?,
+		};
+
+		if period_start > *now {
+			return Some(period_start)
 		}
 
-		if cancel_subscription {
+		if contract.duration.terms_changed() || contract.has_ended(&period_start) {
 			return None
 		}
 
-		let sub_duration = contract.duration.get_duration_or_period();
+		let commission = Self::marketplace_commission(&contract, rent_fee);
+		let payment =
+			Self::transfer_rent_fee(&contract, &rentee, &contract.renter, rent_fee, commission);
+		let marketplace_cut = match payment {
+			Ok(x) => x,
+			Err(_) => {
+				let grace_end = period_start.saturating_add(grace_period);
+				if *now >= grace_end {
+					return None
+				}
 
-		// This is not fully correct.
-		// It can happen that this rent contract is processed later than it should so we need to
-		// adjust for that.
-		Some(*now + *sub_duration)
+				if overdue_period != Some(index) {
+					contract.duration.set_overdue_period(Some(index));
+					Contracts::<T>::insert(nft_id, contract);
+					let event =
+						Event::SubscriptionPaymentFailed { nft_id, period: index, grace_end };
+					Self::deposit_event(event);
+				}
+
+				return Some(now.saturating_add(1u32.into()))
+			},
+		};
+
+		if overdue_period == Some(index) {
+			contract.duration.set_overdue_period(None);
+			Contracts::<T>::insert(nft_id, contract.clone());
+			let event =
+				Event::SubscriptionPaymentRecovered { nft_id, period: index, marketplace_cut };
+			Self::deposit_event(event);
+		} else {
+			let event =
+				Event::ContractSubscriptionPeriodStarted { nft_id, period: index, marketplace_cut };
+			Self::deposit_event(event);
+		}
+
+		contract.subscription_period_start(index.saturating_add(1))
 	}
 
 	pub fn handle_finished_or_unused_contract(nft_id: NFTId) -> DispatchResult {
 		let contract = Contracts::<T>::get(nft_id).ok_or(Error::<T>::ContractNotFound)?;
+		Self::end_sublet(nft_id, true)?;
+		Extensions::<T>::remove(nft_id);
+		Self::refund_offers(nft_id);
+
 		T::NFTExt::mutate_nft(nft_id, |x| -> DispatchResult {
 			let nft = x.as_mut().ok_or(Error::<T>::NFTNotFound)?;
 			let src = &Self::account_id();
@@ -1013,6 +1632,18 @@ impl<T: Config> Pallet<T> {
 			Ok(())
 		})?;
 
+		if let Some(amount) = EscrowedRentFees::<T>::take(nft_id) {
+			T::Currency::transfer(&Self::account_id(), &contract.renter, amount, AllowDeath)?;
+			let renter = contract.renter.clone();
+			let event = Event::EscrowedRentFeeReleased { nft_id, renter, amount };
+			Self::deposit_event(event);
+		}
+
+		if contract.rentee.is_some() {
+			Self::set_contract_user(nft_id, None)?;
+		}
+		Self::release_bundled_nfts(nft_id)?;
+
 		Contracts::<T>::remove(nft_id);
 		Ok(())
 	}
@@ -1029,18 +1660,27 @@ impl<T: Config> Pallet<T> {
 	}
 
 	pub fn take_rent_and_cancellation_fee(
+		nft_id: NFTId,
 		rentee: &T::AccountId,
 		pallet: &T::AccountId,
 		contract: &RentContractDataOf<T>,
-	) -> DispatchResult {
+	) -> Result<BalanceOf<T>, DispatchError> {
 		let renter = &contract.renter;
 		let cancellation_fee = contract.rentee_cancellation_fee.clone();
 
 		// Let's take rentee's token. In case an error happens those balance transactions
 		// will be reverted. ✅ 📦
+		// Escrowed rent fees are kept by the pallet until they are earned by the renter.
 		let amount = contract.rent_fee.get_balance().unwrap_or(0u32.into());
-		T::Currency::transfer(rentee, renter, amount, KeepAlive)
-			.map_err(|_| Error::<T>::NotEnoughFundsForRentFee)?;
+		let commission = Self::marketplace_commission(contract, amount);
+		let is_escrowed = contract.rent_fee.is_escrowed();
+		let destination = if is_escrowed { pallet } else { renter };
+		let marketplace_cut =
+			Self::transfer_rent_fee(contract, rentee, destination, amount, commission)
+				.map_err(|_| Error::<T>::NotEnoughFundsForRentFee)?;
+		if is_escrowed {
+			EscrowedRentFees::<T>::insert(nft_id, amount.saturating_sub(marketplace_cut));
+		}
 
 		let amount = cancellation_fee.get_balance().unwrap_or(0u32.into());
 		T::Currency::transfer(rentee, pallet, amount, KeepAlive)
@@ -1078,6 +1718,215 @@ impl<T: Config> Pallet<T> {
 				.map_err(|_| Error::<T>::RentNFTNotFound)?;
 		}
 
+		Ok(marketplace_cut)
+	}
+
+	/// Marketplace commission taken from a rent payment of the given amount.
+	pub fn marketplace_commission(
+		contract: &RentContractDataOf<T>,
+		amount: BalanceOf<T>,
+	) -> BalanceOf<T> {
+		match contract.commission_fee {
+			Some(CompoundFee::Flat(x)) => x.min(amount),
+			Some(CompoundFee::Percentage(x)) => x * amount,
+			None => 0u32.into(),
+		}
+	}
+
+	/// Transfer a rent payment from `src`, the commission goes to the owner of the marketplace
+	/// the contract was created through and the rest to `dst`. Fails if any part of the payment
+	/// cannot be made. Returns the paid commission.
+	pub fn transfer_rent_fee(
+		contract: &RentContractDataOf<T>,
+		src: &T::AccountId,
+		dst: &T::AccountId,
+		amount: BalanceOf<T>,
+		commission: BalanceOf<T>,
+	) -> Result<BalanceOf<T>, DispatchError> {
+		let marketplace = contract.marketplace_id.and_then(T::MarketplaceExt::get_marketplace);
+		let (owner, commission) = match marketplace {
+			Some(x) if commission > 0u32.into() => (x.owner, commission.min(amount)),
+			_ => return T::Currency::transfer(src, dst, amount, KeepAlive).map(|_| 0u32.into()),
+		};
+
+		T::Currency::transfer(src, dst, amount - commission, KeepAlive)?;
+		T::Currency::transfer(src, &owner, commission, KeepAlive)?;
+
+		Ok(commission)
+	}
+
+	/// Unreserve the funds of every offer made for a contract and remove the offers.
+	pub fn refund_offers(nft_id: NFTId) {
+		for offer in Offers::<T>::take(nft_id).unwrap_or_default() {
+			T::Currency::unreserve(&offer.rentee, offer.reserved);
+		}
+	}
+
+	/// Returns the duration of a running fixed contract extended by `extra_blocks` after
+	/// checking it against the maximum contract duration.
+	pub fn extended_duration(
+		contract: &RentContractDataOf<T>,
+		extra_blocks: T::BlockNumber,
+	) -> Result<T::BlockNumber, DispatchError> {
+		let duration = match contract.duration {
+			Duration::Fixed(x) => x,
+			_ => return Err(Error::<T>::ContractIsNotFixed.into()),
+		};
+		let duration_limit: T::BlockNumber = T::MaximumContractDurationLimit::get().into();
+		let now = frame_system::Pallet::<T>::block_number();
+
+		ensure!(!contract.has_ended(&now), Error::<T>::ContractIsNotRunning);
+		ensure!(extra_blocks > 0u32.into(), Error::<T>::DurationInvalid);
+		let duration = duration.saturating_add(extra_blocks);
+		ensure!(duration <= duration_limit, Error::<T>::DurationExceedsMaximumLimit);
+
+		Ok(duration)
+	}
+
+	/// End the sublet of a contract if there is one and pay out its escrowed rent fee. Unless the
+	/// parent contract is ending as well, the NFT usage goes back to the parent contract rentee.
+	pub fn end_sublet(nft_id: NFTId, parent_ended: bool) -> DispatchResult {
+		SubletQueue::<T>::mutate(|x| x.remove(nft_id));
+		let sublet = match Sublets::<T>::take(nft_id) {
+			Some(x) => x,
+			None => return Ok(()),
+		};
+
+		if parent_ended {
+			Self::refund_sublet_rent_fee(nft_id, &sublet)?;
+		}
+		Self::release_sublet_rent_fee(nft_id, &sublet.renter)?;
+
+		if sublet.rentee.is_some() && !parent_ended {
+			let contract = Contracts::<T>::get(nft_id).ok_or(Error::<T>::ContractNotFound)?;
+			let rentee = contract.rentee.clone().ok_or(Error::<T>::ContractIsNotRunning)?;
+			let expires = contract.end_block().ok_or(Error::<T>::ContractIsNotRunning)?;
+			Self::set_contract_user(nft_id, Some(NFTUser::new(rentee, expires)))?;
+		}
+
+		let event = Event::SubletEnded { nft_id, parent_ended };
+		Self::deposit_event(event);
+
+		Ok(())
+	}
+
+	/// Refund to the rentee of a sublet the part of its escrowed rent fee covering the blocks it
+	/// cannot use anymore.
+	pub fn refund_sublet_rent_fee(
+		nft_id: NFTId,
+		sublet: &SubletContractDataOf<T>,
+	) -> DispatchResult {
+		let (rentee, start_block) = match (&sublet.rentee, sublet.start_block) {
+			(Some(rentee), Some(start_block)) => (rentee.clone(), start_block),
+			_ => return Ok(()),
+		};
+		let escrowed = match EscrowedSubletFees::<T>::get(nft_id) {
+			Some(x) => x,
+			None => return Ok(()),
+		};
+		let now = frame_system::Pallet::<T>::block_number();
+		let unused = start_block.saturating_add(sublet.duration).saturating_sub(now);
+		let amount = Permill::from_rational(unused, sublet.duration) * sublet.rent_fee;
+		let amount = amount.min(escrowed);
+
+		if amount > 0u32.into() {
+			T::Currency::transfer(&Self::account_id(), &rentee, amount, AllowDeath)?;
+			EscrowedSubletFees::<T>::insert(nft_id, escrowed - amount);
+
+			let event = Event::SubletRentFeeRefunded { nft_id, rentee, amount };
+			Self::deposit_event(event);
+		}
+
+		Ok(())
+	}
+
+	/// Release to the renter of a sublet what is left of its escrowed rent fee.
+	pub fn release_sublet_rent_fee(nft_id: NFTId, renter: &T::AccountId) -> DispatchResult {
+		let amount = match EscrowedSubletFees::<T>::take(nft_id) {
+			Some(x) => x,
+			None => return Ok(()),
+		};
+
+		if amount > 0u32.into() {
+			T::Currency::transfer(&Self::account_id(), renter, amount, AllowDeath)?;
+
+			let renter = renter.clone();
+			let event = Event::SubletRentFeeReleased { nft_id, renter, amount };
+			Self::deposit_event(event);
+		}
+
+		Ok(())
+	}
+
+	/// Release to the renter the part of the escrowed rent fee that was earned since the
+	/// contract started and was not released yet. Returns the released amount.
+	pub fn release_escrowed_rent_fee(
+		nft_id: NFTId,
+		contract: &RentContractDataOf<T>,
+		now: &T::BlockNumber,
+	) -> Result<BalanceOf<T>, DispatchError> {
+		let remaining = match EscrowedRentFees::<T>::get(nft_id) {
+			Some(x) => x,
+			None => return Ok(0u32.into()),
+		};
+		let rent_fee = contract.rent_fee.get_balance().unwrap_or(0u32.into());
+		let total = rent_fee.saturating_sub(Self::marketplace_commission(contract, rent_fee));
+		let earned = contract.percentage_of_completion(now) * total;
+		let released = total.saturating_sub(remaining);
+		let amount = earned.saturating_sub(released).min(remaining);
+
+		if amount > 0u32.into() {
+			T::Currency::transfer(&Self::account_id(), &contract.renter, amount, AllowDeath)?;
+			EscrowedRentFees::<T>::insert(nft_id, remaining - amount);
+
+			let renter = contract.renter.clone();
+			let event = Event::EscrowedRentFeeReleased { nft_id, renter, amount };
+			Self::deposit_event(event);
+		}
+
+		Ok(amount)
+	}
+
+	/// Refund to the rentee the part of the escrowed rent fee that was not released.
+	pub fn refund_escrowed_rent_fee(nft_id: NFTId, rentee: &T::AccountId) -> DispatchResult {
+		let amount = match EscrowedRentFees::<T>::take(nft_id) {
+			Some(x) => x,
+			None => return Ok(()),
+		};
+
+		if amount > 0u32.into() {
+			T::Currency::transfer(&Self::account_id(), rentee, amount, AllowDeath)?;
+
+			let rentee = rentee.clone();
+			let event = Event::EscrowedRentFeeRefunded { nft_id, rentee, amount };
+			Self::deposit_event(event);
+		}
+
+		Ok(())
+	}
+
+	/// Set or remove the user of the contract NFT and of all the NFTs bundled with it.
+	pub fn set_contract_user(
+		nft_id: NFTId,
+		user: Option<NFTUser<T::AccountId, T::BlockNumber>>,
+	) -> DispatchResult {
+		for id in BundledNFTs::<T>::get(nft_id).unwrap_or_default() {
+			T::NFTExt::set_nft_user(id, user.clone())?;
+		}
+		T::NFTExt::set_nft_user(nft_id, user)
+	}
+
+	/// Remove the Rented state from the NFTs bundled with a contract and remove the bundle.
+	pub fn release_bundled_nfts(nft_id: NFTId) -> DispatchResult {
+		for id in BundledNFTs::<T>::take(nft_id).unwrap_or_default() {
+			T::NFTExt::mutate_nft(id, |x| -> DispatchResult {
+				let nft = x.as_mut().ok_or(Error::<T>::NFTNotFound)?;
+				_ = nft.set_state(Rented, false);
+				Ok(())
+			})?;
+			BundleParents::<T>::remove(id);
+		}
+
 		Ok(())
 	}
 
@@ -1086,6 +1935,15 @@ impl<T: Config> Pallet<T> {
 	}
 }
 
+impl<T: Config> RentExt<T::AccountId> for Pallet<T> {
+	fn usage_scope(nft_id: NFTId, account: &T::AccountId) -> Option<UsageScope> {
+		let parent_id = BundleParents::<T>::get(nft_id).unwrap_or(nft_id);
+		let contract = Contracts::<T>::get(parent_id)?;
+		let is_user = T::NFTExt::current_nft_user(nft_id).as_ref() == Some(account);
+		is_user.then_some(contract.usage_scope)
+	}
+}
+
 impl<T: Config> Pallet<T> {
 	/// Fill available queue. (benchmarks)
 	pub fn benchmark_fill_available_queue(
@@ -1100,14 +1958,29 @@ impl<T: Config> Pallet<T> {
 		Ok(())
 	}
 
+	/// Fill sublet queue. (benchmarks)
+	pub fn benchmark_fill_sublet_queue(
+		number: u32,
+		block_number: T::BlockNumber,
+	) -> Result<(), DispatchError> {
+		SubletQueue::<T>::try_mutate(|x| -> DispatchResult {
+			let data = vec![(999, block_number); number as usize].into_iter();
+			x.0.try_extend(data).map_err(|_| Error::<T>::MaxSimultaneousContractReached)?;
+			Ok(())
+		})?;
+		Ok(())
+	}
+
 	/// Fill offers vector with any number of data. (benchmarks)
 	pub fn benchmark_fill_offers(
 		number: u32,
 		nft_id: NFTId,
 		account: T::AccountId,
 	) -> Result<(), DispatchError> {
-		let offers: AccountList<T::AccountId, T::AccountSizeLimit> =
-			BoundedVec::try_from(vec![account; number as usize])
+		let offer =
+			RentOffer { rentee: account, rent_fee: None, duration: None, reserved: 0u32.into() };
+		let offers: BoundedVec<RentOfferOf<T>, T::AccountSizeLimit> =
+			BoundedVec::try_from(vec![offer; number as usize])
 				.map_err(|_| Error::<T>::MaximumOffersReached)?;
 		Offers::<T>::insert(nft_id, offers);
 		Ok(())
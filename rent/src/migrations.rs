@@ -1,94 +1,194 @@
-// use super::*;
-
-// pub mod v2 {
-// 	use super::*;
-// 	use frame_support::{
-// 		traits::OnRuntimeUpgrade, CloneNoBound, PartialEqNoBound, RuntimeDebugNoBound,
-// 	};
-// 	use frame_system::Pallet as System;
-// 	use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
-// 	use scale_info::TypeInfo;
-// 	use sp_arithmetic::traits::AtLeast32BitUnsigned;
-// 	use sp_std::fmt::Debug;
-
-// 	#[derive(
-// 		Encode,
-// 		Decode,
-// 		CloneNoBound,
-// 		Eq,
-// 		PartialEqNoBound,
-// 		RuntimeDebugNoBound,
-// 		TypeInfo,
-// 		MaxEncodedLen,
-// 	)]
-// 	#[scale_info(skip_type_params(AccountSizeLimit))]
-// 	#[codec(mel_bound(AccountId: MaxEncodedLen, Balance: MaxEncodedLen, BlockNumber: MaxEncodedLen))]
-// 	pub struct OldRentContractData<AccountId, BlockNumber, Balance, AccountSizeLimit>
-// 	where
-// 		AccountId: Clone + PartialEq + Debug,
-// 		Balance: Clone + PartialEq + Debug + sp_std::cmp::PartialOrd,
-// 		BlockNumber:
-// 			Clone + PartialEq + Debug + sp_std::cmp::PartialOrd + AtLeast32BitUnsigned + Copy,
-// 		AccountSizeLimit: Get<u32>,
-// 	{
-// 		pub start_block: Option<BlockNumber>,
-// 		pub renter: AccountId,
-// 		pub rentee: Option<AccountId>,
-// 		pub duration: Duration<BlockNumber>,
-// 		pub acceptance_type: AcceptanceType<AccountList<AccountId, AccountSizeLimit>>,
-// 		pub renter_can_revoke: bool,
-// 		pub rent_fee: RentFee<Balance>,
-// 		pub renter_cancellation_fee: CancellationFee<Balance>,
-// 		pub rentee_cancellation_fee: CancellationFee<Balance>,
-// 	}
-
-// 	pub struct MigrationV2<T>(sp_std::marker::PhantomData<T>);
-// 	impl<T: Config> OnRuntimeUpgrade for MigrationV2<T> {
-// 		#[cfg(feature = "try-runtime")]
-// 		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
-// 			log::info!("Pre-upgrade inside MigrationV2");
-// 			Ok(Vec::new())
-// 		}
-
-// 		fn on_runtime_upgrade() -> frame_support::weights::Weight {
-// 			let now = System::<T>::block_number();
-// 			let mut read = 0u64;
-// 			let mut write = 0u64;
-
-// 			Contracts::<T>::translate(
-// 				|_id,
-// 				 old: OldRentContractData<
-// 					T::AccountId,
-// 					T::BlockNumber,
-// 					BalanceOf<T>,
-// 					T::AccountSizeLimit,
-// 				>| {
-// 					let new_rent_contract_data = RentContractData::new(
-// 						old.start_block,
-// 						old.renter,
-// 						old.rentee,
-// 						old.duration,
-// 						old.acceptance_type,
-// 						old.renter_can_revoke,
-// 						old.rent_fee,
-// 						old.renter_cancellation_fee,
-// 						old.rentee_cancellation_fee,
-// 						now,
-// 					);
-// 					read += 1;
-// 					write += 1;
-
-// 					Some(new_rent_contract_data)
-// 				},
-// 			);
-
-// 			T::DbWeight::get().reads_writes(read, write)
-// 		}
-
-// 		#[cfg(feature = "try-runtime")]
-// 		fn post_upgrade(_: Vec<u8>) -> Result<(), &'static str> {
-// 			log::info!("Post-upgrade inside MigrationV2");
-// 			Ok(())
-// 		}
-// 	}
-// }
+// Copyright 2023 Capsule Corp (France) SAS.
+// This file is part of Ternoa.
+
+// Ternoa is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ternoa is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ternoa.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+
+pub mod v3 {
+	use super::*;
+	use frame_support::{
+		traits::OnRuntimeUpgrade, CloneNoBound, PartialEqNoBound, RuntimeDebug, RuntimeDebugNoBound,
+	};
+	use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+	use scale_info::TypeInfo;
+	use sp_arithmetic::traits::AtLeast32BitUnsigned;
+	use sp_std::{fmt::Debug, vec::Vec};
+
+	#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub struct OldSubscription<BlockNumber: Clone> {
+		pub period_length: BlockNumber,
+		pub max_duration: BlockNumber,
+		pub is_changeable: bool,
+		pub new_terms: bool,
+	}
+
+	#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum OldDuration<BlockNumber: Clone> {
+		Fixed(BlockNumber),
+		Subscription(OldSubscription<BlockNumber>),
+	}
+
+	impl<BlockNumber: Clone + Default> From<OldDuration<BlockNumber>> for Duration<BlockNumber> {
+		fn from(old: OldDuration<BlockNumber>) -> Self {
+			match old {
+				OldDuration::Fixed(x) => Duration::Fixed(x),
+				// Without a grace period a failed payment ends the contract, like before.
+				OldDuration::Subscription(x) => Duration::Subscription(Subscription {
+					period_length: x.period_length,
+					max_duration: x.max_duration,
+					is_changeable: x.is_changeable,
+					new_terms: x.new_terms,
+					grace_period: BlockNumber::default(),
+					overdue_period: None,
+				}),
+			}
+		}
+	}
+
+	#[derive(
+		Encode,
+		Decode,
+		CloneNoBound,
+		Eq,
+		PartialEqNoBound,
+		RuntimeDebugNoBound,
+		TypeInfo,
+		MaxEncodedLen,
+	)]
+	#[scale_info(skip_type_params(AccountSizeLimit))]
+	#[codec(mel_bound(AccountId: MaxEncodedLen, Balance: MaxEncodedLen, BlockNumber: MaxEncodedLen))]
+	pub struct OldRentContractData<AccountId, BlockNumber, Balance, AccountSizeLimit>
+	where
+		AccountId: Clone + PartialEq + Debug,
+		Balance: Clone + PartialEq + Debug + sp_std::cmp::PartialOrd,
+		BlockNumber:
+			Clone + PartialEq + Debug + sp_std::cmp::PartialOrd + AtLeast32BitUnsigned + Copy,
+		AccountSizeLimit: Get<u32>,
+	{
+		pub start_block: Option<BlockNumber>,
+		pub renter: AccountId,
+		pub rentee: Option<AccountId>,
+		pub duration: OldDuration<BlockNumber>,
+		pub acceptance_type: AcceptanceType<AccountList<AccountId, AccountSizeLimit>>,
+		pub renter_can_revoke: bool,
+		pub rent_fee: RentFee<Balance>,
+		pub renter_cancellation_fee: CancellationFee<Balance>,
+		pub rentee_cancellation_fee: CancellationFee<Balance>,
+		pub creation_block: BlockNumber,
+	}
+
+	pub struct MigrationV3<T>(sp_std::marker::PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrationV3<T> {
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			log::info!("Pre-upgrade inside MigrationV3");
+			Ok(Vec::new())
+		}
+
+		fn on_runtime_upgrade() -> frame_support::weights::Weight {
+			let mut count = 0u64;
+			Contracts::<T>::translate(
+				|nft_id,
+				 old: OldRentContractData<
+					T::AccountId,
+					T::BlockNumber,
+					BalanceOf<T>,
+					T::AccountSizeLimit,
+				>| {
+					count += 1;
+
+					// Existing contracts were not created through a marketplace and cannot be
+					// sublet. Their rentees keep the view only access they had so far.
+					let contract = RentContractData::new(
+						old.start_block,
+						old.renter,
+						old.rentee,
+						old.duration.into(),
+						old.acceptance_type,
+						old.renter_can_revoke,
+						old.rent_fee,
+						old.renter_cancellation_fee,
+						old.rentee_cancellation_fee,
+						old.creation_block,
+						false,
+						None,
+						None,
+					);
+
+					// Rentees of started contracts become the users of their NFTs.
+					if let (Some(rentee), Some(expires)) =
+						(contract.rentee.clone(), contract.end_block())
+					{
+						count += 1;
+						let user = Some(NFTUser::new(rentee, expires));
+						if let Err(err) = T::NFTExt::set_nft_user(nft_id, user) {
+							log::error!(
+								"MigrationV3: failed to set the user of NFT {}: {:?}",
+								nft_id,
+								err
+							);
+						}
+					}
+
+					Some(contract)
+				},
+			);
+
+			// Subscriptions used to be queued a period after their last payment, which is not
+			// always on a period boundary. Queue them at the start of the period they were due in
+			// so that this period is still billed.
+			count += 1;
+			Queues::<T>::mutate(|queues| {
+				let entries = queues.subscription_queue.0.clone();
+				for (nft_id, due) in entries {
+					let aligned = Contracts::<T>::get(nft_id).and_then(|contract| {
+						let (index, start) = contract.next_subscription_period(&due)?;
+						if start == due {
+							return None
+						}
+						contract.subscription_period_start(index.checked_sub(1)?)
+					});
+					if let Some(aligned) = aligned {
+						queues.subscription_queue.update(nft_id, aligned);
+					}
+				}
+			});
+
+			// Existing offers did not reserve anything and use the contract terms.
+			Offers::<T>::translate(|_id, old: AccountList<T::AccountId, T::AccountSizeLimit>| {
+				count += 1;
+				let offers: Vec<_> = old
+					.into_iter()
+					.map(|rentee| RentOffer {
+						rentee,
+						rent_fee: None,
+						duration: None,
+						reserved: 0u32.into(),
+					})
+					.collect();
+
+				Some(BoundedVec::truncate_from(offers))
+			});
+
+			T::DbWeight::get().reads_writes(count, count)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_: Vec<u8>) -> Result<(), &'static str> {
+			log::info!("Post-upgrade inside MigrationV3");
+			Ok(())
+		}
+	}
+}
@@ -17,19 +17,25 @@
 use super::mock::*;
 use frame_support::{assert_noop, BoundedVec};
 use frame_system::RawOrigin;
-use primitives::nfts::{NFTId, NFTState};
+use primitives::{
+	marketplace::{MarketplaceId, MarketplaceType},
+	nfts::{NFTId, NFTState, UsageScope},
+	CompoundFee, ConfigOp,
+};
 use sp_arithmetic::per_things::Permill;
-use ternoa_common::traits::NFTExt;
+use ternoa_common::traits::{NFTExt, RentExt};
 
 use crate::{
 	tests::mock, AcceptanceType, CancellationFee, Duration, DurationInput, Error,
-	Event as RentEvent, RentContractData, RentFee, SubscriptionInput,
+	Event as RentEvent, RentContractData, RentFee, SubletContractData, SubscriptionInput,
 };
 
 pub const BLOCK_DURATION: u64 = 10;
 pub const BLOCK_MAX_DURATION: u64 = 100;
 pub const TOKENS: Balance = 100;
 pub const LESS_TOKENS: Balance = 10;
+pub const GRACE_PERIOD: u64 = 3;
+pub const SUBLET_DURATION: u64 = 5;
 pub const CREATION_BLOCK: BlockNumber = 1;
 
 pub const ALICE_NFT_ID_0: NFTId = 0;
@@ -46,7 +52,9 @@ pub const BOB_NFT_ID_0: NFTId = 10;
 pub const BOB_NFT_ID_1: NFTId = 11;
 pub const BOB_NFT_ID_2: NFTId = 12;
 pub const INVALID_NFT: NFTId = 99;
+pub const INVALID_MARKETPLACE: MarketplaceId = 99;
 pub const PERCENT_0: Permill = Permill::from_parts(0);
+pub const PERCENT_10: Permill = Permill::from_percent(10);
 
 pub const FIXED_AUTO_REV_NFT_TOKENS_TOKENS: NFTId = 0;
 pub const FIXED_AUTO_REV_TOK_FLEXTOK_FLEXTOK: NFTId = 1;
@@ -107,6 +115,8 @@ pub fn prepare_tests() {
 		RentFee::NFT(BOB_NFT_ID_0),
 		CancellationFee::FixedTokens(LESS_TOKENS),
 		CancellationFee::FixedTokens(LESS_TOKENS),
+		false,
+		None,
 	)
 	.unwrap();
 	Rent::create_contract(
@@ -118,6 +128,8 @@ pub fn prepare_tests() {
 		RentFee::Tokens(TOKENS),
 		CancellationFee::FlexibleTokens(LESS_TOKENS),
 		CancellationFee::FlexibleTokens(LESS_TOKENS),
+		false,
+		None,
 	)
 	.unwrap();
 	Rent::create_contract(
@@ -129,6 +141,8 @@ pub fn prepare_tests() {
 		RentFee::NFT(BOB_NFT_ID_1),
 		CancellationFee::NFT(ALICE_NFT_ID_5),
 		CancellationFee::NFT(BOB_NFT_ID_0),
+		false,
+		None,
 	)
 	.unwrap();
 	Rent::create_contract(
@@ -138,12 +152,15 @@ pub fn prepare_tests() {
 			period_length: BLOCK_DURATION,
 			max_duration: Some(BLOCK_MAX_DURATION),
 			is_changeable: true,
+			grace_period: 0,
 		}),
 		AcceptanceType::ManualAcceptance(Some(BoundedVec::try_from(vec![BOB]).unwrap())),
 		true,
 		RentFee::Tokens(TOKENS),
 		CancellationFee::FixedTokens(LESS_TOKENS),
 		CancellationFee::FixedTokens(LESS_TOKENS),
+		false,
+		None,
 	)
 	.unwrap();
 	Rent::create_contract(
@@ -153,12 +170,15 @@ pub fn prepare_tests() {
 			period_length: BLOCK_DURATION,
 			max_duration: None,
 			is_changeable: false,
+			grace_period: 0,
 		}),
 		AcceptanceType::AutoAcceptance(Some(BoundedVec::try_from(vec![BOB]).unwrap())),
 		false,
 		RentFee::Tokens(TOKENS),
 		CancellationFee::None,
 		CancellationFee::FixedTokens(LESS_TOKENS),
+		false,
+		None,
 	)
 	.unwrap();
 	Rent::create_contract(
@@ -170,6 +190,8 @@ pub fn prepare_tests() {
 		RentFee::NFT(BOB_NFT_ID_1),
 		CancellationFee::NFT(ALICE_NFT_ID_9),
 		CancellationFee::NFT(BOB_NFT_ID_0),
+		false,
+		None,
 	)
 	.unwrap();
 
@@ -182,6 +204,149 @@ pub fn prepare_tests() {
 	assert!(Rent::contracts(FIXED_AUTO_REV_NFT_NFT_NFT).is_some());
 }
 
+pub fn prepare_escrowed_contract() {
+	let alice: mock::RuntimeOrigin = origin(ALICE);
+
+	Rent::create_contract(
+		alice,
+		ALICE_NFT_ID_6,
+		DurationInput::Fixed(BLOCK_DURATION),
+		AcceptanceType::AutoAcceptance(None),
+		true,
+		RentFee::EscrowedTokens(TOKENS),
+		CancellationFee::None,
+		CancellationFee::None,
+		false,
+		None,
+	)
+	.unwrap();
+}
+
+pub fn prepare_offer_contract() {
+	let alice: mock::RuntimeOrigin = origin(ALICE);
+
+	Rent::create_contract(
+		alice,
+		ALICE_NFT_ID_6,
+		DurationInput::Fixed(BLOCK_DURATION),
+		AcceptanceType::ManualAcceptance(None),
+		true,
+		RentFee::Tokens(TOKENS),
+		CancellationFee::None,
+		CancellationFee::None,
+		false,
+		None,
+	)
+	.unwrap();
+}
+
+pub fn prepare_bundle_contract() {
+	let alice: mock::RuntimeOrigin = origin(ALICE);
+
+	Rent::create_contract(
+		alice.clone(),
+		ALICE_NFT_ID_6,
+		DurationInput::Fixed(BLOCK_DURATION),
+		AcceptanceType::AutoAcceptance(None),
+		true,
+		RentFee::Tokens(TOKENS),
+		CancellationFee::None,
+		CancellationFee::None,
+		false,
+		None,
+	)
+	.unwrap();
+	let nft_ids = BoundedVec::try_from(vec![ALICE_NFT_ID_7]).unwrap();
+	Rent::bundle_nfts(alice, ALICE_NFT_ID_6, nft_ids).unwrap();
+}
+
+pub fn prepare_capsule_contract(usage_scope: UsageScope) -> NFTId {
+	let alice: mock::RuntimeOrigin = origin(ALICE);
+	let bob: mock::RuntimeOrigin = origin(BOB);
+	let data = BoundedVec::default();
+
+	NFT::create_capsule(alice.clone(), data.clone(), data, PERCENT_0, None, false).unwrap();
+	let nft_id = NFT::next_nft_id() - 1;
+	Rent::create_contract(
+		alice.clone(),
+		nft_id,
+		DurationInput::Fixed(BLOCK_DURATION),
+		AcceptanceType::AutoAcceptance(None),
+		true,
+		RentFee::Tokens(TOKENS),
+		CancellationFee::None,
+		CancellationFee::None,
+		false,
+		None,
+	)
+	.unwrap();
+	Rent::set_usage_scope(alice, nft_id, usage_scope).unwrap();
+	Rent::rent(bob, nft_id, CREATION_BLOCK).unwrap();
+
+	nft_id
+}
+
+pub fn prepare_marketplace(kind: MarketplaceType) -> MarketplaceId {
+	let charlie: mock::RuntimeOrigin = origin(CHARLIE);
+
+	Marketplace::create_marketplace(charlie.clone(), kind).unwrap();
+	let marketplace_id = Marketplace::next_marketplace_id() - 1;
+	Marketplace::set_marketplace_configuration(
+		charlie,
+		marketplace_id,
+		ConfigOp::Set(CompoundFee::Percentage(PERCENT_10)),
+		ConfigOp::Noop,
+		ConfigOp::Noop,
+		ConfigOp::Noop,
+		ConfigOp::Noop,
+	)
+	.unwrap();
+
+	marketplace_id
+}
+
+pub fn prepare_marketplace_contract(rent_fee: RentFee<Balance>) -> MarketplaceId {
+	let alice: mock::RuntimeOrigin = origin(ALICE);
+	let marketplace_id = prepare_marketplace(MarketplaceType::Public);
+
+	Rent::create_contract(
+		alice,
+		ALICE_NFT_ID_6,
+		DurationInput::Fixed(BLOCK_DURATION),
+		AcceptanceType::AutoAcceptance(None),
+		true,
+		rent_fee,
+		CancellationFee::None,
+		CancellationFee::None,
+		false,
+		Some(marketplace_id),
+	)
+	.unwrap();
+
+	marketplace_id
+}
+
+pub fn prepare_sublet_contract() {
+	let alice: mock::RuntimeOrigin = origin(ALICE);
+	let bob: mock::RuntimeOrigin = origin(BOB);
+
+	Rent::create_contract(
+		alice,
+		ALICE_NFT_ID_6,
+		DurationInput::Fixed(BLOCK_DURATION),
+		AcceptanceType::AutoAcceptance(None),
+		true,
+		RentFee::Tokens(TOKENS),
+		CancellationFee::None,
+		CancellationFee::None,
+		true,
+		None,
+	)
+	.unwrap();
+	Rent::rent(bob.clone(), ALICE_NFT_ID_6, CREATION_BLOCK).unwrap();
+	Rent::create_sublet(bob, ALICE_NFT_ID_6, SUBLET_DURATION, LESS_TOKENS).unwrap();
+}
+
 mod create_contract {
 
 	use super::*;
@@ -205,6 +370,9 @@ mod create_contract {
 					CancellationFee::None,
 					CancellationFee::None,
 					System::block_number(),
+					false,
+					None,
+					None,
 				);
 
 			// Create basic contract.
@@ -217,6 +385,8 @@ mod create_contract {
 				data.rent_fee.clone(),
 				data.renter_cancellation_fee.clone(),
 				data.rentee_cancellation_fee.clone(),
+				data.allow_sublet,
+				None,
 			)
 			.unwrap();
 
@@ -236,12 +406,72 @@ mod create_contract {
 				rent_fee: data.rent_fee,
 				renter_cancellation_fee: data.renter_cancellation_fee,
 				rentee_cancellation_fee: data.rentee_cancellation_fee,
+				allow_sublet: false,
+				marketplace_id: None,
 			};
 			let event = RuntimeEvent::Rent(event);
 			System::assert_last_event(event);
 		})
 	}
 
+	#[test]
+	fn create_contract_through_marketplace() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let marketplace_id = prepare_marketplace_contract(RentFee::Tokens(TOKENS));
+
+			// State check.
+			let contract = Rent::contracts(ALICE_NFT_ID_6).unwrap();
+			assert_eq!(contract.marketplace_id, Some(marketplace_id));
+			assert_eq!(contract.commission_fee, Some(CompoundFee::Percentage(PERCENT_10)));
+		})
+	}
+
+	#[test]
+	fn marketplace_not_found() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+
+			let err = Rent::create_contract(
+				alice,
+				ALICE_NFT_ID_6,
+				DurationInput::Fixed(BLOCK_DURATION),
+				AcceptanceType::AutoAcceptance(None),
+				true,
+				RentFee::Tokens(TOKENS),
+				CancellationFee::None,
+				CancellationFee::None,
+				false,
+				Some(INVALID_MARKETPLACE),
+			);
+			assert_noop!(err, Error::<Test>::MarketplaceNotFound);
+		})
+	}
+
+	#[test]
+	fn not_allowed_to_list() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let marketplace_id = prepare_marketplace(MarketplaceType::Private);
+
+			let err = Rent::create_contract(
+				alice,
+				ALICE_NFT_ID_6,
+				DurationInput::Fixed(BLOCK_DURATION),
+				AcceptanceType::AutoAcceptance(None),
+				true,
+				RentFee::Tokens(TOKENS),
+				CancellationFee::None,
+				CancellationFee::None,
+				false,
+				Some(marketplace_id),
+			);
+			assert_noop!(err, Error::<Test>::NotAllowedToList);
+		})
+	}
+
 	#[test]
 	fn max_simultaneous_contract_reached() {
 		ExtBuilder::new_build(None).execute_with(|| {
@@ -264,6 +494,8 @@ mod create_contract {
 					RentFee::Tokens(TOKENS),
 					CancellationFee::None,
 					CancellationFee::None,
+					false,
+					None,
 				)
 				.unwrap();
 			}
@@ -278,6 +510,8 @@ mod create_contract {
 				RentFee::Tokens(TOKENS),
 				CancellationFee::None,
 				CancellationFee::None,
+				false,
+				None,
 			);
 
 			assert_noop!(err, Error::<Test>::MaxSimultaneousContractReached);
@@ -300,6 +534,8 @@ mod create_contract {
 				RentFee::Tokens(TOKENS),
 				CancellationFee::None,
 				CancellationFee::None,
+				false,
+				None,
 			);
 
 			assert_noop!(err, Error::<Test>::NFTNotFound);
@@ -322,6 +558,8 @@ mod create_contract {
 				RentFee::Tokens(TOKENS),
 				CancellationFee::None,
 				CancellationFee::None,
+				false,
+				None,
 			);
 
 			assert_noop!(err, Error::<Test>::NotTheNFTOwner);
@@ -348,6 +586,8 @@ mod create_contract {
 				RentFee::Tokens(TOKENS),
 				CancellationFee::None,
 				CancellationFee::None,
+				false,
+				None,
 			);
 			assert_noop!(err, Error::<Test>::ContractNFTNotInAValidState);
 
@@ -365,6 +605,8 @@ mod create_contract {
 				RentFee::Tokens(TOKENS),
 				CancellationFee::None,
 				CancellationFee::None,
+				false,
+				None,
 			);
 			assert_noop!(err, Error::<Test>::ContractNFTNotInAValidState);
 
@@ -382,6 +624,8 @@ mod create_contract {
 				RentFee::Tokens(TOKENS),
 				CancellationFee::None,
 				CancellationFee::None,
+				false,
+				None,
 			);
 			assert_noop!(err, Error::<Test>::ContractNFTNotInAValidState);
 
@@ -399,6 +643,8 @@ mod create_contract {
 				RentFee::Tokens(TOKENS),
 				CancellationFee::None,
 				CancellationFee::None,
+				false,
+				None,
 			);
 			assert_noop!(err, Error::<Test>::ContractNFTNotInAValidState);
 
@@ -416,6 +662,8 @@ mod create_contract {
 				RentFee::Tokens(TOKENS),
 				CancellationFee::None,
 				CancellationFee::None,
+				false,
+				None,
 			);
 			assert_noop!(err, Error::<Test>::ContractNFTNotInAValidState);
 		})
@@ -437,6 +685,8 @@ mod create_contract {
 				RentFee::Tokens(TOKENS),
 				CancellationFee::None,
 				CancellationFee::None,
+				false,
+				None,
 			);
 
 			assert_noop!(err, Error::<Test>::DurationExceedsMaximumLimit);
@@ -459,6 +709,8 @@ mod create_contract {
 				RentFee::Tokens(TOKENS),
 				CancellationFee::None,
 				CancellationFee::None,
+				false,
+				None,
 			);
 			assert_noop!(err, Error::<Test>::DurationInvalid);
 
@@ -470,12 +722,15 @@ mod create_contract {
 					period_length: 0,
 					max_duration: None,
 					is_changeable: false,
+					grace_period: 0,
 				}),
 				AcceptanceType::AutoAcceptance(None),
 				false,
 				RentFee::Tokens(TOKENS),
 				CancellationFee::None,
 				CancellationFee::None,
+				false,
+				None,
 			);
 			assert_noop!(err, Error::<Test>::DurationInvalid);
 
@@ -487,17 +742,48 @@ mod create_contract {
 					period_length: BLOCK_DURATION,
 					max_duration: Some(0),
 					is_changeable: false,
+					grace_period: 0,
 				}),
 				AcceptanceType::AutoAcceptance(None),
 				false,
 				RentFee::Tokens(TOKENS),
 				CancellationFee::None,
 				CancellationFee::None,
+				false,
+				None,
 			);
 			assert_noop!(err, Error::<Test>::DurationInvalid);
 		})
 	}
 
+	#[test]
+	fn grace_period_too_long() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+
+			// Try to create a subscription contract with a grace period as long as the period.
+			let err = Rent::create_contract(
+				alice,
+				ALICE_NFT_ID_6,
+				DurationInput::Subscription(SubscriptionInput {
+					period_length: BLOCK_DURATION,
+					max_duration: None,
+					is_changeable: false,
+					grace_period: BLOCK_DURATION,
+				}),
+				AcceptanceType::AutoAcceptance(None),
+				false,
+				RentFee::Tokens(TOKENS),
+				CancellationFee::None,
+				CancellationFee::None,
+				false,
+				None,
+			);
+			assert_noop!(err, Error::<Test>::GracePeriodTooLong);
+		})
+	}
+
 	#[test]
 	fn duration_and_rent_fee_mismatch() {
 		ExtBuilder::new_build(None).execute_with(|| {
@@ -512,12 +798,15 @@ mod create_contract {
 					period_length: BLOCK_DURATION,
 					max_duration: None,
 					is_changeable: false,
+					grace_period: 0,
 				}),
 				AcceptanceType::AutoAcceptance(None),
 				false,
 				RentFee::NFT(BOB_NFT_ID_0),
 				CancellationFee::None,
 				CancellationFee::None,
+				false,
+				None,
 			);
 
 			assert_noop!(err, Error::<Test>::DurationAndRentFeeMismatch);
@@ -538,12 +827,15 @@ mod create_contract {
 					period_length: BLOCK_DURATION,
 					max_duration: None,
 					is_changeable: false,
+					grace_period: 0,
 				}),
 				AcceptanceType::AutoAcceptance(None),
 				false,
 				RentFee::Tokens(TOKENS),
 				CancellationFee::FlexibleTokens(LESS_TOKENS),
 				CancellationFee::None,
+				false,
+				None,
 			);
 
 			assert_noop!(err, Error::<Test>::DurationAndCancellationFeeMismatch);
@@ -564,12 +856,15 @@ mod create_contract {
 					period_length: BLOCK_DURATION,
 					max_duration: None,
 					is_changeable: false,
+					grace_period: 0,
 				}),
 				AcceptanceType::AutoAcceptance(None),
 				false,
 				RentFee::Tokens(TOKENS),
 				CancellationFee::None,
 				CancellationFee::FlexibleTokens(LESS_TOKENS),
+				false,
+				None,
 			);
 
 			assert_noop!(err, Error::<Test>::DurationAndCancellationFeeMismatch);
@@ -592,6 +887,8 @@ mod create_contract {
 				RentFee::NFT(INVALID_NFT),
 				CancellationFee::None,
 				CancellationFee::None,
+				false,
+				None,
 			);
 
 			assert_noop!(err, Error::<Test>::RentNFTNotFound);
@@ -614,6 +911,8 @@ mod create_contract {
 				RentFee::Tokens(TOKENS),
 				CancellationFee::None,
 				CancellationFee::NFT(INVALID_NFT),
+				false,
+				None,
 			);
 
 			assert_noop!(err, Error::<Test>::CancellationNFTNotFound);
@@ -636,6 +935,8 @@ mod create_contract {
 				RentFee::Tokens(TOKENS),
 				CancellationFee::FixedTokens(1),
 				CancellationFee::None,
+				false,
+				None,
 			);
 
 			assert_noop!(err, Error::<Test>::AmountTooLow);
@@ -658,6 +959,8 @@ mod create_contract {
 				RentFee::Tokens(TOKENS),
 				CancellationFee::None,
 				CancellationFee::FixedTokens(1),
+				false,
+				None,
 			);
 
 			assert_noop!(err, Error::<Test>::AmountTooLow);
@@ -680,6 +983,8 @@ mod create_contract {
 				RentFee::Tokens(TOKENS),
 				CancellationFee::FixedTokens(1_000_000),
 				CancellationFee::None,
+				false,
+				None,
 			);
 
 			assert_noop!(err, Error::<Test>::NotEnoughFundsForCancellationFee);
@@ -702,6 +1007,8 @@ mod create_contract {
 				RentFee::Tokens(TOKENS),
 				CancellationFee::NFT(INVALID_NFT),
 				CancellationFee::None,
+				false,
+				None,
 			);
 
 			assert_noop!(err, Error::<Test>::CancellationNFTNotFound);
@@ -724,6 +1031,8 @@ mod create_contract {
 				RentFee::Tokens(TOKENS),
 				CancellationFee::NFT(BOB_NFT_ID_0),
 				CancellationFee::None,
+				false,
+				None,
 			);
 
 			assert_noop!(err, Error::<Test>::CallerDoesNotOwnCancellationNFT);
@@ -751,6 +1060,8 @@ mod create_contract {
 				RentFee::Tokens(TOKENS),
 				CancellationFee::NFT(ALICE_NFT_ID_7),
 				CancellationFee::None,
+				false,
+				None,
 			);
 
 			assert_noop!(err, Error::<Test>::CancellationNFTNotInValidState);
@@ -784,6 +1095,27 @@ mod cancel_contract {
 		})
 	}
 
+	#[test]
+	fn cancel_contract_with_offers() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			prepare_offer_contract();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let bob: mock::RuntimeOrigin = origin(BOB);
+			let bob_balance = Balances::free_balance(BOB);
+
+			Rent::make_rent_offer(bob, ALICE_NFT_ID_6, CREATION_BLOCK, Some(TOKENS), None)
+				.unwrap();
+
+			// Cancel contract.
+			Rent::cancel_contract(alice, ALICE_NFT_ID_6).unwrap();
+			// State check.
+			assert!(Rent::offers(ALICE_NFT_ID_6).is_none());
+			assert_eq!(Balances::reserved_balance(BOB), 0);
+			assert_eq!(Balances::free_balance(BOB), bob_balance);
+		})
+	}
+
 	#[test]
 	fn contract_not_found() {
 		ExtBuilder::new_build(None).execute_with(|| {
@@ -826,6 +1158,29 @@ mod cancel_contract {
 mod revoke_contract {
 	use super::*;
 
+	#[test]
+	fn revoke_contract_with_bundled_nfts() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			prepare_bundle_contract();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let bob: mock::RuntimeOrigin = origin(BOB);
+
+			Rent::rent(bob, ALICE_NFT_ID_6, CREATION_BLOCK).unwrap();
+			assert_eq!(NFT::current_nft_user(ALICE_NFT_ID_7), Some(BOB));
+
+			// Revoke.
+			Rent::revoke_contract(alice, ALICE_NFT_ID_6).unwrap();
+
+			// State check.
+			let nft = NFT::nfts(ALICE_NFT_ID_7).unwrap();
+			assert!(Rent::bundled_nfts(ALICE_NFT_ID_6).is_none());
+			assert!(Rent::bundle_parents(ALICE_NFT_ID_7).is_none());
+			assert!(!nft.state.is_rented);
+			assert!(NFT::nft_users(ALICE_NFT_ID_7).is_none());
+		})
+	}
+
 	#[test]
 	fn revoke_contract_by_renter_fixed() {
 		ExtBuilder::new_build(None).execute_with(|| {
@@ -846,6 +1201,8 @@ mod revoke_contract {
 			assert!(Rent::contracts(FIXED_AUTO_REV_NFT_TOKENS_TOKENS).is_none());
 			assert!(Rent::queues().available_queue.get(FIXED_AUTO_REV_NFT_TOKENS_TOKENS).is_none());
 			assert!(!nft.state.is_rented);
+			assert!(NFT::nft_users(FIXED_AUTO_REV_NFT_TOKENS_TOKENS).is_none());
+			assert!(NFT::has_access(FIXED_AUTO_REV_NFT_TOKENS_TOKENS, &ALICE));
 			assert_eq!(Balances::free_balance(ALICE), alice_balance);
 			assert_eq!(Balances::free_balance(BOB), bob_balance + 2 * LESS_TOKENS);
 			// Event check.
@@ -859,23 +1216,63 @@ mod revoke_contract {
 	}
 
 	#[test]
-	fn revoke_contract_by_rentee_fixed() {
+	fn revoke_contract_escrowed_rent_fee() {
 		ExtBuilder::new_build(None).execute_with(|| {
 			prepare_tests();
+			prepare_escrowed_contract();
 			let bob: mock::RuntimeOrigin = origin(BOB);
-
-			Rent::rent(bob.clone(), FIXED_AUTO_REV_NFT_TOKENS_TOKENS, CREATION_BLOCK).unwrap();
-
 			let alice_balance = Balances::free_balance(ALICE);
 			let bob_balance = Balances::free_balance(BOB);
 
+			Rent::rent(bob.clone(), ALICE_NFT_ID_6, CREATION_BLOCK).unwrap();
+			assert_eq!(Rent::escrowed_rent_fees(ALICE_NFT_ID_6), Some(TOKENS));
+			assert_eq!(Balances::free_balance(ALICE), alice_balance);
+			run_to_block(CREATION_BLOCK + 3 * BLOCK_DURATION / 10);
+
 			// Revoke.
-			Rent::revoke_contract(bob, FIXED_AUTO_REV_NFT_TOKENS_TOKENS).unwrap();
+			Rent::revoke_contract(bob, ALICE_NFT_ID_6).unwrap();
 
 			// State check.
-			let nft = NFT::nfts(FIXED_AUTO_REV_NFT_TOKENS_TOKENS).unwrap();
-			assert!(Rent::contracts(FIXED_AUTO_REV_NFT_TOKENS_TOKENS).is_none());
-			assert!(Rent::queues().available_queue.get(FIXED_AUTO_REV_NFT_TOKENS_TOKENS).is_none());
+			let earned = Permill::from_percent(30) * TOKENS;
+			assert!(Rent::contracts(ALICE_NFT_ID_6).is_none());
+			assert!(Rent::escrowed_rent_fees(ALICE_NFT_ID_6).is_none());
+			assert_eq!(Balances::free_balance(ALICE), alice_balance + earned);
+			assert_eq!(Balances::free_balance(BOB), bob_balance - earned);
+
+			// Event check.
+			let event = RentEvent::EscrowedRentFeeReleased {
+				nft_id: ALICE_NFT_ID_6,
+				renter: ALICE,
+				amount: earned,
+			};
+			System::assert_has_event(RuntimeEvent::Rent(event));
+			let event = RentEvent::EscrowedRentFeeRefunded {
+				nft_id: ALICE_NFT_ID_6,
+				rentee: BOB,
+				amount: TOKENS - earned,
+			};
+			System::assert_has_event(RuntimeEvent::Rent(event));
+		})
+	}
+
+	#[test]
+	fn revoke_contract_by_rentee_fixed() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let bob: mock::RuntimeOrigin = origin(BOB);
+
+			Rent::rent(bob.clone(), FIXED_AUTO_REV_NFT_TOKENS_TOKENS, CREATION_BLOCK).unwrap();
+
+			let alice_balance = Balances::free_balance(ALICE);
+			let bob_balance = Balances::free_balance(BOB);
+
+			// Revoke.
+			Rent::revoke_contract(bob, FIXED_AUTO_REV_NFT_TOKENS_TOKENS).unwrap();
+
+			// State check.
+			let nft = NFT::nfts(FIXED_AUTO_REV_NFT_TOKENS_TOKENS).unwrap();
+			assert!(Rent::contracts(FIXED_AUTO_REV_NFT_TOKENS_TOKENS).is_none());
+			assert!(Rent::queues().available_queue.get(FIXED_AUTO_REV_NFT_TOKENS_TOKENS).is_none());
 			assert!(!nft.state.is_rented);
 			assert_eq!(Balances::free_balance(ALICE), alice_balance + 2 * LESS_TOKENS);
 			assert_eq!(Balances::free_balance(BOB), bob_balance);
@@ -896,7 +1293,8 @@ mod revoke_contract {
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			let bob: mock::RuntimeOrigin = origin(BOB);
 
-			Rent::make_rent_offer(bob, FIXED_MANU_REV_NFT_NFT_NFT, CREATION_BLOCK).unwrap();
+			Rent::make_rent_offer(bob, FIXED_MANU_REV_NFT_NFT_NFT, CREATION_BLOCK, None, None)
+				.unwrap();
 			Rent::accept_rent_offer(alice.clone(), FIXED_MANU_REV_NFT_NFT_NFT, BOB).unwrap();
 
 			// Revoke.
@@ -928,7 +1326,14 @@ mod revoke_contract {
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			let bob: mock::RuntimeOrigin = origin(BOB);
 
-			Rent::make_rent_offer(bob.clone(), FIXED_MANU_REV_NFT_NFT_NFT, CREATION_BLOCK).unwrap();
+			Rent::make_rent_offer(
+				bob.clone(),
+				FIXED_MANU_REV_NFT_NFT_NFT,
+				CREATION_BLOCK,
+				None,
+				None,
+			)
+			.unwrap();
 			Rent::accept_rent_offer(alice, FIXED_MANU_REV_NFT_NFT_NFT, BOB).unwrap();
 
 			// Revoke.
@@ -1112,17 +1517,70 @@ mod rent {
 			assert!(Rent::queues().available_queue.get(FIXED_AUTO_REV_NFT_TOKENS_TOKENS).is_none());
 			assert!(Rent::queues().fixed_queue.get(FIXED_AUTO_REV_NFT_TOKENS_TOKENS).is_some());
 			assert!(nft.state.is_rented);
+			let user = NFT::nft_users(FIXED_AUTO_REV_NFT_TOKENS_TOKENS).unwrap();
+			assert_eq!(user.user, BOB);
+			assert_eq!(user.expires, CREATION_BLOCK + BLOCK_DURATION);
+			assert_eq!(NFT::current_nft_user(FIXED_AUTO_REV_NFT_TOKENS_TOKENS), Some(BOB));
+			assert!(NFT::has_access(FIXED_AUTO_REV_NFT_TOKENS_TOKENS, &BOB));
+			assert!(!NFT::has_access(FIXED_AUTO_REV_NFT_TOKENS_TOKENS, &ALICE));
 
 			// Event check.
 			let event = RentEvent::ContractStarted {
 				nft_id: FIXED_AUTO_REV_NFT_TOKENS_TOKENS,
 				rentee: BOB,
+				marketplace_cut: 0,
+			};
+			let event = RuntimeEvent::Rent(event);
+			System::assert_last_event(event);
+		})
+	}
+
+	#[test]
+	fn rent_through_marketplace() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			prepare_marketplace_contract(RentFee::Tokens(TOKENS));
+			let bob: mock::RuntimeOrigin = origin(BOB);
+			let alice_balance = Balances::free_balance(ALICE);
+			let bob_balance = Balances::free_balance(BOB);
+			let charlie_balance = Balances::free_balance(CHARLIE);
+
+			Rent::rent(bob, ALICE_NFT_ID_6, CREATION_BLOCK).unwrap();
+
+			// State check.
+			let cut = PERCENT_10 * TOKENS;
+			assert_eq!(Balances::free_balance(ALICE), alice_balance + TOKENS - cut);
+			assert_eq!(Balances::free_balance(BOB), bob_balance - TOKENS);
+			assert_eq!(Balances::free_balance(CHARLIE), charlie_balance + cut);
+
+			// Event check.
+			let event = RentEvent::ContractStarted {
+				nft_id: ALICE_NFT_ID_6,
+				rentee: BOB,
+				marketplace_cut: cut,
 			};
 			let event = RuntimeEvent::Rent(event);
 			System::assert_last_event(event);
 		})
 	}
 
+	#[test]
+	fn rent_escrowed_through_marketplace() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			prepare_marketplace_contract(RentFee::EscrowedTokens(TOKENS));
+			let bob: mock::RuntimeOrigin = origin(BOB);
+			let charlie_balance = Balances::free_balance(CHARLIE);
+
+			Rent::rent(bob, ALICE_NFT_ID_6, CREATION_BLOCK).unwrap();
+
+			// State check.
+			let cut = PERCENT_10 * TOKENS;
+			assert_eq!(Rent::escrowed_rent_fees(ALICE_NFT_ID_6), Some(TOKENS - cut));
+			assert_eq!(Balances::free_balance(CHARLIE), charlie_balance + cut);
+		})
+	}
+
 	#[test]
 	fn contract_not_found() {
 		ExtBuilder::new_build(None).execute_with(|| {
@@ -1275,13 +1733,20 @@ mod make_rent_offer {
 			prepare_tests();
 			let bob: mock::RuntimeOrigin = origin(BOB);
 
-			Rent::make_rent_offer(bob, SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK, CREATION_BLOCK)
-				.unwrap();
+			Rent::make_rent_offer(
+				bob,
+				SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK,
+				CREATION_BLOCK,
+				None,
+				None,
+			)
+			.unwrap();
 
 			// State check.
 			assert!(Rent::offers(SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK)
 				.unwrap()
-				.contains(&BOB));
+				.iter()
+				.any(|x| x.rentee == BOB));
 			assert!(Rent::queues()
 				.available_queue
 				.get(SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK)
@@ -1291,6 +1756,46 @@ mod make_rent_offer {
 			let event = RentEvent::ContractOfferCreated {
 				nft_id: SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK,
 				rentee: BOB,
+				rent_fee: None,
+				duration: None,
+			};
+			let event = RuntimeEvent::Rent(event);
+			System::assert_last_event(event);
+		})
+	}
+
+	#[test]
+	fn make_priced_rent_offer() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			prepare_offer_contract();
+			let bob: mock::RuntimeOrigin = origin(BOB);
+			let bob_balance = Balances::free_balance(BOB);
+
+			Rent::make_rent_offer(
+				bob,
+				ALICE_NFT_ID_6,
+				CREATION_BLOCK,
+				Some(LESS_TOKENS),
+				Some(BLOCK_MAX_DURATION),
+			)
+			.unwrap();
+
+			// State check.
+			let offers = Rent::offers(ALICE_NFT_ID_6).unwrap();
+			let offer = offers.iter().find(|x| x.rentee == BOB).unwrap();
+			assert_eq!(offer.rent_fee, Some(LESS_TOKENS));
+			assert_eq!(offer.duration, Some(BLOCK_MAX_DURATION));
+			assert_eq!(offer.reserved, LESS_TOKENS);
+			assert_eq!(Balances::reserved_balance(BOB), LESS_TOKENS);
+			assert_eq!(Balances::free_balance(BOB), bob_balance - LESS_TOKENS);
+
+			// Event check.
+			let event = RentEvent::ContractOfferCreated {
+				nft_id: ALICE_NFT_ID_6,
+				rentee: BOB,
+				rent_fee: Some(LESS_TOKENS),
+				duration: Some(BLOCK_MAX_DURATION),
 			};
 			let event = RuntimeEvent::Rent(event);
 			System::assert_last_event(event);
@@ -1303,7 +1808,7 @@ mod make_rent_offer {
 			prepare_tests();
 			let bob: mock::RuntimeOrigin = origin(BOB);
 
-			let err = Rent::make_rent_offer(bob, INVALID_NFT, CREATION_BLOCK);
+			let err = Rent::make_rent_offer(bob, INVALID_NFT, CREATION_BLOCK, None, None);
 			assert_noop!(err, Error::<Test>::ContractNotFound);
 		})
 	}
@@ -1314,7 +1819,13 @@ mod make_rent_offer {
 			prepare_tests();
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 
-			let err = Rent::make_rent_offer(alice, FIXED_MANU_REV_NFT_NFT_NFT, CREATION_BLOCK);
+			let err = Rent::make_rent_offer(
+				alice,
+				FIXED_MANU_REV_NFT_NFT_NFT,
+				CREATION_BLOCK,
+				None,
+				None,
+			);
 			assert_noop!(err, Error::<Test>::CannotRentOwnContract);
 		})
 	}
@@ -1325,7 +1836,13 @@ mod make_rent_offer {
 			prepare_tests();
 			let bob: mock::RuntimeOrigin = origin(BOB);
 
-			let err = Rent::make_rent_offer(bob, FIXED_AUTO_REV_NFT_NFT_NFT, CREATION_BLOCK);
+			let err = Rent::make_rent_offer(
+				bob,
+				FIXED_AUTO_REV_NFT_NFT_NFT,
+				CREATION_BLOCK,
+				None,
+				None,
+			);
 			assert_noop!(err, Error::<Test>::ContractDoesNotSupportOffers);
 		})
 	}
@@ -1336,7 +1853,13 @@ mod make_rent_offer {
 			prepare_tests();
 			let charlie: mock::RuntimeOrigin = origin(CHARLIE);
 
-			let err = Rent::make_rent_offer(charlie, FIXED_MANU_REV_NFT_NFT_NFT, CREATION_BLOCK);
+			let err = Rent::make_rent_offer(
+				charlie,
+				FIXED_MANU_REV_NFT_NFT_NFT,
+				CREATION_BLOCK,
+				None,
+				None,
+			);
 			assert_noop!(err, Error::<Test>::NotWhitelisted);
 		})
 	}
@@ -1360,12 +1883,14 @@ mod make_rent_offer {
 				RentFee::Tokens(TOKENS),
 				CancellationFee::FixedTokens(LESS_TOKENS),
 				CancellationFee::FixedTokens(LESS_TOKENS),
+				false,
+				None,
 			)
 			.unwrap();
 
 			Balances::set_balance(root(), BOB, TOKENS - 1, 0).unwrap();
 
-			let err = Rent::make_rent_offer(bob, nft_id, CREATION_BLOCK);
+			let err = Rent::make_rent_offer(bob, nft_id, CREATION_BLOCK, None, None);
 			assert_noop!(err, Error::<Test>::NotEnoughFundsForRentFee);
 		})
 	}
@@ -1389,12 +1914,14 @@ mod make_rent_offer {
 				RentFee::Tokens(LESS_TOKENS),
 				CancellationFee::FixedTokens(TOKENS),
 				CancellationFee::FixedTokens(TOKENS),
+				false,
+				None,
 			)
 			.unwrap();
 
 			Balances::set_balance(root(), BOB, LESS_TOKENS, 0).unwrap();
 
-			let err = Rent::make_rent_offer(bob, nft_id, CREATION_BLOCK);
+			let err = Rent::make_rent_offer(bob, nft_id, CREATION_BLOCK, None, None);
 			assert_noop!(err, Error::<Test>::NotEnoughFundsForCancellationFee);
 		})
 	}
@@ -1418,12 +1945,14 @@ mod make_rent_offer {
 				RentFee::Tokens(TOKENS),
 				CancellationFee::FixedTokens(TOKENS),
 				CancellationFee::FixedTokens(TOKENS),
+				false,
+				None,
 			)
 			.unwrap();
 
 			Balances::set_balance(root(), BOB, TOKENS, 0).unwrap();
 
-			let err = Rent::make_rent_offer(bob, nft_id, CREATION_BLOCK);
+			let err = Rent::make_rent_offer(bob, nft_id, CREATION_BLOCK, None, None);
 			assert_noop!(err, Error::<Test>::NotEnoughFundsForFees);
 		})
 	}
@@ -1438,7 +1967,13 @@ mod make_rent_offer {
 			nft.owner = ALICE;
 			NFT::set_nft(BOB_NFT_ID_1, nft).unwrap();
 
-			let err = Rent::make_rent_offer(bob, FIXED_MANU_REV_NFT_NFT_NFT, CREATION_BLOCK);
+			let err = Rent::make_rent_offer(
+				bob,
+				FIXED_MANU_REV_NFT_NFT_NFT,
+				CREATION_BLOCK,
+				None,
+				None,
+			);
 			assert_noop!(err, Error::<Test>::CallerDoesNotOwnRentNFT);
 		})
 	}
@@ -1453,7 +1988,13 @@ mod make_rent_offer {
 			nft.state.is_listed = true;
 			NFT::set_nft(BOB_NFT_ID_1, nft).unwrap();
 
-			let err = Rent::make_rent_offer(bob, FIXED_MANU_REV_NFT_NFT_NFT, CREATION_BLOCK);
+			let err = Rent::make_rent_offer(
+				bob,
+				FIXED_MANU_REV_NFT_NFT_NFT,
+				CREATION_BLOCK,
+				None,
+				None,
+			);
 			assert_noop!(err, Error::<Test>::RentNFTNotInValidState);
 		})
 	}
@@ -1468,7 +2009,13 @@ mod make_rent_offer {
 			nft.owner = ALICE;
 			NFT::set_nft(BOB_NFT_ID_0, nft).unwrap();
 
-			let err = Rent::make_rent_offer(bob, FIXED_MANU_REV_NFT_NFT_NFT, CREATION_BLOCK);
+			let err = Rent::make_rent_offer(
+				bob,
+				FIXED_MANU_REV_NFT_NFT_NFT,
+				CREATION_BLOCK,
+				None,
+				None,
+			);
 			assert_noop!(err, Error::<Test>::CallerDoesNotOwnCancellationNFT);
 		})
 	}
@@ -1483,11 +2030,69 @@ mod make_rent_offer {
 			nft.state.is_listed = true;
 			NFT::set_nft(BOB_NFT_ID_0, nft).unwrap();
 
-			let err = Rent::make_rent_offer(bob, FIXED_MANU_REV_NFT_NFT_NFT, CREATION_BLOCK);
+			let err = Rent::make_rent_offer(
+				bob,
+				FIXED_MANU_REV_NFT_NFT_NFT,
+				CREATION_BLOCK,
+				None,
+				None,
+			);
 			assert_noop!(err, Error::<Test>::CancellationNFTNotInValidState);
 		})
 	}
 
+	#[test]
+	fn offered_rent_fee_not_allowed() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let bob: mock::RuntimeOrigin = origin(BOB);
+
+			let err = Rent::make_rent_offer(
+				bob,
+				FIXED_MANU_REV_NFT_NFT_NFT,
+				CREATION_BLOCK,
+				Some(LESS_TOKENS),
+				None,
+			);
+			assert_noop!(err, Error::<Test>::OfferedRentFeeNotAllowed);
+		})
+	}
+
+	#[test]
+	fn contract_is_not_fixed() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let bob: mock::RuntimeOrigin = origin(BOB);
+
+			let err = Rent::make_rent_offer(
+				bob,
+				SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK,
+				CREATION_BLOCK,
+				None,
+				Some(BLOCK_MAX_DURATION),
+			);
+			assert_noop!(err, Error::<Test>::ContractIsNotFixed);
+		})
+	}
+
+	#[test]
+	fn offered_duration_exceeds_maximum_limit() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			prepare_offer_contract();
+			let bob: mock::RuntimeOrigin = origin(BOB);
+
+			let err = Rent::make_rent_offer(
+				bob,
+				ALICE_NFT_ID_6,
+				CREATION_BLOCK,
+				None,
+				Some(BLOCK_MAX_DURATION + 1),
+			);
+			assert_noop!(err, Error::<Test>::DurationExceedsMaximumLimit);
+		})
+	}
+
 	#[test]
 	fn contract_does_not_match() {
 		ExtBuilder::new_build(None).execute_with(|| {
@@ -1499,6 +2104,8 @@ mod make_rent_offer {
 				bob,
 				SUBSC_AUTO_NOREV_NOT_CHANGEABLE_TOK_NONE_FIXTOK,
 				CREATION_BLOCK + 1,
+				None,
+				None,
 			);
 			assert_noop!(err, Error::<Test>::ContractDoesNotMatch);
 		})
@@ -1514,14 +2121,20 @@ mod accept_rent_offer {
 			prepare_tests();
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			let bob: mock::RuntimeOrigin = origin(BOB);
-
-			// Make rent offer.
-			Rent::make_rent_offer(bob, SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK, CREATION_BLOCK)
-				.unwrap();
-
 			let alice_balance = Balances::free_balance(ALICE);
 			let bob_balance = Balances::free_balance(BOB);
 
+			// Make rent offer.
+			Rent::make_rent_offer(
+				bob,
+				SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK,
+				CREATION_BLOCK,
+				None,
+				None,
+			)
+			.unwrap();
+			assert_eq!(Balances::reserved_balance(BOB), TOKENS);
+
 			// Accept rent offer
 			Rent::accept_rent_offer(alice, SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK, BOB)
 				.unwrap();
@@ -1542,16 +2155,61 @@ mod accept_rent_offer {
 			assert!(nft.state.is_rented);
 			assert_eq!(Balances::free_balance(ALICE), alice_balance + TOKENS);
 			assert_eq!(Balances::free_balance(BOB), bob_balance - TOKENS - LESS_TOKENS);
+			assert_eq!(Balances::reserved_balance(BOB), 0);
 			// Event check.
 			let event = RentEvent::ContractStarted {
 				nft_id: SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK,
 				rentee: BOB,
+				marketplace_cut: 0,
 			};
 			let event = RuntimeEvent::Rent(event);
 			System::assert_last_event(event);
 		})
 	}
 
+	#[test]
+	fn accept_priced_rent_offer() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			prepare_offer_contract();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let bob: mock::RuntimeOrigin = origin(BOB);
+			let charlie: mock::RuntimeOrigin = origin(CHARLIE);
+			let alice_balance = Balances::free_balance(ALICE);
+			let bob_balance = Balances::free_balance(BOB);
+			let charlie_balance = Balances::free_balance(CHARLIE);
+
+			Rent::make_rent_offer(bob, ALICE_NFT_ID_6, CREATION_BLOCK, None, None).unwrap();
+			Rent::make_rent_offer(
+				charlie,
+				ALICE_NFT_ID_6,
+				CREATION_BLOCK,
+				Some(LESS_TOKENS),
+				Some(BLOCK_MAX_DURATION),
+			)
+			.unwrap();
+
+			// Accept the priced offer.
+			Rent::accept_rent_offer(alice, ALICE_NFT_ID_6, CHARLIE).unwrap();
+
+			// State check.
+			let contract = Rent::contracts(ALICE_NFT_ID_6).unwrap();
+			assert_eq!(contract.rentee, Some(CHARLIE));
+			assert_eq!(contract.rent_fee, RentFee::Tokens(LESS_TOKENS));
+			assert_eq!(contract.duration, Duration::Fixed(BLOCK_MAX_DURATION));
+			assert!(Rent::offers(ALICE_NFT_ID_6).is_none());
+			assert_eq!(
+				Rent::queues().fixed_queue.get(ALICE_NFT_ID_6),
+				Some(CREATION_BLOCK + BLOCK_MAX_DURATION)
+			);
+			assert_eq!(Balances::reserved_balance(BOB), 0);
+			assert_eq!(Balances::reserved_balance(CHARLIE), 0);
+			assert_eq!(Balances::free_balance(ALICE), alice_balance + LESS_TOKENS);
+			assert_eq!(Balances::free_balance(BOB), bob_balance);
+			assert_eq!(Balances::free_balance(CHARLIE), charlie_balance - LESS_TOKENS);
+		})
+	}
+
 	#[test]
 	fn contract_not_found() {
 		ExtBuilder::new_build(None).execute_with(|| {
@@ -1583,8 +2241,14 @@ mod accept_rent_offer {
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			let bob: mock::RuntimeOrigin = origin(BOB);
 
-			Rent::make_rent_offer(bob, SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK, CREATION_BLOCK)
-				.unwrap();
+			Rent::make_rent_offer(
+				bob,
+				SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK,
+				CREATION_BLOCK,
+				None,
+				None,
+			)
+			.unwrap();
 
 			Balances::set_balance(root(), BOB, 0, 0).unwrap();
 
@@ -1602,8 +2266,14 @@ mod accept_rent_offer {
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			let bob: mock::RuntimeOrigin = origin(BOB);
 
-			Rent::make_rent_offer(bob, SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK, CREATION_BLOCK)
-				.unwrap();
+			Rent::make_rent_offer(
+				bob,
+				SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK,
+				CREATION_BLOCK,
+				None,
+				None,
+			)
+			.unwrap();
 
 			Balances::set_balance(root(), BOB, TOKENS + 1, 0).unwrap();
 
@@ -1621,7 +2291,8 @@ mod accept_rent_offer {
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			let bob: mock::RuntimeOrigin = origin(BOB);
 
-			Rent::make_rent_offer(bob, FIXED_MANU_REV_NFT_NFT_NFT, CREATION_BLOCK).unwrap();
+			Rent::make_rent_offer(bob, FIXED_MANU_REV_NFT_NFT_NFT, CREATION_BLOCK, None, None)
+				.unwrap();
 
 			let mut nft = NFT::get_nft(BOB_NFT_ID_1).unwrap();
 			nft.owner = ALICE;
@@ -1640,7 +2311,8 @@ mod accept_rent_offer {
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			let bob: mock::RuntimeOrigin = origin(BOB);
 
-			Rent::make_rent_offer(bob, FIXED_MANU_REV_NFT_NFT_NFT, CREATION_BLOCK).unwrap();
+			Rent::make_rent_offer(bob, FIXED_MANU_REV_NFT_NFT_NFT, CREATION_BLOCK, None, None)
+				.unwrap();
 
 			let mut nft = NFT::get_nft(BOB_NFT_ID_1).unwrap();
 			nft.state.is_listed = true;
@@ -1659,7 +2331,8 @@ mod accept_rent_offer {
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			let bob: mock::RuntimeOrigin = origin(BOB);
 
-			Rent::make_rent_offer(bob, FIXED_MANU_REV_NFT_NFT_NFT, CREATION_BLOCK).unwrap();
+			Rent::make_rent_offer(bob, FIXED_MANU_REV_NFT_NFT_NFT, CREATION_BLOCK, None, None)
+				.unwrap();
 
 			let mut nft = NFT::get_nft(BOB_NFT_ID_0).unwrap();
 			nft.owner = ALICE;
@@ -1678,7 +2351,8 @@ mod accept_rent_offer {
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			let bob: mock::RuntimeOrigin = origin(BOB);
 
-			Rent::make_rent_offer(bob, FIXED_MANU_REV_NFT_NFT_NFT, CREATION_BLOCK).unwrap();
+			Rent::make_rent_offer(bob, FIXED_MANU_REV_NFT_NFT_NFT, CREATION_BLOCK, None, None)
+				.unwrap();
 
 			let mut nft = NFT::get_nft(BOB_NFT_ID_0).unwrap();
 			nft.state.is_listed = true;
@@ -1700,14 +2374,21 @@ mod retract_rent_offer {
 			prepare_tests();
 			let bob: mock::RuntimeOrigin = origin(BOB);
 
-			Rent::make_rent_offer(bob.clone(), FIXED_MANU_REV_NFT_NFT_NFT, CREATION_BLOCK).unwrap();
-			let offers = Rent::offers(FIXED_MANU_REV_NFT_NFT_NFT).unwrap();
-			assert!(offers.contains(&BOB));
-
+			Rent::make_rent_offer(
+				bob.clone(),
+				FIXED_MANU_REV_NFT_NFT_NFT,
+				CREATION_BLOCK,
+				None,
+				None,
+			)
+			.unwrap();
+			let offers = Rent::offers(FIXED_MANU_REV_NFT_NFT_NFT).unwrap();
+			assert!(offers.iter().any(|x| x.rentee == BOB));
+
 			// Retract offer.
 			Rent::retract_rent_offer(bob, FIXED_MANU_REV_NFT_NFT_NFT).unwrap();
 			let offers = Rent::offers(FIXED_MANU_REV_NFT_NFT_NFT).unwrap();
-			assert!(!offers.contains(&BOB));
+			assert!(!offers.iter().any(|x| x.rentee == BOB));
 
 			// Event check.
 			let event = RentEvent::ContractOfferRetracted {
@@ -1719,6 +2400,25 @@ mod retract_rent_offer {
 		})
 	}
 
+	#[test]
+	fn retract_priced_rent_offer() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			prepare_offer_contract();
+			let bob: mock::RuntimeOrigin = origin(BOB);
+			let bob_balance = Balances::free_balance(BOB);
+
+			Rent::make_rent_offer(bob.clone(), ALICE_NFT_ID_6, CREATION_BLOCK, Some(TOKENS), None)
+				.unwrap();
+			assert_eq!(Balances::reserved_balance(BOB), TOKENS);
+
+			// Retract offer.
+			Rent::retract_rent_offer(bob, ALICE_NFT_ID_6).unwrap();
+			assert_eq!(Balances::reserved_balance(BOB), 0);
+			assert_eq!(Balances::free_balance(BOB), bob_balance);
+		})
+	}
+
 	#[test]
 	fn no_offers_for_this_contract() {
 		ExtBuilder::new_build(None).execute_with(|| {
@@ -1738,9 +2438,10 @@ mod retract_rent_offer {
 			let bob: mock::RuntimeOrigin = origin(BOB);
 			let charlie: mock::RuntimeOrigin = origin(CHARLIE);
 
-			Rent::make_rent_offer(bob, FIXED_MANU_REV_NFT_NFT_NFT, CREATION_BLOCK).unwrap();
+			Rent::make_rent_offer(bob, FIXED_MANU_REV_NFT_NFT_NFT, CREATION_BLOCK, None, None)
+				.unwrap();
 			let offers = Rent::offers(FIXED_MANU_REV_NFT_NFT_NFT).unwrap();
-			assert!(offers.contains(&BOB));
+			assert!(offers.iter().any(|x| x.rentee == BOB));
 
 			// Retract offer.
 			let err = Rent::retract_rent_offer(charlie, FIXED_MANU_REV_NFT_NFT_NFT);
@@ -1759,8 +2460,14 @@ mod change_subscription_terms {
 			let alice: mock::RuntimeOrigin = origin(ALICE);
 			let bob: mock::RuntimeOrigin = origin(BOB);
 
-			Rent::make_rent_offer(bob, SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK, CREATION_BLOCK)
-				.unwrap();
+			Rent::make_rent_offer(
+				bob,
+				SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK,
+				CREATION_BLOCK,
+				None,
+				None,
+			)
+			.unwrap();
 			Rent::accept_rent_offer(
 				alice.clone(),
 				SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK,
@@ -1918,6 +2625,8 @@ mod accept_subscription_terms {
 				bob.clone(),
 				SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK,
 				CREATION_BLOCK,
+				None,
+				None,
 			)
 			.unwrap();
 			Rent::accept_rent_offer(
@@ -1993,6 +2702,8 @@ mod accept_subscription_terms {
 				bob.clone(),
 				SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK,
 				CREATION_BLOCK,
+				None,
+				None,
 			)
 			.unwrap();
 			Rent::accept_rent_offer(
@@ -2037,6 +2748,8 @@ mod accept_subscription_terms {
 				bob.clone(),
 				SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK,
 				CREATION_BLOCK,
+				None,
+				None,
 			)
 			.unwrap();
 			Rent::accept_rent_offer(alice, SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK, BOB)
@@ -2066,6 +2779,8 @@ mod accept_subscription_terms {
 				bob.clone(),
 				SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK,
 				CREATION_BLOCK,
+				None,
+				None,
 			)
 			.unwrap();
 			Rent::accept_rent_offer(
@@ -2132,3 +2847,844 @@ mod accept_subscription_terms {
 		})
 	}
 }
+
+mod claim_escrowed_rent_fee {
+	use super::*;
+
+	#[test]
+	fn claim_escrowed_rent_fee() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			prepare_escrowed_contract();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let bob: mock::RuntimeOrigin = origin(BOB);
+			let alice_balance = Balances::free_balance(ALICE);
+
+			Rent::rent(bob, ALICE_NFT_ID_6, CREATION_BLOCK).unwrap();
+			run_to_block(CREATION_BLOCK + BLOCK_DURATION / 2);
+
+			// Claim.
+			Rent::claim_escrowed_rent_fee(alice.clone(), ALICE_NFT_ID_6).unwrap();
+
+			// State check.
+			let earned = Permill::from_percent(50) * TOKENS;
+			assert_eq!(Rent::escrowed_rent_fees(ALICE_NFT_ID_6), Some(TOKENS - earned));
+			assert_eq!(Balances::free_balance(ALICE), alice_balance + earned);
+
+			// Event check.
+			let event = RentEvent::EscrowedRentFeeReleased {
+				nft_id: ALICE_NFT_ID_6,
+				renter: ALICE,
+				amount: earned,
+			};
+			System::assert_last_event(RuntimeEvent::Rent(event));
+
+			// Claiming again in the same block releases nothing.
+			Rent::claim_escrowed_rent_fee(alice, ALICE_NFT_ID_6).unwrap();
+			assert_eq!(Balances::free_balance(ALICE), alice_balance + earned);
+		})
+	}
+
+	#[test]
+	fn contract_not_found() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+
+			let err = Rent::claim_escrowed_rent_fee(alice, INVALID_NFT);
+			assert_noop!(err, Error::<Test>::ContractNotFound);
+		})
+	}
+
+	#[test]
+	fn not_the_contract_owner() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			prepare_escrowed_contract();
+			let bob: mock::RuntimeOrigin = origin(BOB);
+
+			Rent::rent(bob.clone(), ALICE_NFT_ID_6, CREATION_BLOCK).unwrap();
+
+			let err = Rent::claim_escrowed_rent_fee(bob, ALICE_NFT_ID_6);
+			assert_noop!(err, Error::<Test>::NotTheContractOwner);
+		})
+	}
+
+	#[test]
+	fn no_escrowed_rent_fee() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let bob: mock::RuntimeOrigin = origin(BOB);
+
+			Rent::rent(bob, FIXED_AUTO_REV_NFT_TOKENS_TOKENS, CREATION_BLOCK).unwrap();
+
+			let err = Rent::claim_escrowed_rent_fee(alice, FIXED_AUTO_REV_NFT_TOKENS_TOKENS);
+			assert_noop!(err, Error::<Test>::NoEscrowedRentFee);
+		})
+	}
+}
+
+mod create_sublet {
+	use super::*;
+
+	#[test]
+	fn create_sublet() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			prepare_sublet_contract();
+
+			// State check.
+			let sublet = Rent::sublets(ALICE_NFT_ID_6).unwrap();
+			assert_eq!(sublet, SubletContractData::new(BOB, SUBLET_DURATION, LESS_TOKENS));
+
+			// Event check.
+			let event = RentEvent::SubletCreated {
+				nft_id: ALICE_NFT_ID_6,
+				renter: BOB,
+				parent_renter: ALICE,
+				duration: SUBLET_DURATION,
+				rent_fee: LESS_TOKENS,
+			};
+			let event = RuntimeEvent::Rent(event);
+			System::assert_last_event(event);
+		})
+	}
+
+	#[test]
+	fn not_the_contract_rentee() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			prepare_sublet_contract();
+			let charlie: mock::RuntimeOrigin = origin(CHARLIE);
+
+			let err = Rent::create_sublet(charlie, ALICE_NFT_ID_6, SUBLET_DURATION, LESS_TOKENS);
+			assert_noop!(err, Error::<Test>::NotTheContractRentee);
+		})
+	}
+
+	#[test]
+	fn sublet_not_allowed() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let bob: mock::RuntimeOrigin = origin(BOB);
+
+			Rent::rent(bob.clone(), FIXED_AUTO_REV_NFT_TOKENS_TOKENS, CREATION_BLOCK).unwrap();
+
+			let err = Rent::create_sublet(
+				bob,
+				FIXED_AUTO_REV_NFT_TOKENS_TOKENS,
+				SUBLET_DURATION,
+				LESS_TOKENS,
+			);
+			assert_noop!(err, Error::<Test>::SubletNotAllowed);
+		})
+	}
+
+	#[test]
+	fn sublet_already_exists() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			prepare_sublet_contract();
+			let bob: mock::RuntimeOrigin = origin(BOB);
+
+			let err = Rent::create_sublet(bob, ALICE_NFT_ID_6, SUBLET_DURATION, LESS_TOKENS);
+			assert_noop!(err, Error::<Test>::SubletAlreadyExists);
+		})
+	}
+
+	#[test]
+	fn sublet_exceeds_contract_duration() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			prepare_sublet_contract();
+			let bob: mock::RuntimeOrigin = origin(BOB);
+
+			Rent::cancel_sublet(bob.clone(), ALICE_NFT_ID_6).unwrap();
+
+			let err = Rent::create_sublet(bob, ALICE_NFT_ID_6, BLOCK_DURATION, LESS_TOKENS);
+			assert_noop!(err, Error::<Test>::SubletExceedsContractDuration);
+		})
+	}
+}
+
+mod rent_sublet {
+	use super::*;
+
+	#[test]
+	fn rent_sublet() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			prepare_sublet_contract();
+			let charlie: mock::RuntimeOrigin = origin(CHARLIE);
+			let bob_balance = Balances::free_balance(BOB);
+			let charlie_balance = Balances::free_balance(CHARLIE);
+			let pallet_balance = Balances::free_balance(Rent::account_id());
+
+			Rent::rent_sublet(charlie, ALICE_NFT_ID_6).unwrap();
+
+			// State check.
+			let now = System::block_number();
+			let sublet = Rent::sublets(ALICE_NFT_ID_6).unwrap();
+			assert_eq!(sublet.rentee, Some(CHARLIE));
+			assert_eq!(sublet.start_block, Some(now));
+			assert_eq!(Rent::sublet_queue().get(ALICE_NFT_ID_6), Some(now + SUBLET_DURATION));
+			assert_eq!(NFT::current_nft_user(ALICE_NFT_ID_6), Some(CHARLIE));
+			assert_eq!(Rent::escrowed_sublet_fees(ALICE_NFT_ID_6), Some(LESS_TOKENS));
+			assert_eq!(Balances::free_balance(Rent::account_id()), pallet_balance + LESS_TOKENS);
+			assert_eq!(Balances::free_balance(BOB), bob_balance);
+			assert_eq!(Balances::free_balance(CHARLIE), charlie_balance - LESS_TOKENS);
+
+			// Event check.
+			let event = RentEvent::SubletStarted { nft_id: ALICE_NFT_ID_6, rentee: CHARLIE };
+			let event = RuntimeEvent::Rent(event);
+			System::assert_last_event(event);
+		})
+	}
+
+	#[test]
+	fn sublet_not_found() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let charlie: mock::RuntimeOrigin = origin(CHARLIE);
+
+			let err = Rent::rent_sublet(charlie, ALICE_NFT_ID_6);
+			assert_noop!(err, Error::<Test>::SubletNotFound);
+		})
+	}
+
+	#[test]
+	fn cannot_rent_own_contract() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			prepare_sublet_contract();
+			let bob: mock::RuntimeOrigin = origin(BOB);
+
+			let err = Rent::rent_sublet(bob, ALICE_NFT_ID_6);
+			assert_noop!(err, Error::<Test>::CannotRentOwnContract);
+		})
+	}
+
+	#[test]
+	fn cannot_rent_started_contract() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			prepare_sublet_contract();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let charlie: mock::RuntimeOrigin = origin(CHARLIE);
+
+			Rent::rent_sublet(charlie, ALICE_NFT_ID_6).unwrap();
+
+			let err = Rent::rent_sublet(alice, ALICE_NFT_ID_6);
+			assert_noop!(err, Error::<Test>::CannotRentStartedContract);
+		})
+	}
+
+	#[test]
+	fn sublet_exceeds_contract_duration() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			prepare_sublet_contract();
+			let charlie: mock::RuntimeOrigin = origin(CHARLIE);
+
+			run_to_block(CREATION_BLOCK + BLOCK_DURATION - SUBLET_DURATION);
+
+			let err = Rent::rent_sublet(charlie, ALICE_NFT_ID_6);
+			assert_noop!(err, Error::<Test>::SubletExceedsContractDuration);
+		})
+	}
+}
+
+mod cancel_sublet {
+	use super::*;
+
+	#[test]
+	fn cancel_sublet() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			prepare_sublet_contract();
+			let bob: mock::RuntimeOrigin = origin(BOB);
+
+			Rent::cancel_sublet(bob, ALICE_NFT_ID_6).unwrap();
+
+			// State check.
+			assert!(Rent::sublets(ALICE_NFT_ID_6).is_none());
+
+			// Event check.
+			let event = RentEvent::SubletCanceled { nft_id: ALICE_NFT_ID_6 };
+			let event = RuntimeEvent::Rent(event);
+			System::assert_last_event(event);
+		})
+	}
+
+	#[test]
+	fn not_the_contract_owner() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			prepare_sublet_contract();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+
+			let err = Rent::cancel_sublet(alice, ALICE_NFT_ID_6);
+			assert_noop!(err, Error::<Test>::NotTheContractOwner);
+		})
+	}
+
+	#[test]
+	fn cannot_cancel_running_contract() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			prepare_sublet_contract();
+			let bob: mock::RuntimeOrigin = origin(BOB);
+			let charlie: mock::RuntimeOrigin = origin(CHARLIE);
+
+			Rent::rent_sublet(charlie, ALICE_NFT_ID_6).unwrap();
+
+			let err = Rent::cancel_sublet(bob, ALICE_NFT_ID_6);
+			assert_noop!(err, Error::<Test>::CannotCancelRunningContract);
+		})
+	}
+}
+
+mod propose_extension {
+	use super::*;
+
+	#[test]
+	fn propose_extension() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let bob: mock::RuntimeOrigin = origin(BOB);
+
+			Rent::rent(bob, FIXED_AUTO_REV_NFT_TOKENS_TOKENS, CREATION_BLOCK).unwrap();
+			Rent::propose_extension(alice, FIXED_AUTO_REV_NFT_TOKENS_TOKENS, BLOCK_DURATION, TOKENS)
+				.unwrap();
+
+			// State check.
+			let proposal = Rent::extensions(FIXED_AUTO_REV_NFT_TOKENS_TOKENS).unwrap();
+			assert_eq!(proposal.proposer, ALICE);
+			assert_eq!(proposal.extra_blocks, BLOCK_DURATION);
+			assert_eq!(proposal.extra_fee, TOKENS);
+
+			// Event check.
+			let event = RentEvent::ExtensionProposed {
+				nft_id: FIXED_AUTO_REV_NFT_TOKENS_TOKENS,
+				proposer: ALICE,
+				extra_blocks: BLOCK_DURATION,
+				extra_fee: TOKENS,
+			};
+			let event = RuntimeEvent::Rent(event);
+			System::assert_last_event(event);
+		})
+	}
+
+	#[test]
+	fn contract_not_found() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+
+			let err = Rent::propose_extension(alice, INVALID_NFT, BLOCK_DURATION, TOKENS);
+			assert_noop!(err, Error::<Test>::ContractNotFound);
+		})
+	}
+
+	#[test]
+	fn contract_is_not_running() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+
+			let err = Rent::propose_extension(
+				alice,
+				FIXED_AUTO_REV_NFT_TOKENS_TOKENS,
+				BLOCK_DURATION,
+				TOKENS,
+			);
+			assert_noop!(err, Error::<Test>::ContractIsNotRunning);
+		})
+	}
+
+	#[test]
+	fn not_a_contract_participant() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let bob: mock::RuntimeOrigin = origin(BOB);
+			let charlie: mock::RuntimeOrigin = origin(CHARLIE);
+
+			Rent::rent(bob, FIXED_AUTO_REV_NFT_TOKENS_TOKENS, CREATION_BLOCK).unwrap();
+
+			let err = Rent::propose_extension(
+				charlie,
+				FIXED_AUTO_REV_NFT_TOKENS_TOKENS,
+				BLOCK_DURATION,
+				TOKENS,
+			);
+			assert_noop!(err, Error::<Test>::NotAContractParticipant);
+		})
+	}
+
+	#[test]
+	fn contract_is_not_fixed() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let bob: mock::RuntimeOrigin = origin(BOB);
+
+			Rent::rent(bob.clone(), SUBSC_AUTO_NOREV_NOT_CHANGEABLE_TOK_NONE_FIXTOK, CREATION_BLOCK)
+				.unwrap();
+
+			let err = Rent::propose_extension(
+				bob,
+				SUBSC_AUTO_NOREV_NOT_CHANGEABLE_TOK_NONE_FIXTOK,
+				BLOCK_DURATION,
+				TOKENS,
+			);
+			assert_noop!(err, Error::<Test>::ContractIsNotFixed);
+		})
+	}
+
+	#[test]
+	fn duration_exceeds_maximum_limit() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let bob: mock::RuntimeOrigin = origin(BOB);
+
+			Rent::rent(bob.clone(), FIXED_AUTO_REV_NFT_TOKENS_TOKENS, CREATION_BLOCK).unwrap();
+
+			let err = Rent::propose_extension(
+				bob,
+				FIXED_AUTO_REV_NFT_TOKENS_TOKENS,
+				BLOCK_MAX_DURATION,
+				TOKENS,
+			);
+			assert_noop!(err, Error::<Test>::DurationExceedsMaximumLimit);
+		})
+	}
+}
+
+mod accept_extension {
+	use super::*;
+
+	#[test]
+	fn accept_extension() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let bob: mock::RuntimeOrigin = origin(BOB);
+
+			Rent::rent(bob.clone(), FIXED_AUTO_REV_NFT_TOKENS_TOKENS, CREATION_BLOCK).unwrap();
+			Rent::propose_extension(alice, FIXED_AUTO_REV_NFT_TOKENS_TOKENS, BLOCK_DURATION, TOKENS)
+				.unwrap();
+			let alice_balance = Balances::free_balance(ALICE);
+			let bob_balance = Balances::free_balance(BOB);
+
+			Rent::accept_extension(bob, FIXED_AUTO_REV_NFT_TOKENS_TOKENS, BLOCK_DURATION, TOKENS)
+				.unwrap();
+
+			// State check.
+			let end_block = CREATION_BLOCK + 2 * BLOCK_DURATION;
+			let contract = Rent::contracts(FIXED_AUTO_REV_NFT_TOKENS_TOKENS).unwrap();
+			let user = NFT::nft_users(FIXED_AUTO_REV_NFT_TOKENS_TOKENS).unwrap();
+			assert_eq!(contract.duration, Duration::Fixed(2 * BLOCK_DURATION));
+			assert_eq!(
+				Rent::queues().fixed_queue.get(FIXED_AUTO_REV_NFT_TOKENS_TOKENS),
+				Some(end_block)
+			);
+			assert_eq!(user.expires, end_block);
+			assert!(Rent::extensions(FIXED_AUTO_REV_NFT_TOKENS_TOKENS).is_none());
+			assert_eq!(Balances::free_balance(ALICE), alice_balance + TOKENS);
+			assert_eq!(Balances::free_balance(BOB), bob_balance - TOKENS);
+
+			// Event check.
+			let event = RentEvent::ExtensionAccepted {
+				nft_id: FIXED_AUTO_REV_NFT_TOKENS_TOKENS,
+				duration: 2 * BLOCK_DURATION,
+				extra_fee: TOKENS,
+				marketplace_cut: 0,
+			};
+			let event = RuntimeEvent::Rent(event);
+			System::assert_last_event(event);
+		})
+	}
+
+	#[test]
+	fn no_extension_proposed() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let bob: mock::RuntimeOrigin = origin(BOB);
+
+			Rent::rent(bob.clone(), FIXED_AUTO_REV_NFT_TOKENS_TOKENS, CREATION_BLOCK).unwrap();
+
+			let err = Rent::accept_extension(
+				bob,
+				FIXED_AUTO_REV_NFT_TOKENS_TOKENS,
+				BLOCK_DURATION,
+				TOKENS,
+			);
+			assert_noop!(err, Error::<Test>::NoExtensionProposed);
+		})
+	}
+
+	#[test]
+	fn cannot_accept_own_extension() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let bob: mock::RuntimeOrigin = origin(BOB);
+
+			Rent::rent(bob.clone(), FIXED_AUTO_REV_NFT_TOKENS_TOKENS, CREATION_BLOCK).unwrap();
+			Rent::propose_extension(
+				bob.clone(),
+				FIXED_AUTO_REV_NFT_TOKENS_TOKENS,
+				BLOCK_DURATION,
+				TOKENS,
+			)
+			.unwrap();
+
+			let err = Rent::accept_extension(
+				bob,
+				FIXED_AUTO_REV_NFT_TOKENS_TOKENS,
+				BLOCK_DURATION,
+				TOKENS,
+			);
+			assert_noop!(err, Error::<Test>::CannotAcceptOwnExtension);
+		})
+	}
+
+	#[test]
+	fn contract_terms_do_not_match() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let bob: mock::RuntimeOrigin = origin(BOB);
+
+			Rent::rent(bob.clone(), FIXED_AUTO_REV_NFT_TOKENS_TOKENS, CREATION_BLOCK).unwrap();
+			Rent::propose_extension(alice, FIXED_AUTO_REV_NFT_TOKENS_TOKENS, BLOCK_DURATION, TOKENS)
+				.unwrap();
+
+			let err = Rent::accept_extension(
+				bob,
+				FIXED_AUTO_REV_NFT_TOKENS_TOKENS,
+				BLOCK_DURATION,
+				LESS_TOKENS,
+			);
+			assert_noop!(err, Error::<Test>::ContractTermsDoNotMatch);
+		})
+	}
+}
+
+mod bundle_nfts {
+	use super::*;
+
+	#[test]
+	fn bundle_nfts() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			prepare_bundle_contract();
+			let bob: mock::RuntimeOrigin = origin(BOB);
+
+			// State check.
+			let nft = NFT::nfts(ALICE_NFT_ID_7).unwrap();
+			assert_eq!(Rent::bundled_nfts(ALICE_NFT_ID_6).unwrap().to_vec(), vec![ALICE_NFT_ID_7]);
+			assert_eq!(Rent::bundle_parents(ALICE_NFT_ID_7), Some(ALICE_NFT_ID_6));
+			assert!(nft.state.is_rented);
+
+			// Renting the contract gives access to the whole bundle.
+			Rent::rent(bob, ALICE_NFT_ID_6, CREATION_BLOCK).unwrap();
+			let user = NFT::nft_users(ALICE_NFT_ID_7).unwrap();
+			assert_eq!(user.user, BOB);
+			assert_eq!(user.expires, CREATION_BLOCK + BLOCK_DURATION);
+		})
+	}
+
+	#[test]
+	fn bundle_nfts_event() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let nft_ids = BoundedVec::try_from(vec![ALICE_NFT_ID_6, ALICE_NFT_ID_7]).unwrap();
+
+			Rent::bundle_nfts(alice, FIXED_AUTO_REV_NFT_TOKENS_TOKENS, nft_ids.clone()).unwrap();
+
+			// Event check.
+			let event =
+				RentEvent::NFTsBundled { nft_id: FIXED_AUTO_REV_NFT_TOKENS_TOKENS, nft_ids };
+			let event = RuntimeEvent::Rent(event);
+			System::assert_last_event(event);
+		})
+	}
+
+	#[test]
+	fn contract_not_found() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let nft_ids = BoundedVec::try_from(vec![ALICE_NFT_ID_7]).unwrap();
+
+			let err = Rent::bundle_nfts(alice, INVALID_NFT, nft_ids);
+			assert_noop!(err, Error::<Test>::ContractNotFound);
+		})
+	}
+
+	#[test]
+	fn not_the_contract_owner() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let bob: mock::RuntimeOrigin = origin(BOB);
+			let nft_ids = BoundedVec::try_from(vec![BOB_NFT_ID_2]).unwrap();
+
+			let err = Rent::bundle_nfts(bob, FIXED_AUTO_REV_NFT_TOKENS_TOKENS, nft_ids);
+			assert_noop!(err, Error::<Test>::NotTheContractOwner);
+		})
+	}
+
+	#[test]
+	fn cannot_bundle_running_contract() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let bob: mock::RuntimeOrigin = origin(BOB);
+			let nft_ids = BoundedVec::try_from(vec![ALICE_NFT_ID_7]).unwrap();
+
+			Rent::rent(bob, FIXED_AUTO_REV_TOK_FLEXTOK_FLEXTOK, CREATION_BLOCK).unwrap();
+
+			let err = Rent::bundle_nfts(alice, FIXED_AUTO_REV_TOK_FLEXTOK_FLEXTOK, nft_ids);
+			assert_noop!(err, Error::<Test>::CannotBundleRunningContract);
+		})
+	}
+
+	#[test]
+	fn nft_already_bundled() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			prepare_bundle_contract();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+
+			let nft_ids = BoundedVec::try_from(vec![ALICE_NFT_ID_7]).unwrap();
+			let err = Rent::bundle_nfts(alice.clone(), ALICE_NFT_ID_6, nft_ids);
+			assert_noop!(err, Error::<Test>::NFTAlreadyBundled);
+
+			let nft_ids = BoundedVec::try_from(vec![ALICE_NFT_ID_6]).unwrap();
+			let err = Rent::bundle_nfts(alice, ALICE_NFT_ID_6, nft_ids);
+			assert_noop!(err, Error::<Test>::NFTAlreadyBundled);
+		})
+	}
+
+	#[test]
+	fn not_the_nft_owner() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let nft_ids = BoundedVec::try_from(vec![BOB_NFT_ID_2]).unwrap();
+
+			let err = Rent::bundle_nfts(alice, FIXED_AUTO_REV_NFT_TOKENS_TOKENS, nft_ids);
+			assert_noop!(err, Error::<Test>::NotTheNFTOwner);
+		})
+	}
+
+	#[test]
+	fn bundled_nft_not_in_valid_state() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let nft_ids = BoundedVec::try_from(vec![FIXED_AUTO_REV_TOK_FLEXTOK_FLEXTOK]).unwrap();
+
+			let err = Rent::bundle_nfts(alice, FIXED_AUTO_REV_NFT_TOKENS_TOKENS, nft_ids);
+			assert_noop!(err, Error::<Test>::BundledNFTNotInValidState);
+		})
+	}
+
+	#[test]
+	fn bundle_size_limit_reached() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			prepare_bundle_contract();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+
+			NFT::create_nft(alice.clone(), BoundedVec::default(), PERCENT_0, None, false).unwrap();
+			NFT::create_nft(alice.clone(), BoundedVec::default(), PERCENT_0, None, false).unwrap();
+			NFT::create_nft(alice.clone(), BoundedVec::default(), PERCENT_0, None, false).unwrap();
+			let nft_id = NFT::next_nft_id() - 1;
+			let nft_ids = BoundedVec::try_from(vec![nft_id - 2, nft_id - 1, nft_id]).unwrap();
+
+			let err = Rent::bundle_nfts(alice, ALICE_NFT_ID_6, nft_ids);
+			assert_noop!(err, Error::<Test>::BundleSizeLimitReached);
+		})
+	}
+}
+
+mod set_usage_scope {
+	use super::*;
+
+	#[test]
+	fn set_usage_scope() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+
+			Rent::set_usage_scope(alice, FIXED_AUTO_REV_NFT_TOKENS_TOKENS, UsageScope::Use)
+				.unwrap();
+
+			// State check.
+			let contract = Rent::contracts(FIXED_AUTO_REV_NFT_TOKENS_TOKENS).unwrap();
+			assert_eq!(contract.usage_scope, UsageScope::Use);
+
+			// Event check.
+			let event = RentEvent::UsageScopeSet {
+				nft_id: FIXED_AUTO_REV_NFT_TOKENS_TOKENS,
+				usage_scope: UsageScope::Use,
+			};
+			let event = RuntimeEvent::Rent(event);
+			System::assert_last_event(event);
+		})
+	}
+
+	#[test]
+	fn rentee_usage_scope() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let nft_id = prepare_capsule_contract(UsageScope::Use);
+
+			assert_eq!(Rent::usage_scope(nft_id, &BOB), Some(UsageScope::Use));
+			assert_eq!(Rent::usage_scope(nft_id, &ALICE), None);
+			assert_eq!(Rent::usage_scope(FIXED_AUTO_REV_NFT_TOKENS_TOKENS, &BOB), None);
+		})
+	}
+
+	#[test]
+	fn bundled_capsule_usage_scope() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let bob: mock::RuntimeOrigin = origin(BOB);
+			let data = BoundedVec::default();
+			NFT::create_capsule(alice.clone(), data.clone(), data, PERCENT_0, None, false).unwrap();
+			let capsule_id = NFT::next_nft_id() - 1;
+			Rent::create_contract(
+				alice.clone(),
+				ALICE_NFT_ID_6,
+				DurationInput::Fixed(BLOCK_DURATION),
+				AcceptanceType::AutoAcceptance(None),
+				true,
+				RentFee::Tokens(TOKENS),
+				CancellationFee::None,
+				CancellationFee::None,
+				false,
+				None,
+			)
+			.unwrap();
+			let nft_ids = BoundedVec::try_from(vec![capsule_id]).unwrap();
+			Rent::bundle_nfts(alice.clone(), ALICE_NFT_ID_6, nft_ids).unwrap();
+			Rent::set_usage_scope(alice, ALICE_NFT_ID_6, UsageScope::Use).unwrap();
+			Rent::rent(bob.clone(), ALICE_NFT_ID_6, CREATION_BLOCK).unwrap();
+
+			// The bundled capsule uses the scope of the contract it is rented with.
+			assert_eq!(Rent::usage_scope(capsule_id, &BOB), Some(UsageScope::Use));
+			assert_eq!(Rent::usage_scope(capsule_id, &ALICE), None);
+			NFT::set_capsule_offchaindata(bob, capsule_id, BoundedVec::default()).unwrap();
+		})
+	}
+
+	#[test]
+	fn view_only_rentee_cannot_update_capsule() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let bob: mock::RuntimeOrigin = origin(BOB);
+			let nft_id = prepare_capsule_contract(UsageScope::ViewOnly);
+
+			let err = NFT::set_capsule_offchaindata(bob.clone(), nft_id, BoundedVec::default());
+			assert_noop!(err, ternoa_nft::Error::<Test>::NotAllowedByUsageScope);
+			let err = NFT::notify_enclave_key_update(bob, nft_id);
+			assert_noop!(err, ternoa_nft::Error::<Test>::NotAllowedByUsageScope);
+		})
+	}
+
+	#[test]
+	fn use_rentee_can_set_capsule_offchaindata() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let bob: mock::RuntimeOrigin = origin(BOB);
+			let nft_id = prepare_capsule_contract(UsageScope::Use);
+
+			NFT::set_capsule_offchaindata(bob.clone(), nft_id, BoundedVec::default()).unwrap();
+			let err = NFT::notify_enclave_key_update(bob, nft_id);
+			assert_noop!(err, ternoa_nft::Error::<Test>::NotAllowedByUsageScope);
+		})
+	}
+
+	#[test]
+	fn full_rentee_can_update_capsule_key() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let bob: mock::RuntimeOrigin = origin(BOB);
+			let nft_id = prepare_capsule_contract(UsageScope::Full);
+
+			NFT::notify_enclave_key_update(bob, nft_id).unwrap();
+			assert!(NFT::nfts(nft_id).unwrap().state.is_syncing_capsule);
+		})
+	}
+
+	#[test]
+	fn owner_of_rented_capsule() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let nft_id = prepare_capsule_contract(UsageScope::Full);
+
+			let err = NFT::set_capsule_offchaindata(alice.clone(), nft_id, BoundedVec::default());
+			assert_noop!(err, ternoa_nft::Error::<Test>::CannotSetOffchainDataForRentedNFTs);
+			let err = NFT::notify_enclave_key_update(alice, nft_id);
+			assert_noop!(err, ternoa_nft::Error::<Test>::CannotChangeKeyForRentedNFTs);
+		})
+	}
+
+	#[test]
+	fn stranger_cannot_update_rented_capsule() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let charlie: mock::RuntimeOrigin = origin(CHARLIE);
+			let nft_id = prepare_capsule_contract(UsageScope::Full);
+
+			let err = NFT::set_capsule_offchaindata(charlie.clone(), nft_id, BoundedVec::default());
+			assert_noop!(err, ternoa_nft::Error::<Test>::NotTheNFTOwner);
+			let err = NFT::notify_enclave_key_update(charlie, nft_id);
+			assert_noop!(err, ternoa_nft::Error::<Test>::NotTheNFTOwner);
+		})
+	}
+
+	#[test]
+	fn contract_not_found() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+
+			let err = Rent::set_usage_scope(alice, INVALID_NFT, UsageScope::Full);
+			assert_noop!(err, Error::<Test>::ContractNotFound);
+		})
+	}
+
+	#[test]
+	fn not_the_contract_owner() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let bob: mock::RuntimeOrigin = origin(BOB);
+
+			let err =
+				Rent::set_usage_scope(bob, FIXED_AUTO_REV_NFT_TOKENS_TOKENS, UsageScope::Full);
+			assert_noop!(err, Error::<Test>::NotTheContractOwner);
+		})
+	}
+
+	#[test]
+	fn contract_terms_already_accepted() {
+		ExtBuilder::new_build(None).execute_with(|| {
+			prepare_tests();
+			let alice: mock::RuntimeOrigin = origin(ALICE);
+			let bob: mock::RuntimeOrigin = origin(BOB);
+
+			Rent::rent(bob, FIXED_AUTO_REV_NFT_TOKENS_TOKENS, CREATION_BLOCK).unwrap();
+
+			let err =
+				Rent::set_usage_scope(alice, FIXED_AUTO_REV_NFT_TOKENS_TOKENS, UsageScope::Full);
+			assert_noop!(err, Error::<Test>::ContractTermsAlreadyAccepted);
+		})
+	}
+}
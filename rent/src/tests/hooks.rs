@@ -15,12 +15,16 @@
 // along with Ternoa.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::mock::*;
+use frame_support::traits::Currency;
 use frame_system::RawOrigin;
+use primitives::marketplace::MarketplaceType;
+use sp_arithmetic::per_things::Permill;
 use ternoa_common::traits::NFTExt;
 
 use crate::{
 	tests::{extrinsics::*, mock},
-	Event as RentEvent,
+	AcceptanceType, CancellationFee, Contracts, DurationInput, Error, Event as RentEvent, RentFee,
+	SubscriptionInput,
 };
 
 fn origin(account: u64) -> mock::RuntimeOrigin {
@@ -46,6 +50,7 @@ fn end_contract_fixed() {
 		let rent_fee_nft = NFT::get_nft(BOB_NFT_ID_0).unwrap();
 		assert!(contract.is_none());
 		assert_eq!(rent_fee_nft.owner, ALICE);
+		assert!(NFT::nft_users(FIXED_AUTO_REV_NFT_TOKENS_TOKENS).is_none());
 
 		// Event check.
 		let event =
@@ -64,8 +69,14 @@ fn end_contract_subscription() {
 		let alice_balance = Balances::free_balance(ALICE);
 		let bob_balance = Balances::free_balance(BOB);
 
-		Rent::make_rent_offer(bob, SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK, CREATION_BLOCK)
-			.unwrap();
+		Rent::make_rent_offer(
+			bob,
+			SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK,
+			CREATION_BLOCK,
+			None,
+			None,
+		)
+		.unwrap();
 		Rent::accept_rent_offer(alice, SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK, BOB).unwrap();
 
 		run_to_block(BLOCK_MAX_DURATION + 1);
@@ -101,8 +112,14 @@ fn end_contract_renter() {
 		let alice_balance = Balances::free_balance(ALICE);
 		let bob_balance = Balances::free_balance(BOB);
 
-		Rent::make_rent_offer(bob, SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK, CREATION_BLOCK)
-			.unwrap();
+		Rent::make_rent_offer(
+			bob,
+			SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK,
+			CREATION_BLOCK,
+			None,
+			None,
+		)
+		.unwrap();
 		Rent::accept_rent_offer(alice.clone(), SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK, BOB)
 			.unwrap();
 		Rent::change_subscription_terms(
@@ -141,8 +158,14 @@ fn end_contract_rentee() {
 		let bob: mock::RuntimeOrigin = origin(BOB);
 		let alice_balance = Balances::free_balance(ALICE);
 
-		Rent::make_rent_offer(bob, SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK, CREATION_BLOCK)
-			.unwrap();
+		Rent::make_rent_offer(
+			bob,
+			SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK,
+			CREATION_BLOCK,
+			None,
+			None,
+		)
+		.unwrap();
 		Rent::accept_rent_offer(alice, SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK, BOB).unwrap();
 		Balances::set_balance(root(), BOB, 0, 0).unwrap();
 
@@ -171,8 +194,14 @@ fn renew_contract() {
 		let alice: mock::RuntimeOrigin = origin(ALICE);
 		let bob: mock::RuntimeOrigin = origin(BOB);
 
-		Rent::make_rent_offer(bob, SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK, CREATION_BLOCK)
-			.unwrap();
+		Rent::make_rent_offer(
+			bob,
+			SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK,
+			CREATION_BLOCK,
+			None,
+			None,
+		)
+		.unwrap();
 		Rent::accept_rent_offer(alice, SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK, BOB).unwrap();
 
 		// Check subscription queue
@@ -198,12 +227,398 @@ fn renew_contract() {
 		// Event check.
 		let event = RentEvent::ContractSubscriptionPeriodStarted {
 			nft_id: SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK,
+			period: 1,
+			marketplace_cut: 0,
 		};
 		let event = RuntimeEvent::Rent(event);
 		System::assert_last_event(event);
 	})
 }
 
+#[test]
+fn renew_contract_through_marketplace() {
+	ExtBuilder::new_build(None).execute_with(|| {
+		prepare_tests();
+		let alice: mock::RuntimeOrigin = origin(ALICE);
+		let bob: mock::RuntimeOrigin = origin(BOB);
+		let marketplace_id = prepare_marketplace(MarketplaceType::Public);
+
+		Rent::create_contract(
+			alice,
+			ALICE_NFT_ID_6,
+			DurationInput::Subscription(SubscriptionInput {
+				period_length: BLOCK_DURATION,
+				max_duration: Some(BLOCK_MAX_DURATION),
+				is_changeable: false,
+				grace_period: 0,
+			}),
+			AcceptanceType::AutoAcceptance(None),
+			true,
+			RentFee::Tokens(TOKENS),
+			CancellationFee::None,
+			CancellationFee::None,
+			false,
+			Some(marketplace_id),
+		)
+		.unwrap();
+		Rent::rent(bob, ALICE_NFT_ID_6, CREATION_BLOCK).unwrap();
+		let alice_balance = Balances::free_balance(ALICE);
+		let charlie_balance = Balances::free_balance(CHARLIE);
+
+		run_to_block(BLOCK_DURATION + 1);
+
+		// State check.
+		let cut = Permill::from_percent(10) * TOKENS;
+		assert_eq!(Balances::free_balance(ALICE), alice_balance + TOKENS - cut);
+		assert_eq!(Balances::free_balance(CHARLIE), charlie_balance + cut);
+
+		// Event check.
+		let event = RentEvent::ContractSubscriptionPeriodStarted {
+			nft_id: ALICE_NFT_ID_6,
+			period: 1,
+			marketplace_cut: cut,
+		};
+		System::assert_last_event(RuntimeEvent::Rent(event));
+	})
+}
+
+#[test]
+fn renew_contract_processed_late() {
+	ExtBuilder::new_build(None).execute_with(|| {
+		prepare_tests();
+		let alice: mock::RuntimeOrigin = origin(ALICE);
+		let bob: mock::RuntimeOrigin = origin(BOB);
+
+		Rent::make_rent_offer(
+			bob,
+			SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK,
+			CREATION_BLOCK,
+			None,
+			None,
+		)
+		.unwrap();
+		Rent::accept_rent_offer(alice, SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK, BOB).unwrap();
+		let alice_balance = Balances::free_balance(ALICE);
+		let bob_balance = Balances::free_balance(BOB);
+
+		// The subscription queue is not processed for three periods.
+		System::set_block_number(3 * BLOCK_DURATION + 4);
+		run_to_block(3 * BLOCK_DURATION + 5);
+
+		// State check.
+		assert!(Rent::contracts(SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK).is_some());
+		assert_eq!(
+			Rent::queues()
+				.subscription_queue
+				.get(SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK),
+			Some(4 * BLOCK_DURATION + 1)
+		);
+		assert_eq!(Balances::free_balance(ALICE), alice_balance + 3 * TOKENS);
+		assert_eq!(Balances::free_balance(BOB), bob_balance - 3 * TOKENS);
+
+		// Event check.
+		for period in 1..=3 {
+			let event = RentEvent::ContractSubscriptionPeriodStarted {
+				nft_id: SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK,
+				period,
+				marketplace_cut: 0,
+			};
+			System::assert_has_event(RuntimeEvent::Rent(event));
+		}
+	})
+}
+
+#[test]
+fn late_subscription_periods_are_billed_one_at_a_time() {
+	ExtBuilder::new_build(None).execute_with(|| {
+		prepare_tests();
+		let alice: mock::RuntimeOrigin = origin(ALICE);
+		let bob: mock::RuntimeOrigin = origin(BOB);
+		let nft_id = SUBSC_MANU_REV_CHANGEABLE_TOK_FIXTOK_FIXTOK;
+
+		Rent::make_rent_offer(bob, nft_id, CREATION_BLOCK, None, None).unwrap();
+		Rent::accept_rent_offer(alice, nft_id, BOB).unwrap();
+		let bob_balance = Balances::free_balance(BOB);
+
+		let now = 3 * BLOCK_DURATION + 5;
+		let due = BLOCK_DURATION + 1;
+		let next = Rent::handle_subscription_contract(nft_id, &due, &now);
+
+		// Only the first missed period is billed, the next one is due right away.
+		assert_eq!(next, Some(2 * BLOCK_DURATION + 1));
+		assert_eq!(Balances::free_balance(BOB), bob_balance - TOKENS);
+		let event =
+			RentEvent::ContractSubscriptionPeriodStarted { nft_id, period: 1, marketplace_cut: 0 };
+		System::assert_last_event(RuntimeEvent::Rent(event));
+	})
+}
+
+#[test]
+fn end_contract_escrowed_rent_fee() {
+	ExtBuilder::new_build(None).execute_with(|| {
+		prepare_tests();
+		prepare_escrowed_contract();
+		let alice: mock::RuntimeOrigin = origin(ALICE);
+		let bob: mock::RuntimeOrigin = origin(BOB);
+		let alice_balance = Balances::free_balance(ALICE);
+
+		Rent::rent(bob, ALICE_NFT_ID_6, CREATION_BLOCK).unwrap();
+		run_to_block(CREATION_BLOCK + BLOCK_DURATION / 2);
+		Rent::claim_escrowed_rent_fee(alice, ALICE_NFT_ID_6).unwrap();
+		run_to_block(CREATION_BLOCK + BLOCK_DURATION);
+
+		// State check.
+		assert!(Rent::contracts(ALICE_NFT_ID_6).is_none());
+		assert!(Rent::escrowed_rent_fees(ALICE_NFT_ID_6).is_none());
+		assert_eq!(Balances::free_balance(ALICE), alice_balance + TOKENS);
+
+		// Event check.
+		let event = RentEvent::EscrowedRentFeeReleased {
+			nft_id: ALICE_NFT_ID_6,
+			renter: ALICE,
+			amount: TOKENS - Permill::from_percent(50) * TOKENS,
+		};
+		System::assert_has_event(RuntimeEvent::Rent(event));
+		let event = RentEvent::ContractEnded { nft_id: ALICE_NFT_ID_6, revoked_by: None };
+		System::assert_last_event(RuntimeEvent::Rent(event));
+	})
+}
+
+#[test]
+fn end_contract_with_bundled_nfts() {
+	ExtBuilder::new_build(None).execute_with(|| {
+		prepare_tests();
+		prepare_bundle_contract();
+		let bob: mock::RuntimeOrigin = origin(BOB);
+
+		Rent::rent(bob, ALICE_NFT_ID_6, CREATION_BLOCK).unwrap();
+		run_to_block(CREATION_BLOCK + BLOCK_DURATION);
+
+		// State check.
+		let nft = NFT::get_nft(ALICE_NFT_ID_7).unwrap();
+		assert!(Rent::contracts(ALICE_NFT_ID_6).is_none());
+		assert!(Rent::bundled_nfts(ALICE_NFT_ID_6).is_none());
+		assert!(!nft.state.is_rented);
+		assert!(NFT::nft_users(ALICE_NFT_ID_7).is_none());
+	})
+}
+
+#[test]
+fn end_sublet() {
+	ExtBuilder::new_build(None).execute_with(|| {
+		prepare_tests();
+		prepare_sublet_contract();
+		let charlie: mock::RuntimeOrigin = origin(CHARLIE);
+		let bob_balance = Balances::free_balance(BOB);
+
+		Rent::rent_sublet(charlie, ALICE_NFT_ID_6).unwrap();
+		run_to_block(CREATION_BLOCK + SUBLET_DURATION);
+
+		// State check.
+		let user = NFT::nft_users(ALICE_NFT_ID_6).unwrap();
+		assert!(Rent::sublets(ALICE_NFT_ID_6).is_none());
+		assert!(Rent::contracts(ALICE_NFT_ID_6).is_some());
+		assert_eq!(Rent::sublet_queue().get(ALICE_NFT_ID_6), None);
+		assert_eq!(user.user, BOB);
+		assert_eq!(user.expires, CREATION_BLOCK + BLOCK_DURATION);
+		assert!(Rent::escrowed_sublet_fees(ALICE_NFT_ID_6).is_none());
+		assert_eq!(Balances::free_balance(BOB), bob_balance + LESS_TOKENS);
+
+		// Event check.
+		let event = RentEvent::SubletRentFeeReleased {
+			nft_id: ALICE_NFT_ID_6,
+			renter: BOB,
+			amount: LESS_TOKENS,
+		};
+		System::assert_has_event(RuntimeEvent::Rent(event));
+		let event = RentEvent::SubletEnded { nft_id: ALICE_NFT_ID_6, parent_ended: false };
+		System::assert_last_event(RuntimeEvent::Rent(event));
+	})
+}
+
+#[test]
+fn end_sublet_failed() {
+	ExtBuilder::new_build(None).execute_with(|| {
+		prepare_tests();
+		prepare_sublet_contract();
+		let charlie: mock::RuntimeOrigin = origin(CHARLIE);
+
+		Rent::rent_sublet(charlie, ALICE_NFT_ID_6).unwrap();
+		// The parent contract is gone so the NFT usage cannot be given back.
+		Contracts::<Test>::remove(ALICE_NFT_ID_6);
+		run_to_block(CREATION_BLOCK + SUBLET_DURATION);
+
+		// State check.
+		// The escrowed rent fee is still released to the sublet renter.
+		assert!(Rent::sublets(ALICE_NFT_ID_6).is_none());
+		assert_eq!(Rent::sublet_queue().get(ALICE_NFT_ID_6), None);
+		assert!(Rent::escrowed_sublet_fees(ALICE_NFT_ID_6).is_none());
+
+		// Event check.
+		let error = Error::<Test>::ContractNotFound.into();
+		let event = RentEvent::SubletEndFailed { nft_id: ALICE_NFT_ID_6, error };
+		System::assert_last_event(RuntimeEvent::Rent(event));
+	})
+}
+
+#[test]
+fn end_contract_with_sublet() {
+	ExtBuilder::new_build(None).execute_with(|| {
+		prepare_tests();
+		prepare_sublet_contract();
+
+		run_to_block(CREATION_BLOCK + BLOCK_DURATION);
+
+		// State check.
+		assert!(Rent::sublets(ALICE_NFT_ID_6).is_none());
+		assert!(Rent::contracts(ALICE_NFT_ID_6).is_none());
+		assert!(NFT::nft_users(ALICE_NFT_ID_6).is_none());
+
+		// Event check.
+		let event = RentEvent::SubletEnded { nft_id: ALICE_NFT_ID_6, parent_ended: true };
+		System::assert_has_event(RuntimeEvent::Rent(event));
+		let event = RentEvent::ContractEnded { nft_id: ALICE_NFT_ID_6, revoked_by: None };
+		System::assert_last_event(RuntimeEvent::Rent(event));
+	})
+}
+
+#[test]
+fn revoke_contract_with_running_sublet() {
+	ExtBuilder::new_build(None).execute_with(|| {
+		prepare_tests();
+		prepare_sublet_contract();
+		let alice: mock::RuntimeOrigin = origin(ALICE);
+		let charlie: mock::RuntimeOrigin = origin(CHARLIE);
+
+		Rent::rent_sublet(charlie, ALICE_NFT_ID_6).unwrap();
+		run_to_block(CREATION_BLOCK + 2);
+		let bob_balance = Balances::free_balance(BOB);
+		let charlie_balance = Balances::free_balance(CHARLIE);
+		Rent::revoke_contract(alice, ALICE_NFT_ID_6).unwrap();
+
+		// State check.
+		// Three of the five sublet blocks were not used.
+		let refund = LESS_TOKENS * 3 / 5;
+		assert!(Rent::sublets(ALICE_NFT_ID_6).is_none());
+		assert_eq!(Rent::sublet_queue().get(ALICE_NFT_ID_6), None);
+		assert!(NFT::nft_users(ALICE_NFT_ID_6).is_none());
+		assert!(Rent::escrowed_sublet_fees(ALICE_NFT_ID_6).is_none());
+		assert_eq!(Balances::free_balance(BOB), bob_balance + LESS_TOKENS - refund);
+		assert_eq!(Balances::free_balance(CHARLIE), charlie_balance + refund);
+
+		// Event check.
+		let event = RentEvent::SubletRentFeeRefunded {
+			nft_id: ALICE_NFT_ID_6,
+			rentee: CHARLIE,
+			amount: refund,
+		};
+		System::assert_has_event(RuntimeEvent::Rent(event));
+		let event = RentEvent::SubletRentFeeReleased {
+			nft_id: ALICE_NFT_ID_6,
+			renter: BOB,
+			amount: LESS_TOKENS - refund,
+		};
+		System::assert_has_event(RuntimeEvent::Rent(event));
+		let event = RentEvent::SubletEnded { nft_id: ALICE_NFT_ID_6, parent_ended: true };
+		System::assert_has_event(RuntimeEvent::Rent(event));
+	})
+}
+
+fn prepare_grace_period_contract() {
+	let alice: mock::RuntimeOrigin = origin(ALICE);
+	let bob: mock::RuntimeOrigin = origin(BOB);
+
+	Rent::create_contract(
+		alice,
+		ALICE_NFT_ID_6,
+		DurationInput::Subscription(SubscriptionInput {
+			period_length: BLOCK_DURATION,
+			max_duration: Some(BLOCK_MAX_DURATION),
+			is_changeable: false,
+			grace_period: GRACE_PERIOD,
+		}),
+		AcceptanceType::AutoAcceptance(None),
+		false,
+		RentFee::Tokens(TOKENS),
+		CancellationFee::None,
+		CancellationFee::None,
+		false,
+		None,
+	)
+	.unwrap();
+	Rent::rent(bob, ALICE_NFT_ID_6, CREATION_BLOCK).unwrap();
+}
+
+#[test]
+fn subscription_payment_recovered() {
+	ExtBuilder::new_build(None).execute_with(|| {
+		prepare_tests();
+		prepare_grace_period_contract();
+		let bob_balance = Balances::free_balance(BOB);
+		Balances::set_balance(root(), BOB, LESS_TOKENS, 0).unwrap();
+
+		run_to_block(BLOCK_DURATION + 1);
+
+		// State check.
+		let contract = Rent::contracts(ALICE_NFT_ID_6).unwrap();
+		assert_eq!(contract.duration.overdue_period(), Some(1));
+		assert_eq!(Rent::queues().subscription_queue.get(ALICE_NFT_ID_6), Some(BLOCK_DURATION + 2));
+
+		// Event check.
+		let event = RentEvent::SubscriptionPaymentFailed {
+			nft_id: ALICE_NFT_ID_6,
+			period: 1,
+			grace_end: BLOCK_DURATION + 1 + GRACE_PERIOD,
+		};
+		System::assert_last_event(RuntimeEvent::Rent(event));
+
+		Balances::set_balance(root(), BOB, bob_balance, 0).unwrap();
+		run_to_block(BLOCK_DURATION + 2);
+
+		// State check.
+		let contract = Rent::contracts(ALICE_NFT_ID_6).unwrap();
+		assert_eq!(contract.duration.overdue_period(), None);
+		assert_eq!(Balances::free_balance(BOB), bob_balance - TOKENS);
+		assert_eq!(
+			Rent::queues().subscription_queue.get(ALICE_NFT_ID_6),
+			Some(2 * BLOCK_DURATION + 1)
+		);
+
+		// Event check.
+		let event = RentEvent::SubscriptionPaymentRecovered {
+			nft_id: ALICE_NFT_ID_6,
+			period: 1,
+			marketplace_cut: 0,
+		};
+		System::assert_last_event(RuntimeEvent::Rent(event));
+	})
+}
+
+#[test]
+fn subscription_payment_grace_period_expired() {
+	ExtBuilder::new_build(None).execute_with(|| {
+		prepare_tests();
+		prepare_grace_period_contract();
+		Balances::set_balance(root(), BOB, LESS_TOKENS, 0).unwrap();
+
+		run_to_block(BLOCK_DURATION + GRACE_PERIOD);
+
+		// The contract is still running while the payment is overdue.
+		let contract = Rent::contracts(ALICE_NFT_ID_6).unwrap();
+		assert_eq!(contract.duration.overdue_period(), Some(1));
+
+		run_to_block(BLOCK_DURATION + 1 + GRACE_PERIOD);
+
+		// State check.
+		assert!(Rent::contracts(ALICE_NFT_ID_6).is_none());
+		assert_eq!(Rent::queues().subscription_queue.get(ALICE_NFT_ID_6), None);
+
+		// Event check.
+		let event = RentEvent::ContractEnded { nft_id: ALICE_NFT_ID_6, revoked_by: None };
+		System::assert_last_event(RuntimeEvent::Rent(event));
+	})
+}
+
 #[test]
 fn remove_expired_contract() {
 	ExtBuilder::new_build(None).execute_with(|| {
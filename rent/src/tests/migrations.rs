@@ -0,0 +1,128 @@
+// Copyright 2023 Capsule Corp (France) SAS.
+// This file is part of Ternoa.
+
+// Ternoa is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Ternoa is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Ternoa.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::mock::*;
+use frame_support::{storage::unhashed, traits::OnRuntimeUpgrade, BoundedVec};
+use frame_system::RawOrigin;
+use primitives::nfts::{NFTId, NFTUser};
+
+use crate::{
+	migrations::v3::{MigrationV3, OldDuration, OldRentContractData, OldSubscription},
+	tests::{extrinsics::*, mock},
+	AcceptanceType, CancellationFee, Contracts, QueueKind, Queues, RentFee,
+};
+
+type OldContract = OldRentContractData<u64, BlockNumber, Balance, RentAccountSizeLimit>;
+
+fn origin(account: u64) -> mock::RuntimeOrigin {
+	RawOrigin::Signed(account).into()
+}
+
+fn old_contract(rentee: Option<u64>, duration: OldDuration<BlockNumber>) -> OldContract {
+	OldRentContractData {
+		start_block: rentee.map(|_| CREATION_BLOCK),
+		renter: ALICE,
+		rentee,
+		duration,
+		acceptance_type: AcceptanceType::AutoAcceptance(None),
+		renter_can_revoke: false,
+		rent_fee: RentFee::Tokens(TOKENS),
+		renter_cancellation_fee: CancellationFee::None,
+		rentee_cancellation_fee: CancellationFee::None,
+		creation_block: CREATION_BLOCK,
+	}
+}
+
+fn insert_old_contract(nft_id: NFTId, contract: OldContract) {
+	unhashed::put(&Contracts::<Test>::hashed_key_for(nft_id), &contract);
+}
+
+#[test]
+fn migration_v3_sets_nft_users_of_started_contracts() {
+	ExtBuilder::new_build(None).execute_with(|| {
+		for _ in 0..3 {
+			NFT::create_nft(origin(ALICE), BoundedVec::default(), PERCENT_0, None, false).unwrap();
+		}
+		let subscription = OldSubscription {
+			period_length: BLOCK_DURATION,
+			max_duration: BLOCK_MAX_DURATION,
+			is_changeable: false,
+			new_terms: false,
+		};
+		insert_old_contract(
+			ALICE_NFT_ID_0,
+			old_contract(Some(BOB), OldDuration::Fixed(BLOCK_DURATION)),
+		);
+		insert_old_contract(
+			ALICE_NFT_ID_1,
+			old_contract(Some(CHARLIE), OldDuration::Subscription(subscription)),
+		);
+		insert_old_contract(ALICE_NFT_ID_2, old_contract(None, OldDuration::Fixed(BLOCK_DURATION)));
+
+		MigrationV3::<Test>::on_runtime_upgrade();
+
+		// Started contracts give the usage of their NFT to their rentee until they end.
+		let fixed_user = NFTUser::new(BOB, CREATION_BLOCK + BLOCK_DURATION);
+		assert_eq!(NFT::nft_users(ALICE_NFT_ID_0), Some(fixed_user));
+		let subscription_user = NFTUser::new(CHARLIE, CREATION_BLOCK + BLOCK_MAX_DURATION);
+		assert_eq!(NFT::nft_users(ALICE_NFT_ID_1), Some(subscription_user));
+		assert!(NFT::nft_users(ALICE_NFT_ID_2).is_none());
+
+		// The contracts are readable with the new layout.
+		let contract = Rent::contracts(ALICE_NFT_ID_0).unwrap();
+		assert_eq!(contract.rentee, Some(BOB));
+		assert_eq!(contract.end_block(), Some(CREATION_BLOCK + BLOCK_DURATION));
+		assert!(Rent::contracts(ALICE_NFT_ID_1).is_some());
+		assert!(Rent::contracts(ALICE_NFT_ID_2).unwrap().rentee.is_none());
+	})
+}
+
+#[test]
+fn migration_v3_aligns_subscription_queue() {
+	ExtBuilder::new_build(None).execute_with(|| {
+		for _ in 0..2 {
+			NFT::create_nft(origin(ALICE), BoundedVec::default(), PERCENT_0, None, false).unwrap();
+		}
+		let subscription = OldSubscription {
+			period_length: BLOCK_DURATION,
+			max_duration: BLOCK_MAX_DURATION,
+			is_changeable: false,
+			new_terms: false,
+		};
+		insert_old_contract(
+			ALICE_NFT_ID_0,
+			old_contract(Some(BOB), OldDuration::Subscription(subscription.clone())),
+		);
+		insert_old_contract(
+			ALICE_NFT_ID_1,
+			old_contract(Some(CHARLIE), OldDuration::Subscription(subscription)),
+		);
+		// The first contract was billed three blocks late, the second one on time.
+		let late_due = CREATION_BLOCK + BLOCK_DURATION + 3;
+		let on_time_due = CREATION_BLOCK + BLOCK_DURATION;
+		Queues::<Test>::mutate(|x| {
+			x.insert(ALICE_NFT_ID_0, late_due, QueueKind::Subscription).unwrap();
+			x.insert(ALICE_NFT_ID_1, on_time_due, QueueKind::Subscription).unwrap();
+		});
+
+		MigrationV3::<Test>::on_runtime_upgrade();
+
+		// Both contracts are queued at the start of the period they were due in.
+		let mut queue = Rent::queues().subscription_queue;
+		assert_eq!(queue.get(ALICE_NFT_ID_0), Some(CREATION_BLOCK + BLOCK_DURATION));
+		assert_eq!(queue.get(ALICE_NFT_ID_1), Some(CREATION_BLOCK + BLOCK_DURATION));
+	})
+}
@@ -42,6 +42,7 @@ pub const COLLECTOR: u64 = 99;
 pub const NFT_MINT_FEE: Balance = 10;
 pub const SECRET_NFT_MINT_FEE: Balance = 75;
 pub const CAPSULE_MINT_FEE: Balance = 100;
+pub const MARKETPLACE_MINT_FEE: Balance = 100;
 
 frame_support::construct_runtime!(
 	pub enum Test where
@@ -53,6 +54,7 @@ frame_support::construct_runtime!(
 		Balances: pallet_balances,
 		NFT: ternoa_nft,
 		Rent: ternoa_rent,
+		Marketplace: ternoa_marketplace,
 		TEE: ternoa_tee,
 		Staking: pallet_staking,
 		Timestamp: pallet_timestamp,
@@ -242,6 +244,16 @@ impl pallet_staking::Config for Test {
 parameter_types! {
 	pub const ClusterSize: u32 = 2;
 	pub const MaxUriLen: u32 = 12;
+	pub const MaxQuoteLen: u32 = 256;
+	pub const SlashDeferDuration: u32 = 10;
+	pub const MaxSlashesPerBlock: u32 = 2;
+	pub const MaxMetrics: u32 = 10;
+	pub const MaxMetricNameLen: u32 = 32;
+	pub const InitialReportQuorum: u32 = 1;
+	pub const OutlierThreshold: u8 = 20;
+	pub const MaxNominators: u32 = 4;
+	pub const MinNominationBond: Balance = 10;
+	pub const MaxMissedHeartbeats: u32 = 3;
 	pub const ListSizeLimit: u32 = 10;
 	pub const TeeBondingDuration: u32 = 10;
 	pub const InitialStakingAmount: Balance = 20;
@@ -262,6 +274,19 @@ impl ternoa_tee::Config for Test {
 	type InitalDailyRewardPool = InitalDailyRewardPool;
 	type PalletId = TeePalletId;
 	type TeeHistoryDepth = TeeHistoryDepth;
+	type MaxQuoteLen = MaxQuoteLen;
+	type AttestationVerifier = ();
+	type Slash = ();
+	type SlashDeferDuration = SlashDeferDuration;
+	type MaxSlashesPerBlock = MaxSlashesPerBlock;
+	type MaxMetrics = MaxMetrics;
+	type MaxMetricNameLen = MaxMetricNameLen;
+	type InitialReportQuorum = InitialReportQuorum;
+	type OutlierThreshold = OutlierThreshold;
+	type MaxNominators = MaxNominators;
+	type MinNominationBond = MinNominationBond;
+	type MaxMissedHeartbeats = MaxMissedHeartbeats;
+	type EnclaveSlotHandler = ();
 }
 
 parameter_types! {
@@ -272,6 +297,7 @@ parameter_types! {
 	pub const CollectionSizeLimit: u32 = 10;
 	pub const InitialSecretMintFee: Balance = SECRET_NFT_MINT_FEE;
 	pub const ShardsNumber: u32 = 5;
+	pub const MaxResharesPerBlock: u32 = 10;
 	pub const InitialCapsuleMintFee: Balance = CAPSULE_MINT_FEE;
 	// Rent parameter types
 	pub const RentPalletId: PalletId = PalletId(*b"ter/rent");
@@ -280,6 +306,12 @@ parameter_types! {
 	pub const ActionsInBlockLimit: u32 = 10;
 	pub const MaximumContractAvailabilityLimit: u32 = 2000;
 	pub const MaximumContractDurationLimit: u32 = 100;
+	pub const BundleSizeLimit: u32 = 3;
+	// Marketplace parameter types
+	pub const MarketplaceInitialMintFee: Balance = MARKETPLACE_MINT_FEE;
+	pub const MarketplaceOffchainDataLimit: u32 = 150;
+	pub const MarketplaceAccountSizeLimit: u32 = 3;
+	pub const MarketplaceCollectionSizeLimit: u32 = 3;
 }
 
 impl ternoa_nft::Config for Test {
@@ -293,14 +325,29 @@ impl ternoa_nft::Config for Test {
 	type CollectionSizeLimit = CollectionSizeLimit;
 	type InitialSecretMintFee = InitialSecretMintFee;
 	type ShardsNumber = ShardsNumber;
+	type MaxResharesPerBlock = MaxResharesPerBlock;
 	type TEEExt = TEE;
+	type RentExt = Rent;
 	type InitialCapsuleMintFee = InitialCapsuleMintFee;
 }
 
+impl ternoa_marketplace::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type NFTExt = NFT;
+	type WeightInfo = ();
+	type FeesCollector = ();
+	type InitialMintFee = MarketplaceInitialMintFee;
+	type OffchainDataLimit = MarketplaceOffchainDataLimit;
+	type AccountSizeLimit = MarketplaceAccountSizeLimit;
+	type CollectionSizeLimit = MarketplaceCollectionSizeLimit;
+}
+
 impl Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type Currency = Balances;
 	type NFTExt = NFT;
+	type MarketplaceExt = Marketplace;
 	type WeightInfo = ternoa_rent::weights::TernoaWeight<Test>;
 	type PalletId = RentPalletId;
 	type AccountSizeLimit = RentAccountSizeLimit;
@@ -308,6 +355,7 @@ impl Config for Test {
 	type ActionsInBlockLimit = ActionsInBlockLimit;
 	type MaximumContractAvailabilityLimit = MaximumContractAvailabilityLimit;
 	type MaximumContractDurationLimit = MaximumContractDurationLimit;
+	type BundleSizeLimit = BundleSizeLimit;
 	type ExistentialDeposit = ExistentialDeposit;
 }
 
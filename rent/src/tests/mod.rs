@@ -16,4 +16,5 @@
 
 mod extrinsics;
 mod hooks;
+mod migrations;
 pub mod mock;
@@ -18,7 +18,11 @@ use frame_support::{
 	traits::Get, BoundedVec, CloneNoBound, PartialEqNoBound, RuntimeDebug, RuntimeDebugNoBound,
 };
 use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
-use primitives::nfts::NFTId;
+use primitives::{
+	marketplace::MarketplaceId,
+	nfts::{NFTId, UsageScope},
+	CompoundFee,
+};
 use scale_info::TypeInfo;
 use sp_arithmetic::traits::AtLeast32BitUnsigned;
 use sp_runtime::{Permill, SaturatedConversion};
@@ -31,6 +35,7 @@ pub struct SubscriptionInput<BlockNumber: Clone> {
 	pub period_length: BlockNumber,
 	pub max_duration: Option<BlockNumber>,
 	pub is_changeable: bool,
+	pub grace_period: BlockNumber,
 }
 
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
@@ -48,6 +53,8 @@ impl<BlockNumber: Clone> DurationInput<BlockNumber> {
 				max_duration: x.max_duration.unwrap_or_else(|| max_duration),
 				is_changeable: x.is_changeable,
 				new_terms: false,
+				grace_period: x.grace_period,
+				overdue_period: None,
 			}),
 		}
 	}
@@ -59,6 +66,10 @@ pub struct Subscription<BlockNumber: Clone> {
 	pub max_duration: BlockNumber,
 	pub is_changeable: bool,
 	pub new_terms: bool,
+	/// Number of blocks during which a failed period payment is retried.
+	pub grace_period: BlockNumber,
+	/// Index of the period whose payment is overdue, if any.
+	pub overdue_period: Option<u32>,
 }
 
 /// Enumeration of contract duration.
@@ -71,7 +82,7 @@ pub enum Duration<BlockNumber: Clone> {
 impl<Blocknumber: Clone> Duration<Blocknumber> {
 	pub fn allows_rent_fee<Balance: Clone>(&self, rent_fee: &RentFee<Balance>) -> Option<()> {
 		match self {
-			Self::Subscription(_) => rent_fee.get_nft().is_none(),
+			Self::Subscription(_) => rent_fee.get_nft().is_none() && !rent_fee.is_escrowed(),
 			_ => true,
 		}
 		.then(|| ())
@@ -123,6 +134,20 @@ impl<Blocknumber: Clone> Duration<Blocknumber> {
 		}
 	}
 
+	pub fn overdue_period(&self) -> Option<u32> {
+		match self {
+			Duration::Subscription(x) => x.overdue_period,
+			_ => None,
+		}
+	}
+
+	pub fn set_overdue_period(&mut self, value: Option<u32>) {
+		match self {
+			Duration::Subscription(x) => x.overdue_period = value,
+			_ => (),
+		}
+	}
+
 	pub fn set_terms_changed(&mut self, value: bool) {
 		match self {
 			Duration::Subscription(x) => x.new_terms = value,
@@ -155,6 +180,8 @@ where
 {
 	Tokens(Balance),
 	NFT(NFTId),
+	/// Tokens held by the pallet and released to the renter as the contract progresses.
+	EscrowedTokens(Balance),
 }
 
 impl<Balance> RentFee<Balance>
@@ -163,11 +190,18 @@ where
 {
 	pub fn get_balance(&self) -> Option<Balance> {
 		match self {
-			Self::Tokens(x) => Some(x.clone()),
+			Self::Tokens(x) | Self::EscrowedTokens(x) => Some(x.clone()),
 			_ => None,
 		}
 	}
 
+	pub fn is_escrowed(&self) -> bool {
+		match self {
+			Self::EscrowedTokens(_) => true,
+			_ => false,
+		}
+	}
+
 	pub fn get_nft(&self) -> Option<NFTId> {
 		match self {
 			Self::NFT(x) => Some(*x),
@@ -246,6 +280,14 @@ where
 	pub rentee_cancellation_fee: CancellationFee<Balance>,
 	/// creation block of the contract.
 	pub creation_block: BlockNumber,
+	/// Rentee can sublet the NFT.
+	pub allow_sublet: bool,
+	/// What the rentee is allowed to do with the NFT.
+	pub usage_scope: UsageScope,
+	/// Marketplace through which the contract was created.
+	pub marketplace_id: Option<MarketplaceId>,
+	/// Marketplace commission taken from every rent payment.
+	pub commission_fee: Option<CompoundFee<Balance>>,
 }
 
 impl<AccountId, BlockNumber, Balance, AccountSizeLimit>
@@ -267,6 +309,9 @@ where
 		renter_cancellation_fee: CancellationFee<Balance>,
 		rentee_cancellation_fee: CancellationFee<Balance>,
 		creation_block: BlockNumber,
+		allow_sublet: bool,
+		marketplace_id: Option<MarketplaceId>,
+		commission_fee: Option<CompoundFee<Balance>>,
 	) -> RentContractData<AccountId, BlockNumber, Balance, AccountSizeLimit> {
 		Self {
 			start_block,
@@ -279,6 +324,10 @@ where
 			renter_cancellation_fee,
 			rentee_cancellation_fee,
 			creation_block,
+			allow_sublet,
+			usage_scope: UsageScope::default(),
+			marketplace_id,
+			commission_fee,
 		}
 	}
 
@@ -297,6 +346,30 @@ where
 		(*now - start) >= *end
 	}
 
+	/// Returns the index and the start block of the first subscription period starting at or
+	/// after `block`. Period boundaries are always computed from the contract start block.
+	pub fn next_subscription_period(&self, block: &BlockNumber) -> Option<(u32, BlockNumber)> {
+		let start = self.start_block?;
+		let period = self.duration.as_subscription()?.period_length;
+		if period == 0u32.into() {
+			return None
+		}
+
+		let mut index: u32 = (block.saturating_sub(start) / period).saturated_into();
+		if self.subscription_period_start(index)? < *block {
+			index = index.saturating_add(1);
+		}
+
+		Some((index, self.subscription_period_start(index)?))
+	}
+
+	/// Returns the block at which the subscription period of index `index` starts.
+	pub fn subscription_period_start(&self, index: u32) -> Option<BlockNumber> {
+		let start = self.start_block?;
+		let period = self.duration.as_subscription()?.period_length;
+		Some(start.saturating_add(period.saturating_mul(index.into())))
+	}
+
 	pub fn can_adjust_subscription(&self) -> bool {
 		let is_changeable = self.duration.as_subscription().and_then(|x| Some(x.is_changeable));
 		is_changeable.unwrap_or(false)
@@ -323,6 +396,72 @@ where
 	pub fn is_renter(&self, account: &AccountId) -> Option<()> {
 		(self.renter == *account).then(|| {})
 	}
+
+	/// Replace the contract terms by the ones proposed in an offer.
+	pub fn apply_offer(&mut self, offer: &RentOffer<AccountId, BlockNumber, Balance>) {
+		if let Some(fee) = &offer.rent_fee {
+			self.rent_fee = match &self.rent_fee {
+				RentFee::Tokens(_) => RentFee::Tokens(fee.clone()),
+				RentFee::EscrowedTokens(_) => RentFee::EscrowedTokens(fee.clone()),
+				RentFee::NFT(x) => RentFee::NFT(*x),
+			};
+		}
+		if let (Some(duration), Duration::Fixed(_)) = (offer.duration, &self.duration) {
+			self.duration = Duration::Fixed(duration);
+		}
+	}
+
+	/// Returns the block at which the contract ends.
+	pub fn end_block(&self) -> Option<BlockNumber> {
+		Some(self.start_block? + *self.duration.get_full_duration())
+	}
+}
+
+/// Offer made for a manual acceptance contract. The contract terms are used for the rent fee and
+/// the duration when they are not proposed.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct RentOffer<AccountId, BlockNumber, Balance> {
+	/// Account making the offer.
+	pub rentee: AccountId,
+	/// Proposed rent fee.
+	pub rent_fee: Option<Balance>,
+	/// Proposed duration, only for fixed contracts.
+	pub duration: Option<BlockNumber>,
+	/// Amount reserved from the rentee until the offer is accepted, retracted or refunded.
+	pub reserved: Balance,
+}
+
+/// Extension of a running fixed contract proposed by one of its participants.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct ExtensionProposal<AccountId, BlockNumber, Balance> {
+	/// Participant proposing the extension.
+	pub proposer: AccountId,
+	/// Number of blocks added to the contract duration.
+	pub extra_blocks: BlockNumber,
+	/// Fee paid by the rentee to the renter for the extension.
+	pub extra_fee: Balance,
+}
+
+/// Sublet of a running contract created by its rentee. A sublet always ends before its parent
+/// contract.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct SubletContractData<AccountId, BlockNumber, Balance> {
+	/// Start block of the sublet.
+	pub start_block: Option<BlockNumber>,
+	/// Rentee of the parent contract.
+	pub renter: AccountId,
+	/// Rentee of the sublet.
+	pub rentee: Option<AccountId>,
+	/// Duration of the sublet.
+	pub duration: BlockNumber,
+	/// Rent fee paid by the sublet rentee to the parent contract rentee.
+	pub rent_fee: Balance,
+}
+
+impl<AccountId, BlockNumber, Balance> SubletContractData<AccountId, BlockNumber, Balance> {
+	pub fn new(renter: AccountId, duration: BlockNumber, rent_fee: Balance) -> Self {
+		Self { start_block: None, renter, rentee: None, duration, rent_fee }
+	}
 }
 
 /// wrapper type to store queues of either fixed duration contracts, subscription contract or
@@ -402,9 +541,19 @@ where
 			None
 		}
 	}
+
+	/// Pop and return the first value of the queue along with the block it was queued for.
+	pub fn pop_next_scheduled(&mut self, block_number: BlockNumber) -> Option<(NFTId, BlockNumber)> {
+		let front = self.0.get(0)?;
+		if front.1 <= block_number {
+			Some(self.0.remove(0))
+		} else {
+			None
+		}
+	}
 }
 
-impl<BlockNumber, Limit> Queue<BlockNumber, Limit>
+impl<BlockNumber, Limit> Default for Queue<BlockNumber, Limit>
 where
 	BlockNumber: Clone + PartialEq + Debug + sp_std::cmp::PartialOrd,
 	Limit: Get<u32>,
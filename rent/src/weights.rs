@@ -27,6 +27,14 @@ pub trait WeightInfo {
 	fn retract_rent_offer(_s: u32) -> Weight;
 	fn change_subscription_terms(_s: u32) -> Weight;
 	fn accept_subscription_terms(_s: u32) -> Weight;
+	fn claim_escrowed_rent_fee() -> Weight;
+	fn create_sublet() -> Weight;
+	fn rent_sublet(_s: u32) -> Weight;
+	fn cancel_sublet() -> Weight;
+	fn propose_extension() -> Weight;
+	fn accept_extension(_s: u32) -> Weight;
+	fn bundle_nfts(_s: u32) -> Weight;
+	fn set_usage_scope() -> Weight;
 }
 
 /// Weight functions for `ternoa_rent`.
@@ -59,4 +67,28 @@ impl<T: frame_system::Config> WeightInfo for TernoaWeight<T> {
 	fn accept_subscription_terms(_s: u32) -> Weight {
 		Weight::from_parts(10_000_000, 0)
 	}
+	fn claim_escrowed_rent_fee() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
+	fn create_sublet() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
+	fn rent_sublet(_s: u32) -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
+	fn cancel_sublet() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
+	fn propose_extension() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
+	fn accept_extension(_s: u32) -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
+	fn bundle_nfts(_s: u32) -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
+	fn set_usage_scope() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
 }
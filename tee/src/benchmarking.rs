@@ -46,6 +46,84 @@ pub fn prepare_benchmarks<T: Config>() {
 
 	let auction_account = TEE::<T>::account_id();
 	T::Currency::make_free_balance_be(&auction_account, BalanceOf::<T>::max_value() / 5u32.into());
+
+	let policy = MeasurementPolicy::MrEnclave(BENCHMARK_MEASUREMENT.mr_enclave);
+	TEE::<T>::approve_measurement(RawOrigin::Root.into(), policy).unwrap();
+}
+
+pub const BENCHMARK_MEASUREMENT: EnclaveMeasurement =
+	EnclaveMeasurement { mr_enclave: [1; 32], mr_signer: [2; 32] };
+
+pub fn attestation<T: Config>(enclave_address: &T::AccountId) -> AttestationQuote<T::MaxQuoteLen> {
+	let verified = VerifiedQuote {
+		measurement: BENCHMARK_MEASUREMENT,
+		report_data: blake2_256(&enclave_address.encode()),
+	};
+	let quote = T::AttestationVerifier::benchmark_quote(&TeeKind::Sgx, &verified);
+	AttestationQuote::new(TeeKind::Sgx, BENCHMARK_MEASUREMENT, quote.try_into().unwrap())
+}
+
+pub fn metrics<T: Config>() -> BoundedVec<Metric<T::MaxMetricNameLen>, T::MaxMetrics> {
+	let name: BoundedVec<u8, T::MaxMetricNameLen> =
+		BoundedVec::try_from(vec![1; T::MaxMetricNameLen::get() as usize]).unwrap();
+	let metrics: Vec<_> = (0..T::MaxMetrics::get())
+		.map(|id| Metric::new(id, name.clone(), 20, AggregationRule::Median))
+		.collect();
+	BoundedVec::try_from(metrics).unwrap()
+}
+
+pub fn metrics_report<T: Config>(
+	submitted_by: T::AccountId,
+) -> MetricsServerReport<T::AccountId, T::MaxMetrics> {
+	let values: Vec<_> = (0..T::MaxMetrics::get()).map(|id| (id, 20)).collect();
+	MetricsServerReport::new(BoundedVec::try_from(values).unwrap(), submitted_by)
+}
+
+/// Prepare the rewards of ALICE for the era 10.
+pub fn prepare_rewards<T: Config>() {
+	prepare_benchmarks::<T>();
+	let raw = (10 as sp_staking::EraIndex, Some(10u64)).encode();
+	let info = pallet_staking::ActiveEraInfo::decode(&mut &raw[..]).unwrap();
+	pallet_staking::ActiveEra::<T>::put(&info);
+
+	let alice: T::AccountId = get_account::<T>("ALICE");
+	let enclave_address: T::AccountId = get_account::<T>("ALICE_ENCLAVE");
+	let uri: BoundedVec<u8, T::MaxUriLen> =
+		BoundedVec::try_from(vec![1; T::MaxUriLen::get() as usize]).unwrap();
+
+	TEE::<T>::create_cluster(RawOrigin::Root.into(), ClusterType::Public).unwrap();
+	TEE::<T>::register_enclave(
+		origin::<T>("ALICE").into(),
+		enclave_address.clone(),
+		uri,
+		attestation::<T>(&enclave_address),
+	)
+	.unwrap();
+	TEE::<T>::assign_enclave(RawOrigin::Root.into(), alice.clone(), 0, 0).unwrap();
+
+	let metrics_server: MetricsServer<T::AccountId> =
+		MetricsServer::new(alice.clone(), ClusterType::Public);
+	TEE::<T>::register_metrics_server(RawOrigin::Root.into(), metrics_server).unwrap();
+	TEE::<T>::set_metrics(RawOrigin::Root.into(), metrics::<T>()).unwrap();
+
+	// Reports submitted during era 11 are accounted to era 10
+	let raw = (11 as sp_staking::EraIndex, Some(11u64)).encode();
+	let info = pallet_staking::ActiveEraInfo::decode(&mut &raw[..]).unwrap();
+	pallet_staking::ActiveEra::<T>::put(&info);
+
+	let metrics_server_report = metrics_report::<T>(alice.clone());
+	TEE::<T>::submit_metrics_server_report(
+		origin::<T>("ALICE").into(),
+		alice.clone(),
+		metrics_server_report,
+	)
+	.unwrap();
+	TEE::<T>::snapshot_era(10);
+	TEE::<T>::compute_era_reward_points(10);
+
+	let raw = (15 as sp_staking::EraIndex, Some(15u64)).encode();
+	let info = pallet_staking::ActiveEraInfo::decode(&mut &raw[..]).unwrap();
+	pallet_staking::ActiveEra::<T>::put(&info);
 }
 
 benchmarks! {
@@ -56,7 +134,8 @@ benchmarks! {
 		let uri: BoundedVec<u8, T::MaxUriLen> = BoundedVec::try_from(vec![1; T::MaxUriLen::get() as usize]).unwrap();
 		let enclave = Enclave::new(enclave_address.clone(), uri.clone());
 
-	}: _(origin::<T>("ALICE"), enclave_address.clone(), uri)
+		let attestation = attestation::<T>(&enclave_address);
+	}: _(origin::<T>("ALICE"), enclave_address.clone(), uri, attestation)
 	verify {
 		assert_eq!(EnclaveRegistrations::<T>::get(alice), Some(enclave));
 	}
@@ -75,7 +154,7 @@ benchmarks! {
 		pallet_staking::ActiveEra::<T>::put(&info);
 
 		TEE::<T>::create_cluster(RawOrigin::Root.into(), ClusterType::Public).unwrap();
-		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone()).unwrap();
+		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone(), attestation::<T>(&enclave_address)).unwrap();
 		TEE::<T>::assign_enclave(RawOrigin::Root.into(), alice.clone(), cluster_id, slot_id).unwrap();
 	}: _(origin::<T>("ALICE"))
 
@@ -93,16 +172,18 @@ benchmarks! {
 		pallet_staking::ActiveEra::<T>::put(&info);
 
 		TEE::<T>::create_cluster(RawOrigin::Root.into(), ClusterType::Public).unwrap();
-		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone()).unwrap();
+		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone(), attestation::<T>(&enclave_address)).unwrap();
 		TEE::<T>::assign_enclave(RawOrigin::Root.into(), alice.clone(), cluster_id, slot_id).unwrap();
 
 		let bob: T::AccountId = get_account::<T>("BOB");
 		let new_enclave_address: T::AccountId= get_account::<T>("BOB");
 		let new_uri: BoundedVec<u8, T::MaxUriLen> = BoundedVec::try_from(vec![1; T::MaxUriLen::get() as usize]).unwrap();
 		let new_enclave = Enclave::new(new_enclave_address.clone(), new_uri.clone());
-	}: _(origin::<T>("ALICE"), new_enclave_address.clone(), new_uri)
+		let new_attestation = attestation::<T>(&new_enclave_address);
+	}: _(origin::<T>("ALICE"), new_enclave_address.clone(), new_uri, new_attestation)
 	verify {
-		assert_eq!(EnclaveUpdates::<T>::get(alice), Some(new_enclave));
+		assert_eq!(EnclaveUpdates::<T>::get(alice.clone()), Some(new_enclave));
+		assert_eq!(EnclaveUpdateAttestations::<T>::get(alice), Some(BENCHMARK_MEASUREMENT));
 	}
 
 	cancel_update {
@@ -120,9 +201,9 @@ benchmarks! {
 		pallet_staking::ActiveEra::<T>::put(&info);
 
 		TEE::<T>::create_cluster(RawOrigin::Root.into(), ClusterType::Public).unwrap();
-		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone()).unwrap();
+		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone(), attestation::<T>(&enclave_address)).unwrap();
 		TEE::<T>::assign_enclave(RawOrigin::Root.into(), alice.clone(), cluster_id, slot_id).unwrap();
-		TEE::<T>::update_enclave(origin::<T>("ALICE").into(), new_enclave_address.clone(), uri.clone()).unwrap();
+		TEE::<T>::update_enclave(origin::<T>("ALICE").into(), new_enclave_address.clone(), uri.clone(), attestation::<T>(&new_enclave_address)).unwrap();
 
 	}: _(origin::<T>("ALICE"))
 	verify {
@@ -143,7 +224,7 @@ benchmarks! {
 		pallet_staking::ActiveEra::<T>::put(&info);
 
 		TEE::<T>::create_cluster(RawOrigin::Root.into(), ClusterType::Public).unwrap();
-		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone()).unwrap();
+		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone(), attestation::<T>(&enclave_address)).unwrap();
 
 	}: _(RawOrigin::Root, alice.clone(), cluster_id, slot_id)
 	verify {
@@ -160,7 +241,7 @@ benchmarks! {
 		let uri: BoundedVec<u8, T::MaxUriLen> = BoundedVec::try_from(vec![1; T::MaxUriLen::get() as usize]).unwrap();
 		let enclave = Enclave::new(enclave_address.clone(), uri.clone());
 
-		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone()).unwrap();
+		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone(), attestation::<T>(&enclave_address)).unwrap();
 	}: _(RawOrigin::Root, alice.clone())
 	verify {
 		assert_eq!(EnclaveRegistrations::<T>::get(alice), None);
@@ -180,14 +261,14 @@ benchmarks! {
 		pallet_staking::ActiveEra::<T>::put(&info);
 
 		TEE::<T>::create_cluster(RawOrigin::Root.into(), ClusterType::Public).unwrap();
-		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone()).unwrap();
+		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone(), attestation::<T>(&enclave_address)).unwrap();
 		TEE::<T>::assign_enclave(RawOrigin::Root.into(), alice.clone(), cluster_id, slot_id).unwrap();
 
 		let bob: T::AccountId = get_account::<T>("BOB");
 		let new_enclave_address: T::AccountId= get_account::<T>("BOB");
 		let new_uri: BoundedVec<u8, T::MaxUriLen> = BoundedVec::try_from(vec![1; T::MaxUriLen::get() as usize]).unwrap();
 		let new_enclave = Enclave::new(new_enclave_address.clone(), new_uri.clone());
-		TEE::<T>::update_enclave(origin::<T>("ALICE").into(), new_enclave_address.clone(), new_uri).unwrap();
+		TEE::<T>::update_enclave(origin::<T>("ALICE").into(), new_enclave_address.clone(), new_uri, attestation::<T>(&new_enclave_address)).unwrap();
 	}: _(RawOrigin::Root, alice.clone())
 	verify {
 		assert_eq!(EnclaveUpdates::<T>::get(alice), None);
@@ -207,7 +288,7 @@ benchmarks! {
 		pallet_staking::ActiveEra::<T>::put(&info);
 
 		TEE::<T>::create_cluster(RawOrigin::Root.into(), ClusterType::Public).unwrap();
-		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone()).unwrap();
+		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone(), attestation::<T>(&enclave_address)).unwrap();
 		TEE::<T>::assign_enclave(RawOrigin::Root.into(), alice.clone(), cluster_id, slot_id).unwrap();
 	}: _(RawOrigin::Root, alice.clone())
 	verify {
@@ -232,13 +313,13 @@ benchmarks! {
 		pallet_staking::ActiveEra::<T>::put(&info);
 
 		TEE::<T>::create_cluster(RawOrigin::Root.into(), ClusterType::Public).unwrap();
-		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone()).unwrap();
+		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone(), attestation::<T>(&enclave_address)).unwrap();
 		TEE::<T>::assign_enclave(RawOrigin::Root.into(), alice.clone(), cluster_id, slot_id).unwrap();
 
 		let new_enclave_address: T::AccountId= get_account::<T>("BOB");
 		let new_uri: BoundedVec<u8, T::MaxUriLen> = BoundedVec::try_from(vec![1; T::MaxUriLen::get() as usize]).unwrap();
 		let new_enclave = Enclave::new(new_enclave_address.clone(), new_uri.clone());
-		TEE::<T>::update_enclave(origin::<T>("ALICE").into(), new_enclave_address.clone(), new_uri.clone()).unwrap();
+		TEE::<T>::update_enclave(origin::<T>("ALICE").into(), new_enclave_address.clone(), new_uri.clone(), attestation::<T>(&new_enclave_address)).unwrap();
 
 	}: _(RawOrigin::Root, alice.clone(), Some(new_enclave_address.clone()), Some(new_uri))
 	verify {
@@ -313,7 +394,7 @@ benchmarks! {
 		pallet_staking::ActiveEra::<T>::put(&info);
 
 		TEE::<T>::create_cluster(RawOrigin::Root.into(), ClusterType::Public).unwrap();
-		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone()).unwrap();
+		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone(), attestation::<T>(&enclave_address)).unwrap();
 		TEE::<T>::assign_enclave(RawOrigin::Root.into(), alice.clone(), cluster_id, slot_id).unwrap();
 
 		let metrics_server: MetricsServer<T::AccountId> = MetricsServer::new(alice.clone(), ClusterType::Public);
@@ -339,7 +420,7 @@ benchmarks! {
 		pallet_staking::ActiveEra::<T>::put(&info);
 
 		TEE::<T>::create_cluster(RawOrigin::Root.into(), ClusterType::Public).unwrap();
-		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone()).unwrap();
+		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone(), attestation::<T>(&enclave_address)).unwrap();
 		TEE::<T>::assign_enclave(RawOrigin::Root.into(), alice.clone(), cluster_id, slot_id).unwrap();
 
 		let metrics_server: MetricsServer<T::AccountId> = MetricsServer::new(alice.clone(), ClusterType::Public);
@@ -368,38 +449,24 @@ benchmarks! {
 		pallet_staking::ActiveEra::<T>::put(&info);
 
 		TEE::<T>::create_cluster(RawOrigin::Root.into(), ClusterType::Public).unwrap();
-		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone()).unwrap();
+		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone(), attestation::<T>(&enclave_address)).unwrap();
 		TEE::<T>::assign_enclave(RawOrigin::Root.into(), alice.clone(), cluster_id, slot_id).unwrap();
 
 		let metrics_server: MetricsServer<T::AccountId> = MetricsServer::new(alice.clone(), ClusterType::Public);
 		TEE::<T>::register_metrics_server(RawOrigin::Root.into(), metrics_server).unwrap();
+		TEE::<T>::set_metrics(RawOrigin::Root.into(), metrics::<T>()).unwrap();
 
-		let metrics_server_report: MetricsServerReport<T::AccountId> = MetricsServerReport {
-			param_1: 20,
-			param_2: 20,
-			param_3: 20,
-			param_4: 20,
-			param_5: 20,
-			submitted_by: alice.clone(),
-		};
-
-
+		let metrics_server_report = metrics_report::<T>(alice.clone());
 	}: _(origin::<T>("ALICE"), alice.clone(), metrics_server_report.clone())
 	verify {
 		assert_eq!(MetricsReports::<T>::get(3, alice).unwrap(), vec![metrics_server_report]);
 	}
 
-	set_report_params_weightage {
-		let report_params_weightage = ReportParamsWeightage {
-			param_1_weightage: 20,
-			param_2_weightage: 20,
-			param_3_weightage: 20,
-			param_4_weightage: 20,
-			param_5_weightage: 20,
-		};
-	}: _(RawOrigin::Root, report_params_weightage.clone())
+	set_metrics {
+		let metrics = metrics::<T>();
+	}: _(RawOrigin::Root, metrics.clone())
 	verify {
-		assert_eq!(ReportParamsWeightages::<T>::get(), report_params_weightage);
+		assert_eq!(Metrics::<T>::get(), metrics);
 	}
 
 	set_staking_amount {
@@ -417,51 +484,12 @@ benchmarks! {
 	}
 
 	claim_rewards {
-		prepare_benchmarks::<T>();
-		let raw = (10 as sp_staking::EraIndex, Some(10u64)).encode();
-		let info = pallet_staking::ActiveEraInfo::decode(&mut &raw[..]).unwrap();
-		pallet_staking::ActiveEra::<T>::put(&info);
-
+		prepare_rewards::<T>();
 		let alice: T::AccountId = get_account::<T>("ALICE");
-		let bob: T::AccountId = get_account::<T>("BOB");
-
-		let cluster_id: ClusterId = 0;
-		let slot_id: SlotId = 0;
-		let enclave_address: T::AccountId= get_account::<T>("ALICE_ENCLAVE");
-		let uri: BoundedVec<u8, T::MaxUriLen> = BoundedVec::try_from(vec![1; T::MaxUriLen::get() as usize]).unwrap();
-		let enclave = Enclave::new(enclave_address.clone(), uri.clone());
-
-		TEE::<T>::create_cluster(RawOrigin::Root.into(), ClusterType::Public).unwrap();
-		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone()).unwrap();
-		TEE::<T>::assign_enclave(RawOrigin::Root.into(), alice.clone(), cluster_id, slot_id).unwrap();
-
-		let metrics_server: MetricsServer<T::AccountId> = MetricsServer::new(alice.clone(), ClusterType::Public);
-		TEE::<T>::register_metrics_server(RawOrigin::Root.into(), metrics_server).unwrap();
-
-		let report_params_weightage = ReportParamsWeightage {
-			param_1_weightage: 20,
-			param_2_weightage: 20,
-			param_3_weightage: 20,
-			param_4_weightage: 20,
-			param_5_weightage: 20,
-		};
-		TEE::<T>::set_report_params_weightage(RawOrigin::Root.into(), report_params_weightage).unwrap();
-
-		let metrics_server_report: MetricsServerReport<T::AccountId> = MetricsServerReport {
-			param_1: 20,
-			param_2: 20,
-			param_3: 20,
-			param_4: 20,
-			param_5: 20,
-			submitted_by: alice.clone(),
-		};
-		TEE::<T>::submit_metrics_server_report(origin::<T>("ALICE").into(), alice.clone(), metrics_server_report).unwrap();
-
-		let raw = (15 as sp_staking::EraIndex, Some(15u64)).encode();
-		let info = pallet_staking::ActiveEraInfo::decode(&mut &raw[..]).unwrap();
-		pallet_staking::ActiveEra::<T>::put(&info);
-
 	}: _(origin::<T>("ALICE"), 10)
+	verify {
+		assert!(ClaimedRewards::<T>::get(10, alice).is_some());
+	}
 
 	update_operator_assigned_era {
 		prepare_benchmarks::<T>();
@@ -479,7 +507,7 @@ benchmarks! {
 		let enclave = Enclave::new(enclave_address.clone(), uri.clone());
 
 		TEE::<T>::create_cluster(RawOrigin::Root.into(), ClusterType::Public).unwrap();
-		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone()).unwrap();
+		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone(), attestation::<T>(&enclave_address)).unwrap();
 		TEE::<T>::assign_enclave(RawOrigin::Root.into(), alice.clone(), cluster_id, slot_id).unwrap();
 	}: _(RawOrigin::Root, alice.clone(), 11)
 	verify {
@@ -504,7 +532,7 @@ benchmarks! {
 
 		TEE::<T>::set_staking_amount(RawOrigin::Root.into(), stake_amount).unwrap();
 		TEE::<T>::create_cluster(RawOrigin::Root.into(), ClusterType::Public).unwrap();
-		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone()).unwrap();
+		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone(), attestation::<T>(&enclave_address)).unwrap();
 		TEE::<T>::assign_enclave(RawOrigin::Root.into(), alice.clone(), cluster_id, slot_id).unwrap();
 		let stake_amount: BalanceOf<T> = 60u32.into();
 
@@ -534,7 +562,7 @@ benchmarks! {
 		TEE::<T>::set_staking_amount(RawOrigin::Root.into(), stake_amount).unwrap();
 
 		TEE::<T>::create_cluster(RawOrigin::Root.into(), ClusterType::Public).unwrap();
-		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone()).unwrap();
+		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone(), attestation::<T>(&enclave_address)).unwrap();
 		TEE::<T>::assign_enclave(RawOrigin::Root.into(), alice.clone(), cluster_id, slot_id).unwrap();
 		let stake_amount: BalanceOf<T> = 10u32.into();
 
@@ -543,6 +571,184 @@ benchmarks! {
 	verify {
 		assert_eq!(OperatorAssignedEra::<T>::get(alice).unwrap(), 10);
 	}
+
+	approve_measurement {
+		let policy = MeasurementPolicy::MrSigner([2; 32]);
+	}: _(RawOrigin::Root, policy.clone())
+	verify {
+		assert!(ApprovedMeasurements::<T>::get(policy).is_some());
+	}
+
+	revoke_measurement {
+		let policy = MeasurementPolicy::MrSigner([2; 32]);
+		TEE::<T>::approve_measurement(RawOrigin::Root.into(), policy.clone()).unwrap();
+	}: _(RawOrigin::Root, policy.clone())
+	verify {
+		assert!(ApprovedMeasurements::<T>::get(policy).is_none());
+	}
+
+	slash_operator {
+		prepare_benchmarks::<T>();
+		let alice: T::AccountId = get_account::<T>("ALICE");
+		let enclave_address: T::AccountId = get_account::<T>("ALICE_ENCLAVE");
+		let uri: BoundedVec<u8, T::MaxUriLen> = BoundedVec::try_from(vec![1; T::MaxUriLen::get() as usize]).unwrap();
+		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone(), attestation::<T>(&enclave_address)).unwrap();
+	}: _(RawOrigin::Root, alice.clone(), Perbill::from_percent(10), SlashReason::Offline)
+	verify {
+		assert!(PendingSlashes::<T>::get(0).is_some());
+	}
+
+	cancel_slash {
+		prepare_benchmarks::<T>();
+		let alice: T::AccountId = get_account::<T>("ALICE");
+		let enclave_address: T::AccountId = get_account::<T>("ALICE_ENCLAVE");
+		let uri: BoundedVec<u8, T::MaxUriLen> = BoundedVec::try_from(vec![1; T::MaxUriLen::get() as usize]).unwrap();
+		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone(), attestation::<T>(&enclave_address)).unwrap();
+		TEE::<T>::slash_operator(RawOrigin::Root.into(), alice, Perbill::from_percent(10), SlashReason::Offline).unwrap();
+	}: _(RawOrigin::Root, 0)
+	verify {
+		assert!(PendingSlashes::<T>::get(0).is_none());
+	}
+
+	set_report_quorum {
+		let quorum: u32 = 3;
+	}: _(RawOrigin::Root, quorum)
+	verify {
+		assert_eq!(ReportQuorum::<T>::get(), quorum);
+	}
+
+	set_reward_destination {
+		prepare_benchmarks::<T>();
+		let alice: T::AccountId = get_account::<T>("ALICE");
+		let bob: T::AccountId = get_account::<T>("BOB");
+		let enclave_address: T::AccountId = get_account::<T>("ALICE_ENCLAVE");
+		let uri: BoundedVec<u8, T::MaxUriLen> = BoundedVec::try_from(vec![1; T::MaxUriLen::get() as usize]).unwrap();
+		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri, attestation::<T>(&enclave_address)).unwrap();
+
+		let destination = RewardDestination::Account(bob);
+	}: _(origin::<T>("ALICE"), destination.clone())
+	verify {
+		assert_eq!(RewardDestinations::<T>::get(alice), destination);
+	}
+
+	payout_operator {
+		prepare_rewards::<T>();
+		let alice: T::AccountId = get_account::<T>("ALICE");
+		let bob: T::AccountId = get_account::<T>("BOB");
+		TEE::<T>::set_reward_destination(origin::<T>("ALICE").into(), RewardDestination::Account(bob)).unwrap();
+	}: _(origin::<T>("BOB"), alice.clone(), 10)
+	verify {
+		assert!(ClaimedRewards::<T>::get(10, alice).is_some());
+	}
+
+	nominate {
+		prepare_benchmarks::<T>();
+		let alice: T::AccountId = get_account::<T>("ALICE");
+		let bob: T::AccountId = get_account::<T>("BOB");
+		let enclave_address: T::AccountId = get_account::<T>("ALICE_ENCLAVE");
+		let uri: BoundedVec<u8, T::MaxUriLen> = BoundedVec::try_from(vec![1; T::MaxUriLen::get() as usize]).unwrap();
+		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri, attestation::<T>(&enclave_address)).unwrap();
+		let amount = T::MinNominationBond::get();
+	}: _(origin::<T>("BOB"), alice.clone(), amount)
+	verify {
+		assert_eq!(Nominators::<T>::get(alice).into_inner(), vec![(bob, amount)]);
+	}
+
+	unbond_nomination {
+		prepare_benchmarks::<T>();
+		let alice: T::AccountId = get_account::<T>("ALICE");
+		let bob: T::AccountId = get_account::<T>("BOB");
+		let enclave_address: T::AccountId = get_account::<T>("ALICE_ENCLAVE");
+		let uri: BoundedVec<u8, T::MaxUriLen> = BoundedVec::try_from(vec![1; T::MaxUriLen::get() as usize]).unwrap();
+		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri, attestation::<T>(&enclave_address)).unwrap();
+		TEE::<T>::nominate(origin::<T>("BOB").into(), alice.clone(), T::MinNominationBond::get()).unwrap();
+	}: _(origin::<T>("BOB"))
+	verify {
+		assert!(Nominators::<T>::get(alice).is_empty());
+	}
+
+	withdraw_nomination {
+		prepare_benchmarks::<T>();
+		let alice: T::AccountId = get_account::<T>("ALICE");
+		let bob: T::AccountId = get_account::<T>("BOB");
+		let enclave_address: T::AccountId = get_account::<T>("ALICE_ENCLAVE");
+		let uri: BoundedVec<u8, T::MaxUriLen> = BoundedVec::try_from(vec![1; T::MaxUriLen::get() as usize]).unwrap();
+		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri, attestation::<T>(&enclave_address)).unwrap();
+		TEE::<T>::nominate(origin::<T>("BOB").into(), alice.clone(), T::MinNominationBond::get()).unwrap();
+		TEE::<T>::unbond_nomination(origin::<T>("BOB").into()).unwrap();
+		let now = frame_system::Pallet::<T>::block_number();
+		frame_system::Pallet::<T>::set_block_number(now + T::TeeBondingDuration::get().into());
+	}: _(origin::<T>("BOB"))
+	verify {
+		assert!(NominatorLedgers::<T>::get(bob).is_none());
+	}
+
+	set_commission {
+		prepare_benchmarks::<T>();
+		let alice: T::AccountId = get_account::<T>("ALICE");
+		let enclave_address: T::AccountId = get_account::<T>("ALICE_ENCLAVE");
+		let uri: BoundedVec<u8, T::MaxUriLen> = BoundedVec::try_from(vec![1; T::MaxUriLen::get() as usize]).unwrap();
+		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri, attestation::<T>(&enclave_address)).unwrap();
+		let commission = Perbill::from_percent(10);
+	}: _(origin::<T>("ALICE"), commission)
+	verify {
+		assert_eq!(Commissions::<T>::get(alice), commission);
+	}
+
+	set_min_cluster_backing {
+		let amount: BalanceOf<T> = 100u32.into();
+	}: _(RawOrigin::Root, amount)
+	verify {
+		assert_eq!(MinClusterBacking::<T>::get(), amount);
+	}
+
+	auto_assign_enclave {
+		prepare_benchmarks::<T>();
+		let alice: T::AccountId = get_account::<T>("ALICE");
+		let enclave_address: T::AccountId= get_account::<T>("ALICE_ENCLAVE");
+		let uri: BoundedVec<u8, T::MaxUriLen> = BoundedVec::try_from(vec![1; T::MaxUriLen::get() as usize]).unwrap();
+		let enclave = Enclave::new(enclave_address.clone(), uri.clone());
+
+		let raw = (4 as sp_staking::EraIndex, Some(10u64)).encode();
+		let info = pallet_staking::ActiveEraInfo::decode(&mut &raw[..]).unwrap();
+		pallet_staking::ActiveEra::<T>::put(&info);
+
+		TEE::<T>::create_cluster(RawOrigin::Root.into(), ClusterType::Public).unwrap();
+		TEE::<T>::create_cluster(RawOrigin::Root.into(), ClusterType::Public).unwrap();
+		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone(), attestation::<T>(&enclave_address)).unwrap();
+	}: _(RawOrigin::Root, alice.clone(), ClusterType::Public)
+	verify {
+		assert_eq!(EnclaveData::<T>::get(alice.clone()), Some(enclave));
+		assert!(EnclaveClusterId::<T>::get(alice.clone()).is_some());
+		assert_eq!(EnclaveRegistrations::<T>::get(alice), None);
+	}
+
+	heartbeat {
+		prepare_benchmarks::<T>();
+		let alice: T::AccountId = get_account::<T>("ALICE");
+		let enclave_address: T::AccountId= get_account::<T>("ALICE_ENCLAVE");
+		let uri: BoundedVec<u8, T::MaxUriLen> = BoundedVec::try_from(vec![1; T::MaxUriLen::get() as usize]).unwrap();
+
+		let raw = (4 as sp_staking::EraIndex, Some(10u64)).encode();
+		let info = pallet_staking::ActiveEraInfo::decode(&mut &raw[..]).unwrap();
+		pallet_staking::ActiveEra::<T>::put(&info);
+
+		TEE::<T>::create_cluster(RawOrigin::Root.into(), ClusterType::Public).unwrap();
+		TEE::<T>::register_enclave(origin::<T>("ALICE").into(), enclave_address.clone(), uri.clone(), attestation::<T>(&enclave_address)).unwrap();
+		TEE::<T>::assign_enclave(RawOrigin::Root.into(), alice.clone(), 0, 0).unwrap();
+		MissedHeartbeats::<T>::insert(alice.clone(), 1);
+	}: _(origin::<T>("ALICE_ENCLAVE"))
+	verify {
+		assert_eq!(EnclaveHeartbeats::<T>::get(alice.clone()), Some(4));
+		assert_eq!(MissedHeartbeats::<T>::get(alice), 0);
+	}
+
+	set_allow_unverified_quotes {
+		let allow = true;
+	}: _(RawOrigin::Root, allow)
+	verify {
+		assert_eq!(AllowUnverifiedQuotes::<T>::get(), allow);
+	}
 }
 
 impl_benchmark_test_suite!(TEE, crate::tests::mock::new_test_ext(), crate::tests::mock::Test);
@@ -28,6 +28,7 @@ mod weights;
 
 use frame_support::{
 	dispatch::{DispatchResult, DispatchResultWithPostInfo},
+	ensure,
 	traits::ExistenceRequirement::AllowDeath,
 	BoundedVec, PalletId,
 };
@@ -35,19 +36,24 @@ use frame_support::{
 pub use pallet::*;
 pub use types::*;
 
-use frame_support::traits::{Get, LockIdentifier, StorageVersion};
-use sp_std::vec;
+use frame_support::traits::{
+	Currency, Get, LockIdentifier, LockableCurrency, OnUnbalanced, StorageVersion, WithdrawReasons,
+};
+use sp_std::{vec, vec::Vec};
 
+use parity_scale_codec::Encode;
 use primitives::tee::{ClusterId, SlotId};
+use sp_core::hashing::blake2_256;
 use sp_runtime::{
-	traits::{AccountIdConversion, CheckedSub, SaturatedConversion},
-	Perbill, Saturating,
+	traits::{AccountIdConversion, CheckedSub, SaturatedConversion, Zero},
+	DispatchError, Perbill, Saturating,
 };
 use ternoa_common::traits;
 pub use weights::WeightInfo;
 
-const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
 const TEE_STAKING_ID: LockIdentifier = *b"teestake";
+const TEE_NOMINATION_ID: LockIdentifier = *b"teenomin";
 use pallet_staking::Pallet as Staking;
 use sp_staking::EraIndex;
 
@@ -59,7 +65,7 @@ pub mod pallet {
 
 	use frame_support::{
 		pallet_prelude::*,
-		traits::{Currency, LockableCurrency, WithdrawReasons},
+		traits::{Currency, LockableCurrency, OnRuntimeUpgrade, WithdrawReasons},
 	};
 
 	#[pallet::pallet]
@@ -70,6 +76,10 @@ pub mod pallet {
 		<T as frame_system::Config>::AccountId,
 	>>::Balance;
 
+	pub type NegativeImbalanceOf<T> = <<T as pallet_staking::Config>::Currency as Currency<
+		<T as frame_system::Config>::AccountId,
+	>>::NegativeImbalance;
+
 	#[pallet::config]
 	pub trait Config: frame_system::Config + pallet_staking::Config {
 		/// Because this pallet emits events, it depends on the runtime's definition of an event.
@@ -110,6 +120,56 @@ pub mod pallet {
 		/// Number of eras to keep in history for the metrics report.
 		#[pallet::constant]
 		type TeeHistoryDepth: Get<u32>;
+
+		/// Max attestation quote length
+		#[pallet::constant]
+		type MaxQuoteLen: Get<u32>;
+
+		/// Verifier of the attestation quotes signature chain.
+		type AttestationVerifier: AttestationVerifier;
+
+		/// Handler for the slashed operators stake.
+		type Slash: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+		/// Number of blocks during which a reported slash can be cancelled.
+		#[pallet::constant]
+		type SlashDeferDuration: Get<u32>;
+
+		/// Maximum number of slashes applied in a block, the others are applied in the next
+		/// blocks. Checking a block without slashes left counts as one slash.
+		#[pallet::constant]
+		type MaxSlashesPerBlock: Get<u32>;
+
+		/// Maximum number of metrics that can be registered
+		#[pallet::constant]
+		type MaxMetrics: Get<u32>;
+
+		/// Max metric name length
+		#[pallet::constant]
+		type MaxMetricNameLen: Get<u32>;
+
+		/// Default number of reports needed for the reports of an operator to count.
+		#[pallet::constant]
+		type InitialReportQuorum: Get<u32>;
+
+		/// Max distance from the aggregated value before a report is seen as an outlier.
+		#[pallet::constant]
+		type OutlierThreshold: Get<u8>;
+
+		/// Maximum number of nominators backing an operator
+		#[pallet::constant]
+		type MaxNominators: Get<u32>;
+
+		/// Minimum amount a nominator has to bond
+		#[pallet::constant]
+		type MinNominationBond: Get<BalanceOf<Self>>;
+
+		/// Number of consecutive eras without heartbeat after which an enclave is inactive
+		#[pallet::constant]
+		type MaxMissedHeartbeats: Get<u32>;
+
+		/// Notified when enclaves leave, move or take a slot of a cluster
+		type EnclaveSlotHandler: traits::EnclaveSlotHandler;
 	}
 
 	/// Mapping of operator addresses who want to be registered as enclaves
@@ -210,14 +270,33 @@ pub mod pallet {
 		EraIndex,
 		Blake2_128Concat,
 		T::AccountId,
-		BoundedVec<MetricsServerReport<T::AccountId>, T::ListSizeLimit>,
+		BoundedVec<MetricsServerReport<T::AccountId, T::MaxMetrics>, T::ListSizeLimit>,
 		OptionQuery,
 	>;
 
-	/// Report params weightage
+	/// Metrics registered by governance that the metrics servers report on
+	#[pallet::storage]
+	#[pallet::getter(fn metrics)]
+	pub type Metrics<T: Config> =
+		StorageValue<_, BoundedVec<Metric<T::MaxMetricNameLen>, T::MaxMetrics>, ValueQuery>;
+
+	/// Number of reports needed for the reports of an operator to count in its rewards
 	#[pallet::storage]
-	#[pallet::getter(fn report_params_weightages)]
-	pub type ReportParamsWeightages<T: Config> = StorageValue<_, ReportParamsWeightage, ValueQuery>;
+	#[pallet::getter(fn report_quorum)]
+	pub type ReportQuorum<T: Config> = StorageValue<_, u32, ValueQuery, T::InitialReportQuorum>;
+
+	/// Number of operators for which a metrics server disagreed with the consensus, by era
+	#[pallet::storage]
+	#[pallet::getter(fn metrics_server_outliers)]
+	pub type MetricsServerOutliers<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		EraIndex,
+		Blake2_128Concat,
+		T::AccountId,
+		u32,
+		ValueQuery,
+	>;
 
 	/// Daily reward amount for TEE operator.
 	#[pallet::storage]
@@ -237,29 +316,199 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	/// Stake of the operators eligible to rewards, snapshotted at the end of each era
+	#[pallet::storage]
+	#[pallet::getter(fn eras_operator_stakes)]
+	pub type ErasOperatorStakes<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		EraIndex,
+		Blake2_128Concat,
+		T::AccountId,
+		BalanceOf<T>,
+		OptionQuery,
+	>;
+
+	/// Reward pool of each era, snapshotted at the end of the era
+	#[pallet::storage]
+	#[pallet::getter(fn eras_reward_pool)]
+	pub type ErasRewardPool<T: Config> =
+		StorageMap<_, Blake2_128Concat, EraIndex, BalanceOf<T>, OptionQuery>;
+
+	/// Stake weighted by the performance score of each operator of an era
+	#[pallet::storage]
+	#[pallet::getter(fn eras_reward_points)]
+	pub type ErasRewardPoints<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		EraIndex,
+		Blake2_128Concat,
+		T::AccountId,
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	/// Sum of the reward points of an era, computed once its reports are submitted
+	#[pallet::storage]
+	#[pallet::getter(fn eras_total_reward_points)]
+	pub type ErasTotalRewardPoints<T: Config> =
+		StorageMap<_, Blake2_128Concat, EraIndex, BalanceOf<T>, OptionQuery>;
+
+	/// Account receiving the rewards of an operator
+	#[pallet::storage]
+	#[pallet::getter(fn reward_destinations)]
+	pub type RewardDestinations<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, RewardDestination<T::AccountId>, ValueQuery>;
+
+	/// Nominators backing an operator along with their nominated amount
+	#[pallet::storage]
+	#[pallet::getter(fn nominators)]
+	pub type Nominators<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<(T::AccountId, BalanceOf<T>), T::MaxNominators>,
+		ValueQuery,
+	>;
+
+	/// Unbonding nominations of an operator, still slashable until they are withdrawn
+	#[pallet::storage]
+	#[pallet::getter(fn unbonding_nominators)]
+	pub type UnbondingNominators<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		BoundedVec<(T::AccountId, BalanceOf<T>), T::MaxNominators>,
+		ValueQuery,
+	>;
+
+	/// Nomination details mapped to nominator address
+	#[pallet::storage]
+	#[pallet::getter(fn nominator_ledgers)]
+	pub type NominatorLedgers<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		NominatorLedger<T::AccountId, T::BlockNumber, BalanceOf<T>>,
+		OptionQuery,
+	>;
+
+	/// Share of the nominators rewards kept by an operator
+	#[pallet::storage]
+	#[pallet::getter(fn commissions)]
+	pub type Commissions<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, Perbill, ValueQuery>;
+
+	/// Minimum total backing of an operator, own stake and nominations, to be assigned
+	#[pallet::storage]
+	#[pallet::getter(fn min_cluster_backing)]
+	pub type MinClusterBacking<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+	/// Nominators backing each operator of an era, snapshotted at the end of the era
+	#[pallet::storage]
+	#[pallet::getter(fn eras_nominators)]
+	pub type ErasNominators<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		EraIndex,
+		Blake2_128Concat,
+		T::AccountId,
+		NominatorsExposure<T::AccountId, BalanceOf<T>, T::MaxNominators>,
+		OptionQuery,
+	>;
+
+	/// Era of the last heartbeat sent by the enclave of each operator
+	#[pallet::storage]
+	#[pallet::getter(fn enclave_heartbeats)]
+	pub type EnclaveHeartbeats<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, EraIndex, OptionQuery>;
+
+	/// Number of consecutive eras the enclave of each operator missed its heartbeat
+	#[pallet::storage]
+	#[pallet::getter(fn missed_heartbeats)]
+	pub type MissedHeartbeats<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
 	#[pallet::storage]
 	#[pallet::getter(fn operator_assigned_block_number)]
 	pub type OperatorAssignedEra<T: Config> =
 		StorageMap<_, Blake2_128Concat, T::AccountId, EraIndex, OptionQuery>;
 
+	/// Allow-list of enclave measurements approved by governance
+	#[pallet::storage]
+	#[pallet::getter(fn approved_measurements)]
+	pub type ApprovedMeasurements<T: Config> =
+		StorageMap<_, Blake2_128Concat, MeasurementPolicy, (), OptionQuery>;
+
+	/// Whether attestation quotes the verifier cannot check are accepted, set by governance
+	#[pallet::storage]
+	#[pallet::getter(fn allow_unverified_quotes)]
+	pub type AllowUnverifiedQuotes<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// Mapping of operator addresses to the attested measurement of their enclave
+	#[pallet::storage]
+	#[pallet::getter(fn enclave_attestations)]
+	pub type EnclaveAttestations<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, EnclaveMeasurement, OptionQuery>;
+
+	/// Mapping of operator addresses to the attested measurement of their requested new enclave
+	#[pallet::storage]
+	#[pallet::getter(fn enclave_update_attestations)]
+	pub type EnclaveUpdateAttestations<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, EnclaveMeasurement, OptionQuery>;
+
+	/// Holds generated SlashIds
+	#[pallet::storage]
+	#[pallet::getter(fn next_slash_id)]
+	pub type NextSlashId<T: Config> = StorageValue<_, SlashId, ValueQuery>;
+
+	/// Mapping of slash ids to the slashes waiting for their cancellation window to end
+	#[pallet::storage]
+	#[pallet::getter(fn pending_slashes)]
+	pub type PendingSlashes<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		SlashId,
+		PendingSlash<T::AccountId, T::BlockNumber>,
+		OptionQuery,
+	>;
+
+	/// Slash ids indexed by the block number at which they get applied
+	#[pallet::storage]
+	#[pallet::getter(fn slash_schedule)]
+	pub type SlashSchedule<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::BlockNumber,
+		Blake2_128Concat,
+		SlashId,
+		(),
+		OptionQuery,
+	>;
+
+	/// First block of the slash schedule that may still have slashes to apply
+	#[pallet::storage]
+	#[pallet::getter(fn next_slash_block)]
+	pub type NextSlashBlock<T: Config> = StorageValue<_, T::BlockNumber, OptionQuery>;
+
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
-		// fn on_runtime_upgrade() -> frame_support::weights::Weight {
-		// 	let mut weight = Weight::zero();
+		fn on_runtime_upgrade() -> frame_support::weights::Weight {
+			let mut weight = Weight::zero();
 
-		// 	// let version = StorageVersion::get::<Pallet<T>>();
-		// 	// if version == StorageVersion::new(1) {
-		// 	// 	weight = <migrations::v2::MigrationV2<T> as OnRuntimeUpgrade>::on_runtime_upgrade();
+			let version = StorageVersion::get::<Pallet<T>>();
+			if version == StorageVersion::new(1) {
+				weight = <migrations::v2::MigrationV2<T> as OnRuntimeUpgrade>::on_runtime_upgrade();
 
-		// 	// 	StorageVersion::put::<Pallet<T>>(&StorageVersion::new(2));
-		// 	// }
+				StorageVersion::put::<Pallet<T>>(&StorageVersion::new(2));
+			}
 
-		// 	weight
-		// }
+			weight
+		}
 
 		fn on_initialize(now: T::BlockNumber) -> frame_support::weights::Weight {
 			let mut read = 0u64;
-			let write = 0u64;
+			let mut write = 0u64;
 
 			let current_active_era: Option<EraIndex> = match Staking::<T>::active_era() {
 				Some(era) => {
@@ -274,11 +523,71 @@ pub mod pallet {
 			};
 
 			if let Some(current_active_era) = current_active_era {
+				// Snapshot the operators of the era that just ended.
+				if let Some(ended_era) = current_active_era.checked_sub(1) {
+					read += 1;
+					if !ErasRewardPool::<T>::contains_key(ended_era) {
+						let operators = Self::snapshot_era(ended_era);
+						read += operators.saturating_mul(3).saturating_add(1);
+						write += operators.saturating_add(1);
+
+						let enclaves = Self::check_heartbeats(ended_era);
+						read += enclaves.saturating_mul(3);
+						write += enclaves;
+					}
+				}
+
+				// Score the operators of the era whose reports were submitted during the last era.
+				if let Some(scored_era) = current_active_era.checked_sub(2) {
+					read += 2;
+					if ErasRewardPool::<T>::contains_key(scored_era) &&
+						!ErasTotalRewardPoints::<T>::contains_key(scored_era)
+					{
+						let (operators, reports) = Self::compute_era_reward_points(scored_era);
+						read += 2 + operators.saturating_mul(3).saturating_add(reports);
+						write += operators.saturating_add(1);
+
+						let (reported, outliers) = Self::record_era_outliers(scored_era);
+						read += reported.saturating_add(outliers);
+						write += outliers;
+					}
+				}
+
 				// Clean old era information.
 				if let Some(old_era) = current_active_era.checked_sub(T::TeeHistoryDepth::get()) {
 					Self::clear_old_era(old_era);
 				}
 			}
+
+			// Apply the slashes whose cancellation window is over, starting with the ones left
+			// over by the previous blocks.
+			let mut budget = T::MaxSlashesPerBlock::get();
+			let mut block = NextSlashBlock::<T>::get().unwrap_or(now);
+			read += 1;
+			while budget > 0 && block <= now {
+				let slash_ids: Vec<SlashId> = SlashSchedule::<T>::drain_prefix(block)
+					.take(budget as usize)
+					.map(|(slash_id, _)| slash_id)
+					.collect();
+				let processed = slash_ids.len() as u32;
+				read += 1 + processed as u64;
+				write += processed as u64;
+				if processed < budget {
+					block = block.saturating_add(1u32.into());
+				}
+				budget = budget.saturating_sub(processed.max(1));
+
+				for slash_id in slash_ids {
+					if let Some(slash) = PendingSlashes::<T>::take(slash_id) {
+						let nominators = Self::apply_slash(slash);
+						read += nominators.saturating_mul(2).saturating_add(6);
+						write += nominators.saturating_mul(3).saturating_add(8);
+					}
+				}
+			}
+			NextSlashBlock::<T>::put(block);
+			write += 1;
+
 			T::DbWeight::get().reads_writes(read, write)
 		}
 	}
@@ -349,15 +658,72 @@ pub mod pallet {
 		MetricsServerReportSubmitted {
 			era: EraIndex,
 			operator_address: T::AccountId,
-			metrics_server_report: MetricsServerReport<T::AccountId>,
+			metrics_server_report: MetricsServerReport<T::AccountId, T::MaxMetrics>,
+		},
+		/// The set of metrics got replaced
+		MetricsSet { metrics: BoundedVec<Metric<T::MaxMetricNameLen>, T::MaxMetrics> },
+		/// Report quorum is set
+		ReportQuorumSet { quorum: u32 },
+		/// The reward destination of an operator got changed
+		RewardDestinationSet {
+			operator_address: T::AccountId,
+			destination: RewardDestination<T::AccountId>,
+		},
+		/// An account started backing an operator
+		Nominated { nominator: T::AccountId, operator_address: T::AccountId, amount: BalanceOf<T> },
+		/// A nominator stopped backing an operator
+		NominationUnbonded {
+			nominator: T::AccountId,
+			operator_address: T::AccountId,
+			amount: BalanceOf<T>,
+		},
+		/// Withdrawn the nominated amount
+		NominationWithdrawn { nominator: T::AccountId, amount: BalanceOf<T> },
+		/// The commission of an operator got changed
+		CommissionSet { operator_address: T::AccountId, commission: Perbill },
+		/// Minimum cluster backing is set
+		MinClusterBackingSet { amount: BalanceOf<T> },
+		/// A nominator got its share of the rewards of an operator
+		NominatorRewarded {
+			era: EraIndex,
+			operator_address: T::AccountId,
+			nominator: T::AccountId,
+			amount: BalanceOf<T>,
+		},
+		/// The share of a nominator could not be paid and stays in the pot
+		NominatorRewardSkipped {
+			era: EraIndex,
+			operator_address: T::AccountId,
+			nominator: T::AccountId,
+			amount: BalanceOf<T>,
+		},
+		/// A nominator got slashed along with the operator it backs
+		NominatorSlashed {
+			nominator: T::AccountId,
+			operator_address: T::AccountId,
+			amount: BalanceOf<T>,
+		},
+		/// An enclave got assigned to the least-full cluster of its type
+		EnclaveAutoAssigned {
+			operator_address: T::AccountId,
+			cluster_id: ClusterId,
+			slot_id: SlotId,
+		},
+		/// An enclave got moved to another slot of its cluster
+		EnclaveSlotChanged {
+			operator_address: T::AccountId,
+			cluster_id: ClusterId,
+			slot_id: SlotId,
 		},
-		/// Report parameters weightage modified
-		ReportParamsWeightageModified {
-			param_1_weightage: u8,
-			param_2_weightage: u8,
-			param_3_weightage: u8,
-			param_4_weightage: u8,
-			param_5_weightage: u8,
+		/// An enclave signaled it is alive
+		HeartbeatReceived { operator_address: T::AccountId, era: EraIndex },
+		/// An enclave missed too many heartbeats and is now inactive
+		EnclaveMarkedInactive { operator_address: T::AccountId },
+		/// A metrics server report disagreed with the other reports of an operator
+		MetricsServerOutlier {
+			era: EraIndex,
+			metrics_server_address: T::AccountId,
+			operator_address: T::AccountId,
 		},
 		/// Rewards claimed by operator
 		RewardsClaimed { era: EraIndex, operator_address: T::AccountId, amount: BalanceOf<T> },
@@ -373,6 +739,35 @@ pub mod pallet {
 		BondedExtra { operator_address: T::AccountId, amount: BalanceOf<T> },
 		/// Bonded extra to match default staking amount
 		RefundedExcess { operator_address: T::AccountId, amount: BalanceOf<T> },
+		/// An enclave measurement got approved
+		MeasurementApproved { policy: MeasurementPolicy },
+		/// An enclave measurement got revoked
+		MeasurementRevoked { policy: MeasurementPolicy },
+		/// Acceptance of attestation quotes the verifier cannot check got changed
+		AllowUnverifiedQuotesSet { allow: bool },
+		/// The attestation quote of an enclave could not be checked, the enclave is unattested
+		EnclaveAttestationUnverified {
+			operator_address: T::AccountId,
+			enclave_address: T::AccountId,
+		},
+		/// A slash got reported and will be applied at the given block
+		SlashReported {
+			slash_id: SlashId,
+			operator_address: T::AccountId,
+			fraction: Perbill,
+			reason: SlashReason,
+			apply_at: T::BlockNumber,
+		},
+		/// A deferred slash got cancelled
+		SlashCancelled { slash_id: SlashId },
+		/// An operator got slashed
+		OperatorSlashed {
+			operator_address: T::AccountId,
+			amount: BalanceOf<T>,
+			reason: SlashReason,
+		},
+		/// An enclave got unassigned from its cluster because its stake is too low
+		EnclaveUnassigned { operator_address: T::AccountId },
 	}
 
 	#[pallet::error]
@@ -441,6 +836,10 @@ pub mod pallet {
 		InvalidEraToClaimRewards,
 		/// Rewards already claimed for the era
 		RewardsAlreadyClaimedForEra,
+		/// The operator was not part of the era snapshot
+		NotEligibleForRewards,
+		/// The reward points of the era are not computed yet
+		EraRewardPointsNotComputed,
 		/// Insuffience Balance to Bond
 		InsufficientBalanceToBond,
 		/// Operator assigned era not found
@@ -455,6 +854,48 @@ pub mod pallet {
 		NoUpdatesProvided,
 		/// Registration not allowed while unbonding period
 		UnbondingPeriod,
+		/// The signature chain of the attestation quote could not be verified
+		InvalidAttestationQuote,
+		/// The measurement of the attestation quote is not the one provided
+		AttestationMeasurementMismatch,
+		/// The attestation quote was not produced for the given enclave address
+		AttestationNotBoundToEnclave,
+		/// The enclave measurement is not in the approved list
+		MeasurementNotApproved,
+		/// The measurement is already approved
+		MeasurementAlreadyApproved,
+		/// The measurement was not found in the approved list
+		MeasurementNotFound,
+		/// The slash was not found in the pending slashes
+		SlashNotFound,
+		/// The same metric id is used more than once
+		DuplicateMetric,
+		/// The metric is not registered
+		UnknownMetric,
+		/// The report must be submitted by the metrics server it belongs to
+		MetricsReportSubmitterMismatch,
+		/// The account already backs an operator
+		AlreadyNominating,
+		/// The nomination does not exist
+		NominationNotFound,
+		/// The nominated amount is lower than the minimum nomination bond
+		NominationBelowMinimum,
+		/// The maximum number of nominators of the operator has been reached
+		NominatorsLimitReached,
+		/// The maximum number of unbonding nominators of the operator has been reached
+		UnbondingNominatorsLimitReached,
+		/// The operator is not bonded
+		NominationTargetNotFound,
+		/// The nomination is already being unbonded
+		NominationAlreadyUnbonding,
+		/// The total backing of the operator is lower than the minimum cluster backing
+		InsufficientBacking,
+		/// The slot is already used by another enclave of the cluster
+		SlotIdAlreadyTaken,
+		/// No cluster of the requested type has a free slot
+		NoClusterAvailable,
+		/// The enclave already sent its heartbeat for the current era
+		HeartbeatAlreadySent,
 	}
 
 	#[pallet::call]
@@ -466,6 +907,7 @@ pub mod pallet {
 			origin: OriginFor<T>,
 			enclave_address: T::AccountId,
 			api_uri: BoundedVec<u8, T::MaxUriLen>,
+			attestation: AttestationQuote<T::MaxQuoteLen>,
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 
@@ -481,6 +923,7 @@ pub mod pallet {
 				Error::<T>::EnclaveAddressAlreadyExists
 			);
 			ensure!(StakingLedger::<T>::get(&who).is_none(), Error::<T>::UnbondingPeriod);
+			let measurement = Self::verify_attestation(&enclave_address, &attestation)?;
 			let default_staking_amount = StakingAmount::<T>::get();
 
 			let operator_balance = T::Currency::free_balance(&who);
@@ -511,6 +954,13 @@ pub mod pallet {
 
 			let enclave = Enclave::new(enclave_address.clone(), api_uri.clone());
 			EnclaveRegistrations::<T>::insert(who.clone(), enclave);
+			match measurement {
+				Some(measurement) => EnclaveAttestations::<T>::insert(who.clone(), measurement),
+				None => Self::deposit_event(Event::EnclaveAttestationUnverified {
+					operator_address: who.clone(),
+					enclave_address: enclave_address.clone(),
+				}),
+			}
 
 			Self::deposit_event(Event::EnclaveAddedForRegistration {
 				operator_address: who.clone(),
@@ -576,6 +1026,7 @@ pub mod pallet {
 						},
 					)?;
 					StakingLedger::<T>::remove(who.clone());
+					EnclaveAttestations::<T>::remove(&who);
 					T::Currency::remove_lock(TEE_STAKING_ID, &who);
 
 					Self::deposit_event(Event::RegistrationRemoved {
@@ -591,13 +1042,14 @@ pub mod pallet {
 			Ok(().into())
 		}
 
-		/// Ask for enclave update
+		/// Ask for enclave update, the attestation has to be bound to the new enclave address
 		#[pallet::call_index(2)]
 		#[pallet::weight(T::TeeWeightInfo::update_enclave())]
 		pub fn update_enclave(
 			origin: OriginFor<T>,
 			new_enclave_address: T::AccountId,
 			new_api_uri: BoundedVec<u8, T::MaxUriLen>,
+			attestation: AttestationQuote<T::MaxQuoteLen>,
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 
@@ -616,9 +1068,13 @@ pub mod pallet {
 					EnclaveAccountOperator::<T>::get(&new_enclave_address).is_none(),
 				Error::<T>::EnclaveAddressAlreadyExists
 			);
+			let measurement = Self::verify_attestation(&new_enclave_address, &attestation)?;
 
 			let enclave = Enclave::new(new_enclave_address.clone(), new_api_uri.clone());
 			EnclaveUpdates::<T>::insert(who.clone(), enclave);
+			if let Some(measurement) = measurement {
+				EnclaveUpdateAttestations::<T>::insert(who.clone(), measurement);
+			}
 
 			Self::deposit_event(Event::MovedForUpdate {
 				operator_address: who,
@@ -639,6 +1095,7 @@ pub mod pallet {
 				*maybe_update = None;
 				Ok(())
 			})?;
+			EnclaveUpdateAttestations::<T>::remove(&who);
 
 			Self::deposit_event(Event::UpdateRequestCancelled { operator_address: who });
 			Ok(().into())
@@ -655,64 +1112,7 @@ pub mod pallet {
 		) -> DispatchResultWithPostInfo {
 			ensure_root(origin)?;
 
-			EnclaveRegistrations::<T>::try_mutate(
-				&operator_address,
-				|maybe_registration| -> DispatchResult {
-					let registration =
-						maybe_registration.as_mut().ok_or(Error::<T>::RegistrationNotFound)?;
-
-					ClusterData::<T>::try_mutate(cluster_id, |maybe_cluster| -> DispatchResult {
-						let cluster = maybe_cluster.as_mut().ok_or(Error::<T>::ClusterNotFound)?;
-
-						ensure!(
-							cluster.enclaves.len() < T::ClusterSize::get() as usize,
-							Error::<T>::ClusterIsFull
-						);
-
-						ensure!(
-							EnclaveAccountOperator::<T>::get(&registration.enclave_address)
-								.is_none(),
-							Error::<T>::EnclaveAddressAlreadyExists
-						);
-						ensure!(
-							EnclaveData::<T>::get(&operator_address).is_none(),
-							Error::<T>::OperatorAlreadyExists
-						);
-
-						// Add enclave account to operator
-						EnclaveAccountOperator::<T>::insert(
-							registration.enclave_address.clone(),
-							operator_address.clone(),
-						);
-
-						// Add enclave data
-						EnclaveData::<T>::insert(operator_address.clone(), registration);
-
-						// Add enclave to cluster id
-						EnclaveClusterId::<T>::insert(operator_address.clone(), cluster_id);
-
-						let current_active_era = Staking::<T>::active_era()
-							.map(|e| e.index)
-							.ok_or(Error::<T>::FailedToGetActiveEra)?;
-
-						OperatorAssignedEra::<T>::insert(
-							operator_address.clone(),
-							current_active_era,
-						);
-
-						// Add enclave operator to cluster
-						cluster
-							.enclaves
-							.try_push((operator_address.clone(), slot_id))
-							.map_err(|_| Error::<T>::ClusterIsFull)?;
-
-						Ok(())
-					})?;
-
-					*maybe_registration = None;
-					Ok(())
-				},
-			)?;
+			Self::do_assign_enclave(&operator_address, cluster_id, slot_id)?;
 
 			Self::deposit_event(Event::EnclaveAssigned { operator_address, cluster_id });
 			Ok(().into())
@@ -733,6 +1133,7 @@ pub mod pallet {
 					let _ = maybe_registration.as_mut().ok_or(Error::<T>::RegistrationNotFound)?;
 					*maybe_registration = None;
 					StakingLedger::<T>::remove(&operator_address);
+					EnclaveAttestations::<T>::remove(&operator_address);
 					T::Currency::remove_lock(TEE_STAKING_ID, &operator_address);
 					Ok(())
 				},
@@ -756,6 +1157,7 @@ pub mod pallet {
 				*maybe_update = None;
 				Ok(())
 			})?;
+			EnclaveUpdateAttestations::<T>::remove(&operator_address);
 
 			Self::deposit_event(Event::UpdateRequestRemoved { operator_address });
 			Ok(().into())
@@ -805,14 +1207,10 @@ pub mod pallet {
 										Ok(())
 									},
 								)?;
+								EnclaveUpdateAttestations::<T>::remove(&operator_address);
 
 								// Remove the operator from cluster
-								if let Some(index) =
-									cluster.enclaves.iter().position(|(account_id, _slot_id)| {
-										*account_id == operator_address.clone()
-									}) {
-									cluster.enclaves.swap_remove(index);
-								}
+								Self::remove_from_cluster(cluster_id, cluster, &operator_address);
 
 								// Remove the mapping between operator to cluster id
 								EnclaveClusterId::<T>::remove(&operator_address);
@@ -823,6 +1221,13 @@ pub mod pallet {
 								// Remove the mapping between enclave address to operator address
 								EnclaveAccountOperator::<T>::remove(&enclave.enclave_address);
 
+								// Remove the attested measurement of the enclave
+								EnclaveAttestations::<T>::remove(&operator_address);
+
+								// Remove the liveness information of the enclave
+								EnclaveHeartbeats::<T>::remove(&operator_address);
+								MissedHeartbeats::<T>::remove(&operator_address);
+
 								Ok(())
 							},
 						)?;
@@ -884,15 +1289,10 @@ pub mod pallet {
 							Ok(())
 						},
 					)?;
+					EnclaveUpdateAttestations::<T>::remove(&operator_address);
 
 					// Remove the operator from cluster
-					if let Some(index) = cluster
-						.enclaves
-						.iter()
-						.position(|(account_id, _slot_id)| *account_id == operator_address.clone())
-					{
-						cluster.enclaves.swap_remove(index);
-					}
+					Self::remove_from_cluster(cluster_id, cluster, &operator_address);
 
 					// Remove the mapping between operator to cluster id
 					EnclaveClusterId::<T>::remove(&operator_address);
@@ -903,6 +1303,13 @@ pub mod pallet {
 					// Remove the mapping between enclave address to operator address
 					EnclaveAccountOperator::<T>::remove(&enclave.enclave_address);
 
+					// Remove the attested measurement of the enclave
+					EnclaveAttestations::<T>::remove(&operator_address);
+
+					// Remove the liveness information of the enclave
+					EnclaveHeartbeats::<T>::remove(&operator_address);
+					MissedHeartbeats::<T>::remove(&operator_address);
+
 					Ok(())
 				})?;
 
@@ -977,6 +1384,19 @@ pub mod pallet {
 
 				*maybe_update = None;
 
+				match EnclaveUpdateAttestations::<T>::take(&operator_address) {
+					Some(measurement) => {
+						EnclaveAttestations::<T>::insert(&operator_address, measurement)
+					},
+					None => {
+						EnclaveAttestations::<T>::remove(&operator_address);
+						Self::deposit_event(Event::EnclaveAttestationUnverified {
+							operator_address: operator_address.clone(),
+							enclave_address: new_enclave_address.clone(),
+						});
+					},
+				}
+
 				Self::deposit_event(Event::EnclaveUpdated {
 					operator_address: operator_address.clone(),
 					new_enclave_address,
@@ -1025,6 +1445,13 @@ pub mod pallet {
 							address.clone(),
 							operator_address.clone(),
 						);
+
+						// The new enclave was not attested
+						EnclaveAttestations::<T>::remove(&operator_address);
+						Self::deposit_event(Event::EnclaveAttestationUnverified {
+							operator_address: operator_address.clone(),
+							enclave_address: address.clone(),
+						});
 					}
 
 					enclave.enclave_address = address.clone();
@@ -1045,6 +1472,7 @@ pub mod pallet {
 				}
 				Ok(())
 			})?;
+			EnclaveUpdateAttestations::<T>::remove(&operator_address);
 
 			Self::deposit_event(Event::EnclaveForceUpdated {
 				operator_address,
@@ -1219,7 +1647,7 @@ pub mod pallet {
 		pub fn submit_metrics_server_report(
 			origin: OriginFor<T>,
 			operator_address: T::AccountId,
-			metrics_server_report: MetricsServerReport<T::AccountId>,
+			metrics_server_report: MetricsServerReport<T::AccountId, T::MaxMetrics>,
 		) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
 
@@ -1239,6 +1667,21 @@ pub mod pallet {
 			EnclaveData::<T>::get(&operator_address)
 				.ok_or(Error::<T>::EnclaveNotFoundForTheOperator)?;
 
+			ensure!(
+				metrics_server_report.submitted_by == who,
+				Error::<T>::MetricsReportSubmitterMismatch
+			);
+
+			let metrics = Metrics::<T>::get();
+			let values = &metrics_server_report.values;
+			for (index, (metric_id, _)) in values.iter().enumerate() {
+				ensure!(metrics.iter().any(|m| m.id == *metric_id), Error::<T>::UnknownMetric);
+				ensure!(
+					!values.iter().skip(index + 1).any(|(id, _)| id == metric_id),
+					Error::<T>::DuplicateMetric
+				);
+			}
+
 			// Retrieve the era index
 			let era_index = Staking::<T>::active_era()
 				.map(|e| e.index)
@@ -1262,8 +1705,10 @@ pub mod pallet {
 
 				MetricsReports::<T>::insert(&era_index, &operator_address, existing_reports);
 			} else {
-				let mut reports =
-					BoundedVec::<MetricsServerReport<T::AccountId>, T::ListSizeLimit>::default();
+				let mut reports = BoundedVec::<
+					MetricsServerReport<T::AccountId, T::MaxMetrics>,
+					T::ListSizeLimit,
+				>::default();
 				reports
 					.try_push(metrics_server_report.clone())
 					.map_err(|_| Error::<T>::MetricsReportsLimitReached)?;
@@ -1281,24 +1726,25 @@ pub mod pallet {
 			Ok(().into())
 		}
 
+		/// Replace the set of metrics reported by the metrics servers, by Technical Committee.
 		#[pallet::call_index(19)]
-		/// Report parameters weightage modification which can be done by Technical Committee.
-		#[pallet::weight(T::TeeWeightInfo::set_report_params_weightage())]
-		pub fn set_report_params_weightage(
+		#[pallet::weight(T::TeeWeightInfo::set_metrics())]
+		pub fn set_metrics(
 			origin: OriginFor<T>,
-			report_params_weightage: ReportParamsWeightage,
+			metrics: BoundedVec<Metric<T::MaxMetricNameLen>, T::MaxMetrics>,
 		) -> DispatchResultWithPostInfo {
 			ensure_root(origin)?;
 
-			ReportParamsWeightages::<T>::put(report_params_weightage.clone());
+			for (index, metric) in metrics.iter().enumerate() {
+				ensure!(
+					!metrics.iter().skip(index + 1).any(|m| m.id == metric.id),
+					Error::<T>::DuplicateMetric
+				);
+			}
+
+			Metrics::<T>::put(metrics.clone());
 
-			Self::deposit_event(Event::ReportParamsWeightageModified {
-				param_1_weightage: report_params_weightage.param_1_weightage,
-				param_2_weightage: report_params_weightage.param_2_weightage,
-				param_3_weightage: report_params_weightage.param_3_weightage,
-				param_4_weightage: report_params_weightage.param_4_weightage,
-				param_5_weightage: report_params_weightage.param_5_weightage,
-			});
+			Self::deposit_event(Event::MetricsSet { metrics });
 			Ok(().into())
 		}
 
@@ -1337,70 +1783,10 @@ pub mod pallet {
 		#[pallet::weight(T::TeeWeightInfo::claim_rewards())]
 		pub fn claim_rewards(origin: OriginFor<T>, era: EraIndex) -> DispatchResultWithPostInfo {
 			let who = ensure_signed(origin)?;
+			Self::do_claim_rewards(&who, era)?;
 
-			ensure!(
-				ClaimedRewards::<T>::get(&era, &who).is_none(),
-				Error::<T>::RewardsAlreadyClaimedForEra
-			);
-
-			let current_active_era = Staking::<T>::active_era()
-				.map(|e| e.index)
-				.ok_or(Error::<T>::FailedToGetActiveEra)?;
-
-			let operator_assigned_era = OperatorAssignedEra::<T>::get(&who)
-				.ok_or(Error::<T>::OperatorAssignedEraNotFound)?;
-
-			ensure!(
-				era < current_active_era.saturating_sub(2) &&
-					era > current_active_era.saturating_sub(T::TeeHistoryDepth::get()) &&
-					era >= operator_assigned_era,
-				Error::<T>::InvalidEraToClaimRewards
-			);
-
-			EnclaveData::<T>::get(&who).ok_or(Error::<T>::EnclaveNotFoundForTheOperator)?;
-
-			let total_operators = EnclaveData::<T>::iter_keys().count();
-			let share_fraction = Perbill::from_rational(1, total_operators as u32);
-			let reward_pool = Self::daily_reward_pool();
-
-			let reward_per_operator: BalanceOf<T> = share_fraction * reward_pool;
-
-			let submitted_metrics_report = MetricsReports::<T>::get(&era, &who);
-
-			if let Some(submitted_metrics_report) = submitted_metrics_report {
-				let variance = Self::calculate_highest_params(&submitted_metrics_report);
-
-				let report_params_weightage = Self::report_params_weightages();
-
-				let weighted_sum =
-					Self::calculate_weighted_sum(&variance, &report_params_weightage);
-				let percent = Perbill::from_rational(weighted_sum, 10000 as u32);
-
-				let weighted_reward_amount = percent * reward_per_operator;
-
-				T::Currency::transfer(
-					&Self::account_id(),
-					&who,
-					weighted_reward_amount,
-					AllowDeath,
-				)?;
-				ClaimedRewards::<T>::insert(era, who.clone(), weighted_reward_amount.clone());
-				Self::deposit_event(Event::RewardsClaimed {
-					era,
-					operator_address: who.clone(),
-					amount: weighted_reward_amount,
-				});
-			} else {
-				T::Currency::transfer(&Self::account_id(), &who, reward_per_operator, AllowDeath)?;
-				ClaimedRewards::<T>::insert(era, who.clone(), reward_per_operator.clone());
-				Self::deposit_event(Event::RewardsClaimed {
-					era,
-					operator_address: who.clone(),
-					amount: reward_per_operator,
-				});
-			}
-			Ok(().into())
-		}
+			Ok(().into())
+		}
 
 		// Updates assigned era for an operator
 		#[pallet::call_index(23)]
@@ -1517,11 +1903,506 @@ pub mod pallet {
 			Ok(().into())
 		}
 
+		/// Add a measurement to the allow-list of approved enclaves
+		#[pallet::call_index(26)]
+		#[pallet::weight(T::TeeWeightInfo::approve_measurement())]
+		pub fn approve_measurement(
+			origin: OriginFor<T>,
+			policy: MeasurementPolicy,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+
+			ensure!(
+				ApprovedMeasurements::<T>::get(&policy).is_none(),
+				Error::<T>::MeasurementAlreadyApproved
+			);
+			ApprovedMeasurements::<T>::insert(policy.clone(), ());
+
+			Self::deposit_event(Event::MeasurementApproved { policy });
+			Ok(().into())
+		}
+
+		/// Remove a measurement from the allow-list of approved enclaves
+		#[pallet::call_index(27)]
+		#[pallet::weight(T::TeeWeightInfo::revoke_measurement())]
+		pub fn revoke_measurement(
+			origin: OriginFor<T>,
+			policy: MeasurementPolicy,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+
+			ApprovedMeasurements::<T>::try_mutate(&policy, |maybe_policy| -> DispatchResult {
+				let _ = maybe_policy.as_mut().ok_or(Error::<T>::MeasurementNotFound)?;
+				*maybe_policy = None;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::MeasurementRevoked { policy });
+			Ok(().into())
+		}
+
+		/// Report a slash of an operator stake, by governance or by a metrics server.
+		/// The slash is applied once the cancellation window is over.
+		#[pallet::call_index(28)]
+		#[pallet::weight(T::TeeWeightInfo::slash_operator())]
+		pub fn slash_operator(
+			origin: OriginFor<T>,
+			operator_address: T::AccountId,
+			fraction: Perbill,
+			reason: SlashReason,
+		) -> DispatchResultWithPostInfo {
+			if let Some(who) = frame_system::ensure_signed_or_root(origin)? {
+				ensure!(
+					MetricsServers::<T>::get()
+						.iter()
+						.any(|server| server.metrics_server_address == who),
+					Error::<T>::MetricsServerAddressNotFound
+				);
+			}
+			ensure!(
+				StakingLedger::<T>::get(&operator_address).is_some(),
+				Error::<T>::StakingNotFound
+			);
+
+			let now = frame_system::Pallet::<T>::block_number();
+			let defer_duration = T::SlashDeferDuration::get();
+			let apply_at = now.saturating_add(defer_duration.into());
+			let slash_id = Self::get_next_slash_id();
+			let slash =
+				PendingSlash::new(operator_address.clone(), fraction, reason.clone(), apply_at);
+
+			Self::deposit_event(Event::SlashReported {
+				slash_id,
+				operator_address,
+				fraction,
+				reason,
+				apply_at,
+			});
+
+			if defer_duration == 0 {
+				Self::apply_slash(slash);
+			} else {
+				PendingSlashes::<T>::insert(slash_id, slash);
+				SlashSchedule::<T>::insert(apply_at, slash_id, ());
+			}
+
+			Ok(().into())
+		}
+
+		/// Cancel a slash during its cancellation window
+		#[pallet::call_index(29)]
+		#[pallet::weight(T::TeeWeightInfo::cancel_slash())]
+		pub fn cancel_slash(origin: OriginFor<T>, slash_id: SlashId) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+
+			let slash = PendingSlashes::<T>::take(slash_id).ok_or(Error::<T>::SlashNotFound)?;
+			SlashSchedule::<T>::remove(slash.apply_at, slash_id);
+
+			Self::deposit_event(Event::SlashCancelled { slash_id });
+			Ok(().into())
+		}
+
+		/// Set the number of reports needed for the reports of an operator to count, by
+		/// Technical Committee
+		#[pallet::call_index(30)]
+		#[pallet::weight(T::TeeWeightInfo::set_report_quorum())]
+		pub fn set_report_quorum(origin: OriginFor<T>, quorum: u32) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+
+			ReportQuorum::<T>::put(quorum);
+
+			Self::deposit_event(Event::ReportQuorumSet { quorum });
+			Ok(().into())
+		}
+
+		/// Set the account receiving the rewards of the operator
+		#[pallet::call_index(31)]
+		#[pallet::weight(T::TeeWeightInfo::set_reward_destination())]
+		pub fn set_reward_destination(
+			origin: OriginFor<T>,
+			destination: RewardDestination<T::AccountId>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(StakingLedger::<T>::contains_key(&who), Error::<T>::StakingNotFound);
+
+			RewardDestinations::<T>::insert(&who, destination.clone());
+
+			Self::deposit_event(Event::RewardDestinationSet { operator_address: who, destination });
+			Ok(().into())
+		}
+
+		/// Claim rewards for several eras at once
+		#[pallet::call_index(32)]
+		#[pallet::weight(T::TeeWeightInfo::claim_rewards().saturating_mul(eras.len() as u64))]
+		pub fn claim_rewards_for_eras(
+			origin: OriginFor<T>,
+			eras: BoundedVec<EraIndex, T::TeeHistoryDepth>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			for era in eras {
+				Self::do_claim_rewards(&who, era)?;
+			}
+
+			Ok(().into())
+		}
+
+		/// Pay the rewards of an operator for an era, can be called by anyone
+		#[pallet::call_index(33)]
+		#[pallet::weight(T::TeeWeightInfo::payout_operator())]
+		pub fn payout_operator(
+			origin: OriginFor<T>,
+			operator_address: T::AccountId,
+			era: EraIndex,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+
+			Self::do_claim_rewards(&operator_address, era)?;
+
+			Ok(().into())
+		}
+
+		/// Back an operator with the given amount
+		#[pallet::call_index(34)]
+		#[pallet::weight(T::TeeWeightInfo::nominate())]
+		pub fn nominate(
+			origin: OriginFor<T>,
+			operator_address: T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(NominatorLedgers::<T>::get(&who).is_none(), Error::<T>::AlreadyNominating);
+			ensure!(amount >= T::MinNominationBond::get(), Error::<T>::NominationBelowMinimum);
+			let ledger = StakingLedger::<T>::get(&operator_address)
+				.ok_or(Error::<T>::NominationTargetNotFound)?;
+			ensure!(!ledger.is_unlocking, Error::<T>::NominationTargetNotFound);
+
+			let nominator_balance = T::Currency::free_balance(&who);
+			let new_nominator_balance = nominator_balance
+				.checked_sub(&amount)
+				.ok_or(Error::<T>::InsufficientBalanceToBond)?;
+			T::Currency::ensure_can_withdraw(
+				&who,
+				amount,
+				WithdrawReasons::all(),
+				new_nominator_balance,
+			)?;
+
+			Nominators::<T>::try_mutate(&operator_address, |nominators| {
+				nominators
+					.try_push((who.clone(), amount))
+					.map_err(|_| Error::<T>::NominatorsLimitReached)
+			})?;
+			let nomination =
+				NominatorLedger::new(operator_address.clone(), amount, false, Default::default());
+			NominatorLedgers::<T>::insert(&who, nomination);
+			T::Currency::set_lock(TEE_NOMINATION_ID, &who, amount, WithdrawReasons::all());
+
+			Self::deposit_event(Event::Nominated { nominator: who, operator_address, amount });
+			Ok(().into())
+		}
+
+		/// Stop backing the operator, the nominated amount can be withdrawn after the bonding
+		/// duration
+		#[pallet::call_index(35)]
+		#[pallet::weight(T::TeeWeightInfo::unbond_nomination())]
+		pub fn unbond_nomination(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let nomination = NominatorLedgers::<T>::try_mutate(
+				&who,
+				|maybe_nomination| -> Result<_, DispatchError> {
+					let nomination =
+						maybe_nomination.as_mut().ok_or(Error::<T>::NominationNotFound)?;
+					ensure!(!nomination.is_unlocking, Error::<T>::NominationAlreadyUnbonding);
+					nomination.is_unlocking = true;
+					nomination.unbonded_at = frame_system::Pallet::<T>::block_number();
+					Ok(nomination.clone())
+				},
+			)?;
+			Nominators::<T>::mutate(&nomination.operator, |nominators| {
+				nominators.retain(|(nominator, _)| nominator != &who)
+			});
+			// The nomination stays slashable until it is withdrawn.
+			UnbondingNominators::<T>::try_mutate(&nomination.operator, |nominators| {
+				nominators
+					.try_push((who.clone(), nomination.amount))
+					.map_err(|_| Error::<T>::UnbondingNominatorsLimitReached)
+			})?;
+
+			Self::deposit_event(Event::NominationUnbonded {
+				nominator: who,
+				operator_address: nomination.operator,
+				amount: nomination.amount,
+			});
+			Ok(().into())
+		}
+
+		/// Withdraw the unbonded nomination
+		#[pallet::call_index(36)]
+		#[pallet::weight(T::TeeWeightInfo::withdraw_nomination())]
+		pub fn withdraw_nomination(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let nomination =
+				NominatorLedgers::<T>::get(&who).ok_or(Error::<T>::NominationNotFound)?;
+			ensure!(nomination.is_unlocking, Error::<T>::UnbondingNotStarted);
+			let now = frame_system::Pallet::<T>::block_number();
+			let duration: u32 = (now - nomination.unbonded_at).saturated_into();
+			ensure!(duration >= T::TeeBondingDuration::get(), Error::<T>::WithdrawProhibited);
+
+			T::Currency::remove_lock(TEE_NOMINATION_ID, &who);
+			NominatorLedgers::<T>::remove(&who);
+			UnbondingNominators::<T>::mutate(&nomination.operator, |nominators| {
+				nominators.retain(|(nominator, _)| nominator != &who)
+			});
+
+			Self::deposit_event(Event::NominationWithdrawn {
+				nominator: who,
+				amount: nomination.amount,
+			});
+			Ok(().into())
+		}
+
+		/// Set the share of the nominators rewards kept by the operator
+		#[pallet::call_index(37)]
+		#[pallet::weight(T::TeeWeightInfo::set_commission())]
+		pub fn set_commission(
+			origin: OriginFor<T>,
+			commission: Perbill,
+		) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			ensure!(StakingLedger::<T>::contains_key(&who), Error::<T>::StakingNotFound);
+
+			Commissions::<T>::insert(&who, commission);
+
+			Self::deposit_event(Event::CommissionSet { operator_address: who, commission });
+			Ok(().into())
+		}
+
+		/// Set the minimum total backing needed to assign an enclave, by Technical Committee
+		#[pallet::call_index(38)]
+		#[pallet::weight(T::TeeWeightInfo::set_min_cluster_backing())]
+		pub fn set_min_cluster_backing(
+			origin: OriginFor<T>,
+			amount: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+
+			MinClusterBacking::<T>::put(amount);
+
+			Self::deposit_event(Event::MinClusterBackingSet { amount });
+			Ok(().into())
+		}
+
+		/// Assign an enclave to the least-full cluster of the given type, in its lowest free slot
+		#[pallet::call_index(39)]
+		#[pallet::weight(T::TeeWeightInfo::auto_assign_enclave())]
+		pub fn auto_assign_enclave(
+			origin: OriginFor<T>,
+			operator_address: T::AccountId,
+			cluster_type: ClusterType,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+
+			let (cluster_id, slot_id) =
+				Self::least_full_cluster(&cluster_type).ok_or(Error::<T>::NoClusterAvailable)?;
+			Self::do_assign_enclave(&operator_address, cluster_id, slot_id)?;
+
+			Self::deposit_event(Event::EnclaveAutoAssigned {
+				operator_address,
+				cluster_id,
+				slot_id,
+			});
+			Ok(().into())
+		}
+
+		/// Signal that an enclave is alive, once per era. Free for assigned enclaves
+		#[pallet::call_index(40)]
+		#[pallet::weight(T::TeeWeightInfo::heartbeat())]
+		pub fn heartbeat(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			let operator_address =
+				EnclaveAccountOperator::<T>::get(&who).ok_or(Error::<T>::EnclaveAddressNotFound)?;
+			ensure!(
+				EnclaveClusterId::<T>::contains_key(&operator_address),
+				Error::<T>::EnclaveNotFound
+			);
+
+			let era = Staking::<T>::active_era()
+				.map(|e| e.index)
+				.ok_or(Error::<T>::FailedToGetActiveEra)?;
+			ensure!(
+				EnclaveHeartbeats::<T>::get(&operator_address) != Some(era),
+				Error::<T>::HeartbeatAlreadySent
+			);
+
+			EnclaveHeartbeats::<T>::insert(&operator_address, era);
+			MissedHeartbeats::<T>::remove(&operator_address);
+
+			Self::deposit_event(Event::HeartbeatReceived { operator_address, era });
+			Ok(Pays::No.into())
+		}
+
+		/// Set whether attestation quotes the verifier cannot check are accepted, by Technical
+		/// Committee
+		#[pallet::call_index(41)]
+		#[pallet::weight(T::TeeWeightInfo::set_allow_unverified_quotes())]
+		pub fn set_allow_unverified_quotes(
+			origin: OriginFor<T>,
+			allow: bool,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+
+			AllowUnverifiedQuotes::<T>::put(allow);
+
+			Self::deposit_event(Event::AllowUnverifiedQuotesSet { allow });
+			Ok(().into())
+		}
 	}
 }
 
 // Helper Methods for Storage
 impl<T: Config> Pallet<T> {
+	/// Assign the registered enclave of an operator to the given slot of a cluster.
+	fn do_assign_enclave(
+		operator_address: &T::AccountId,
+		cluster_id: ClusterId,
+		slot_id: SlotId,
+	) -> DispatchResult {
+		ensure!(
+			Self::total_backing(operator_address) >= MinClusterBacking::<T>::get(),
+			Error::<T>::InsufficientBacking
+		);
+
+		EnclaveRegistrations::<T>::try_mutate(
+			operator_address,
+			|maybe_registration| -> DispatchResult {
+				let registration =
+					maybe_registration.as_mut().ok_or(Error::<T>::RegistrationNotFound)?;
+
+				ClusterData::<T>::try_mutate(cluster_id, |maybe_cluster| -> DispatchResult {
+					let cluster = maybe_cluster.as_mut().ok_or(Error::<T>::ClusterNotFound)?;
+
+					ensure!(
+						cluster.enclaves.len() < T::ClusterSize::get() as usize,
+						Error::<T>::ClusterIsFull
+					);
+
+					ensure!(
+						EnclaveAccountOperator::<T>::get(&registration.enclave_address).is_none(),
+						Error::<T>::EnclaveAddressAlreadyExists
+					);
+					ensure!(
+						EnclaveData::<T>::get(operator_address).is_none(),
+						Error::<T>::OperatorAlreadyExists
+					);
+					ensure!(
+						!cluster.enclaves.iter().any(|(_, x)| *x == slot_id),
+						Error::<T>::SlotIdAlreadyTaken
+					);
+
+					// Add enclave account to operator
+					EnclaveAccountOperator::<T>::insert(
+						registration.enclave_address.clone(),
+						operator_address.clone(),
+					);
+
+					// Add enclave data
+					EnclaveData::<T>::insert(operator_address.clone(), registration);
+
+					// Add enclave to cluster id
+					EnclaveClusterId::<T>::insert(operator_address.clone(), cluster_id);
+
+					let current_active_era = Staking::<T>::active_era()
+						.map(|e| e.index)
+						.ok_or(Error::<T>::FailedToGetActiveEra)?;
+
+					OperatorAssignedEra::<T>::insert(operator_address.clone(), current_active_era);
+
+					// Add enclave operator to cluster
+					cluster
+						.enclaves
+						.try_push((operator_address.clone(), slot_id))
+						.map_err(|_| Error::<T>::ClusterIsFull)?;
+
+					Ok(())
+				})?;
+
+				*maybe_registration = None;
+				Ok(())
+			},
+		)?;
+
+		T::EnclaveSlotHandler::on_slot_filled(cluster_id, slot_id);
+		Ok(())
+	}
+
+	/// Find the least-full cluster of the given type that still has room, and the lowest free
+	/// slot in it. Ties are broken by the lowest cluster id.
+	fn least_full_cluster(cluster_type: &ClusterType) -> Option<(ClusterId, SlotId)> {
+		ClusterData::<T>::iter()
+			.filter(|(_, cluster)| {
+				cluster.cluster_type == *cluster_type &&
+					cluster.enclaves.len() < T::ClusterSize::get() as usize
+			})
+			.min_by_key(|(cluster_id, cluster)| (cluster.enclaves.len(), *cluster_id))
+			.map(|(cluster_id, cluster)| (cluster_id, cluster.free_slot()))
+	}
+
+	/// Remove the enclave of an operator from its cluster, then move the enclaves that are past
+	/// the end of the slot range into the holes left, and notify them of their new slot.
+	fn remove_from_cluster(
+		cluster_id: ClusterId,
+		cluster: &mut Cluster<T::AccountId, T::ClusterSize>,
+		operator_address: &T::AccountId,
+	) {
+		if let Some(index) = cluster
+			.enclaves
+			.iter()
+			.position(|(account_id, _slot_id)| account_id == operator_address)
+		{
+			let (_, slot_id) = cluster.enclaves.swap_remove(index);
+			T::EnclaveSlotHandler::on_slot_vacated(cluster_id, slot_id);
+		}
+
+		for (operator_address, from, slot_id) in cluster.fill_slot_holes() {
+			T::EnclaveSlotHandler::on_slot_moved(cluster_id, from, slot_id);
+			Self::deposit_event(Event::EnclaveSlotChanged {
+				operator_address,
+				cluster_id,
+				slot_id,
+			});
+		}
+	}
+
+	/// Count a missed heartbeat for every assigned enclave that did not send one during the era.
+	/// Returns the number of enclaves checked.
+	pub fn check_heartbeats(era: EraIndex) -> u64 {
+		let max_missed = T::MaxMissedHeartbeats::get();
+		let mut count = 0u64;
+		for (operator_address, _) in EnclaveClusterId::<T>::iter() {
+			count += 1;
+			if EnclaveHeartbeats::<T>::get(&operator_address) == Some(era) {
+				continue
+			}
+
+			let missed = MissedHeartbeats::<T>::mutate(&operator_address, |missed| {
+				*missed = missed.saturating_add(1);
+				*missed
+			});
+			if missed == max_missed {
+				Self::deposit_event(Event::EnclaveMarkedInactive { operator_address });
+			}
+		}
+
+		count
+	}
+
+	/// Whether the enclave of an operator sent a heartbeat recently enough.
+	pub fn is_enclave_active(operator_address: &T::AccountId) -> bool {
+		MissedHeartbeats::<T>::get(operator_address) < T::MaxMissedHeartbeats::get()
+	}
+
 	/// Increment the cluster id generator and return the id
 	fn get_next_cluster_id() -> ClusterId {
 		let id = NextClusterId::<T>::get();
@@ -1533,41 +2414,461 @@ impl<T: Config> Pallet<T> {
 		id
 	}
 
+	/// Increment the slash id generator and return the id
+	fn get_next_slash_id() -> SlashId {
+		let id = NextSlashId::<T>::get();
+		let next_id = id
+			.checked_add(1)
+			.expect("If u32 is not enough we should crash for safety; qed.");
+		NextSlashId::<T>::put(next_id);
+
+		id
+	}
+
 	/// The account ID of the tee pot.
 	pub fn account_id() -> T::AccountId {
 		T::PalletId::get().into_account_truncating()
 	}
 
-	pub fn calculate_highest_params(
-		enclave_reports: &BoundedVec<MetricsServerReport<T::AccountId>, T::ListSizeLimit>,
-	) -> HighestParamsResponse {
-		let mut highest_params =
-			HighestParamsResponse { param_1: 0, param_2: 0, param_3: 0, param_4: 0, param_5: 0 };
+	/// Aggregate the reports of every registered metric with its rule. Metrics without any
+	/// reported value are left out.
+	pub fn aggregate_reports(
+		enclave_reports: &BoundedVec<
+			MetricsServerReport<T::AccountId, T::MaxMetrics>,
+			T::ListSizeLimit,
+		>,
+	) -> Vec<(Metric<T::MaxMetricNameLen>, u8)> {
+		Metrics::<T>::get()
+			.into_iter()
+			.filter_map(|metric| {
+				let values = enclave_reports.iter().filter_map(|r| r.value(metric.id)).collect();
+				let value = metric.aggregation.aggregate(values)?;
+				Some((metric, value))
+			})
+			.collect()
+	}
+
+	/// Sum the aggregated metrics according to their weightage.
+	pub fn calculate_weighted_sum(aggregated: &[(Metric<T::MaxMetricNameLen>, u8)]) -> u32 {
+		aggregated.iter().fold(0u32, |sum, (metric, value)| {
+			sum.saturating_add((*value as u32).saturating_mul(metric.weight as u32))
+		})
+	}
+
+	/// Pay the rewards of an operator for an era into its reward destination.
+	fn do_claim_rewards(operator_address: &T::AccountId, era: EraIndex) -> DispatchResult {
+		ensure!(
+			ClaimedRewards::<T>::get(&era, operator_address).is_none(),
+			Error::<T>::RewardsAlreadyClaimedForEra
+		);
+
+		let current_active_era = Staking::<T>::active_era()
+			.map(|e| e.index)
+			.ok_or(Error::<T>::FailedToGetActiveEra)?;
+
+		let operator_assigned_era = OperatorAssignedEra::<T>::get(operator_address)
+			.ok_or(Error::<T>::OperatorAssignedEraNotFound)?;
+
+		ensure!(
+			era < current_active_era.saturating_sub(2) &&
+				era > current_active_era.saturating_sub(T::TeeHistoryDepth::get()) &&
+				era >= operator_assigned_era,
+			Error::<T>::InvalidEraToClaimRewards
+		);
+
+		// Eras ended before the era snapshots keep the rewards of the time.
+		let reward_pool = match ErasRewardPool::<T>::get(&era) {
+			Some(reward_pool) => reward_pool,
+			None => return Self::do_claim_legacy_rewards(operator_address, era),
+		};
+
+		let total_stake = ErasOperatorStakes::<T>::get(&era, operator_address)
+			.ok_or(Error::<T>::NotEligibleForRewards)?;
+
+		let total_points =
+			ErasTotalRewardPoints::<T>::get(&era).ok_or(Error::<T>::EraRewardPointsNotComputed)?;
+		let points = ErasRewardPoints::<T>::get(&era, operator_address);
+
+		let reward_amount: BalanceOf<T> = if total_points.is_zero() {
+			Zero::zero()
+		} else {
+			Perbill::from_rational(points, total_points) * reward_pool
+		};
+
+		// Nominators get their share of the rewards minus the commission of the operator.
+		let mut operator_reward = reward_amount;
+		if let Some(exposure) = ErasNominators::<T>::get(&era, operator_address) {
+			for (nominator, stake) in exposure.nominators {
+				let share = Perbill::from_rational(stake, total_stake) * reward_amount;
+				let amount = share.saturating_sub(exposure.commission * share);
+				operator_reward = operator_reward.saturating_sub(amount);
+
+				// A share that cannot be paid does not prevent the others from being paid.
+				let operator_address = operator_address.clone();
+				match T::Currency::transfer(&Self::account_id(), &nominator, amount, AllowDeath) {
+					Ok(()) => Self::deposit_event(Event::NominatorRewarded {
+						era,
+						operator_address,
+						nominator,
+						amount,
+					}),
+					Err(_) => Self::deposit_event(Event::NominatorRewardSkipped {
+						era,
+						operator_address,
+						nominator,
+						amount,
+					}),
+				}
+			}
+		}
+
+		Self::pay_operator_reward(operator_address, era, operator_reward)
+	}
+
+	/// Pay the rewards of an operator for an era ended before the era snapshots. The reward pool
+	/// is shared equally between the operators and weighted by their reports.
+	fn do_claim_legacy_rewards(operator_address: &T::AccountId, era: EraIndex) -> DispatchResult {
+		EnclaveData::<T>::get(operator_address).ok_or(Error::<T>::EnclaveNotFoundForTheOperator)?;
+
+		let total_operators = EnclaveData::<T>::iter_keys().count();
+		let share_fraction = Perbill::from_rational(1, total_operators as u32);
+		let reward_per_operator: BalanceOf<T> = share_fraction * DailyRewardPool::<T>::get();
+
+		let reports = MetricsReports::<T>::get(&era, operator_address)
+			.filter(|reports| reports.len() as u32 >= ReportQuorum::<T>::get());
+		let reward_amount = match reports {
+			Some(reports) => {
+				let aggregated = Self::aggregate_reports(&reports);
+				let weighted_sum = Self::calculate_weighted_sum(&aggregated);
+				Perbill::from_rational(weighted_sum, 10000) * reward_per_operator
+			},
+			None => reward_per_operator,
+		};
+
+		Self::pay_operator_reward(operator_address, era, reward_amount)
+	}
+
+	/// Pay the reward of an operator for an era into its reward destination.
+	fn pay_operator_reward(
+		operator_address: &T::AccountId,
+		era: EraIndex,
+		amount: BalanceOf<T>,
+	) -> DispatchResult {
+		let destination = match RewardDestinations::<T>::get(operator_address) {
+			RewardDestination::Operator => operator_address.clone(),
+			RewardDestination::Account(account) => account,
+		};
+
+		T::Currency::transfer(&Self::account_id(), &destination, amount, AllowDeath)?;
+		ClaimedRewards::<T>::insert(era, operator_address.clone(), amount);
+		Self::deposit_event(Event::RewardsClaimed {
+			era,
+			operator_address: operator_address.clone(),
+			amount,
+		});
+
+		Ok(())
+	}
+
+	/// Sum of the amounts nominated to an operator.
+	fn nominated_amount(
+		nominators: &BoundedVec<(T::AccountId, BalanceOf<T>), T::MaxNominators>,
+	) -> BalanceOf<T> {
+		nominators
+			.iter()
+			.fold(Zero::zero(), |sum: BalanceOf<T>, (_, amount)| sum.saturating_add(*amount))
+	}
+
+	/// Own stake of an operator along with the amounts nominated to it.
+	fn total_backing(operator_address: &T::AccountId) -> BalanceOf<T> {
+		let own_stake = StakingLedger::<T>::get(operator_address)
+			.map_or_else(Zero::zero, |ledger| ledger.staked_amount);
+		own_stake.saturating_add(Self::nominated_amount(&Nominators::<T>::get(operator_address)))
+	}
+
+	/// Snapshot the stake of the operators eligible to the rewards of an era along with its
+	/// reward pool. Returns the number of eligible operators.
+	fn snapshot_era(era: EraIndex) -> u64 {
+		let mut operators = 0u64;
+		for operator in EnclaveData::<T>::iter_keys() {
+			let assigned = OperatorAssignedEra::<T>::get(&operator).map_or(false, |e| e <= era);
+			match StakingLedger::<T>::get(&operator) {
+				Some(ledger) if assigned && !ledger.is_unlocking => {
+					let nominators = Nominators::<T>::get(&operator);
+					let nominated = Self::nominated_amount(&nominators);
+					let stake = ledger.staked_amount.saturating_add(nominated);
+					ErasOperatorStakes::<T>::insert(era, &operator, stake);
+					if !nominators.is_empty() {
+						let commission = Commissions::<T>::get(&operator);
+						let exposure = NominatorsExposure::new(commission, nominators);
+						ErasNominators::<T>::insert(era, &operator, exposure);
+					}
+					operators += 1;
+				},
+				_ => {},
+			}
+		}
+		ErasRewardPool::<T>::insert(era, DailyRewardPool::<T>::get());
+
+		operators
+	}
+
+	/// Weight the stake of every operator of the era by its performance score and store the
+	/// total. Returns the number of scored operators and of reports read.
+	fn compute_era_reward_points(era: EraIndex) -> (u64, u64) {
+		let quorum = ReportQuorum::<T>::get();
+		let mut total_points: BalanceOf<T> = Zero::zero();
+		let mut operators = 0u64;
+		let mut report_count = 0u64;
+		for (operator, stake) in ErasOperatorStakes::<T>::iter_prefix(era) {
+			let reports = MetricsReports::<T>::get(&era, &operator);
+			report_count += reports.as_ref().map_or(0, |reports| reports.len() as u64);
+			let reports = reports.filter(|reports| reports.len() as u32 >= quorum);
+
+			let points = Self::performance_score(reports) * stake;
+			ErasRewardPoints::<T>::insert(era, &operator, points);
+			total_points = total_points.saturating_add(points);
+			operators += 1;
+		}
+		ErasTotalRewardPoints::<T>::insert(era, total_points);
+
+		(operators, report_count)
+	}
 
+	/// Performance score of an operator for an era from its reports reaching the quorum.
+	/// Operators without enough reports get no score, their share goes to the performing
+	/// operators.
+	fn performance_score(
+		reports: Option<
+			BoundedVec<MetricsServerReport<T::AccountId, T::MaxMetrics>, T::ListSizeLimit>,
+		>,
+	) -> Perbill {
+		match reports {
+			Some(reports) => {
+				let aggregated = Self::aggregate_reports(&reports);
+				Perbill::from_rational(Self::calculate_weighted_sum(&aggregated), 10000)
+			},
+			None => Perbill::zero(),
+		}
+	}
+
+	/// Record the outliers of an era for every operator whose reports reach the quorum, whether
+	/// it is eligible to the rewards or not. Returns the number of operators with reports and of
+	/// recorded outliers.
+	fn record_era_outliers(era: EraIndex) -> (u64, u64) {
+		let quorum = ReportQuorum::<T>::get();
+		let mut operators = 0u64;
+		let mut outliers = 0u64;
+		for (operator, reports) in MetricsReports::<T>::iter_prefix(era) {
+			if reports.len() as u32 >= quorum {
+				let aggregated = Self::aggregate_reports(&reports);
+				outliers += Self::record_outliers(era, &operator, &reports, &aggregated);
+			}
+			operators += 1;
+		}
+
+		(operators, outliers)
+	}
+
+	/// Record the metrics servers having reported a value too far from the aggregated one.
+	/// Returns the number of outliers.
+	pub fn record_outliers(
+		era: EraIndex,
+		operator_address: &T::AccountId,
+		enclave_reports: &BoundedVec<
+			MetricsServerReport<T::AccountId, T::MaxMetrics>,
+			T::ListSizeLimit,
+		>,
+		aggregated: &[(Metric<T::MaxMetricNameLen>, u8)],
+	) -> u64 {
+		let threshold = T::OutlierThreshold::get();
+		let mut outliers = 0u64;
 		for report in enclave_reports.iter() {
-			highest_params.param_1 = highest_params.param_1.max(report.param_1);
-			highest_params.param_2 = highest_params.param_2.max(report.param_2);
-			highest_params.param_3 = highest_params.param_3.max(report.param_3);
-			highest_params.param_4 = highest_params.param_4.max(report.param_4);
-			highest_params.param_5 = highest_params.param_5.max(report.param_5);
+			let is_outlier = aggregated.iter().any(|(metric, value)| {
+				report.value(metric.id).map_or(false, |v| v.abs_diff(*value) > threshold)
+			});
+			if is_outlier {
+				MetricsServerOutliers::<T>::mutate(era, &report.submitted_by, |count| {
+					*count = count.saturating_add(1)
+				});
+				Self::deposit_event(Event::MetricsServerOutlier {
+					era,
+					metrics_server_address: report.submitted_by.clone(),
+					operator_address: operator_address.clone(),
+				});
+				outliers += 1;
+			}
+		}
+
+		outliers
+	}
+
+	/// Verify that an attestation quote is genuine, bound to the enclave address and that the
+	/// enclave runs an approved measurement. Quotes the verifier cannot check are rejected unless
+	/// governance allows unverified quotes, in which case `None` is returned and only the claimed
+	/// measurement is checked against the allow-list.
+	pub fn verify_attestation(
+		enclave_address: &T::AccountId,
+		attestation: &AttestationQuote<T::MaxQuoteLen>,
+	) -> Result<Option<EnclaveMeasurement>, DispatchError> {
+		let verified =
+			match T::AttestationVerifier::verify_quote(&attestation.kind, &attestation.quote) {
+				QuoteVerification::Verified(verified) => Some(verified),
+				QuoteVerification::Invalid => {
+					return Err(Error::<T>::InvalidAttestationQuote.into())
+				},
+				QuoteVerification::Unsupported => {
+					ensure!(AllowUnverifiedQuotes::<T>::get(), Error::<T>::InvalidAttestationQuote);
+					None
+				},
+			};
+
+		if let Some(verified) = &verified {
+			ensure!(
+				verified.measurement == attestation.measurement,
+				Error::<T>::AttestationMeasurementMismatch
+			);
+			ensure!(
+				verified.report_data == blake2_256(&enclave_address.encode()),
+				Error::<T>::AttestationNotBoundToEnclave
+			);
+		}
+
+		let measurement = attestation.measurement;
+		let by_enclave = MeasurementPolicy::MrEnclave(measurement.mr_enclave);
+		let by_signer = MeasurementPolicy::MrSigner(measurement.mr_signer);
+		ensure!(
+			ApprovedMeasurements::<T>::contains_key(by_enclave) ||
+				ApprovedMeasurements::<T>::contains_key(by_signer),
+			Error::<T>::MeasurementNotApproved
+		);
+
+		Ok(verified.map(|_| measurement))
+	}
+
+	/// Slash an operator and its nominators. Returns the number of slashed nominators.
+	fn apply_slash(slash: PendingSlash<T::AccountId, T::BlockNumber>) -> u64 {
+		let operator_address = slash.operator_address;
+		let mut ledger = match StakingLedger::<T>::get(&operator_address) {
+			Some(ledger) => ledger,
+			None => return 0,
+		};
+
+		let amount = slash.fraction * ledger.staked_amount;
+		let (imbalance, missing) = T::Currency::slash(&operator_address, amount);
+		T::Slash::on_unbalanced(imbalance);
+		let slashed_amount = amount.saturating_sub(missing);
+
+		ledger.staked_amount = ledger.staked_amount.saturating_sub(slashed_amount);
+		if ledger.staked_amount.is_zero() {
+			T::Currency::remove_lock(TEE_STAKING_ID, &operator_address);
+		} else {
+			T::Currency::set_lock(
+				TEE_STAKING_ID,
+				&operator_address,
+				ledger.staked_amount,
+				WithdrawReasons::all(),
+			);
+		}
+		let is_unlocking = ledger.is_unlocking;
+		let staked_amount = ledger.staked_amount;
+		StakingLedger::<T>::insert(&operator_address, ledger);
+
+		Self::deposit_event(Event::OperatorSlashed {
+			operator_address: operator_address.clone(),
+			amount: slashed_amount,
+			reason: slash.reason,
+		});
+
+		// The nominators are slashed by the same fraction, unbonding ones included.
+		let mut nominators_count = 0u64;
+		Nominators::<T>::mutate(&operator_address, |nominators| {
+			nominators_count += nominators.len() as u64;
+			Self::slash_nominators(&operator_address, slash.fraction, nominators)
+		});
+		UnbondingNominators::<T>::mutate(&operator_address, |nominators| {
+			nominators_count += nominators.len() as u64;
+			Self::slash_nominators(&operator_address, slash.fraction, nominators)
+		});
+
+		if staked_amount < StakingAmount::<T>::get() ||
+			Self::total_backing(&operator_address) < MinClusterBacking::<T>::get()
+		{
+			Self::unassign_enclave(&operator_address, !is_unlocking);
 		}
 
-		highest_params
+		nominators_count
 	}
 
-	pub fn calculate_weighted_sum(
-		variances: &HighestParamsResponse,
-		weightages: &ReportParamsWeightage,
-	) -> u32 {
-		// Calculate the weighted sum for each index
-		let weighted_sum: u32 = (variances.param_1 as u32)
-			.saturating_mul(weightages.param_1_weightage as u32) +
-			(variances.param_2 as u32).saturating_mul(weightages.param_2_weightage as u32) +
-			(variances.param_3 as u32).saturating_mul(weightages.param_3_weightage as u32) +
-			(variances.param_4 as u32).saturating_mul(weightages.param_4_weightage as u32) +
-			(variances.param_5 as u32).saturating_mul(weightages.param_5_weightage as u32);
-
-		weighted_sum
+	/// Slash the nominations of an operator by a fraction.
+	fn slash_nominators(
+		operator_address: &T::AccountId,
+		fraction: Perbill,
+		nominators: &mut BoundedVec<(T::AccountId, BalanceOf<T>), T::MaxNominators>,
+	) {
+		for (nominator, nominated) in nominators.iter_mut() {
+			let amount = fraction * *nominated;
+			let (imbalance, missing) = T::Currency::slash(nominator, amount);
+			T::Slash::on_unbalanced(imbalance);
+			let slashed_amount = amount.saturating_sub(missing);
+
+			*nominated = nominated.saturating_sub(slashed_amount);
+			NominatorLedgers::<T>::mutate(&*nominator, |maybe_nomination| {
+				if let Some(nomination) = maybe_nomination {
+					nomination.amount = *nominated;
+				}
+			});
+			if nominated.is_zero() {
+				T::Currency::remove_lock(TEE_NOMINATION_ID, nominator);
+			} else {
+				T::Currency::set_lock(
+					TEE_NOMINATION_ID,
+					nominator,
+					*nominated,
+					WithdrawReasons::all(),
+				);
+			}
+
+			Self::deposit_event(Event::NominatorSlashed {
+				nominator: nominator.clone(),
+				operator_address: operator_address.clone(),
+				amount: slashed_amount,
+			});
+		}
+	}
+
+	/// Remove an enclave from its cluster. The enclave goes back to the registration list so that
+	/// it can be assigned again once the operator bonded enough, unless it is being unregistered.
+	fn unassign_enclave(operator_address: &T::AccountId, keep_registration: bool) {
+		let enclave = match EnclaveData::<T>::take(operator_address) {
+			Some(enclave) => enclave,
+			None => return,
+		};
+
+		if let Some(cluster_id) = EnclaveClusterId::<T>::take(operator_address) {
+			ClusterData::<T>::mutate(cluster_id, |maybe_cluster| {
+				if let Some(cluster) = maybe_cluster {
+					Self::remove_from_cluster(cluster_id, cluster, operator_address);
+				}
+			});
+		}
+		OperatorAssignedEra::<T>::remove(operator_address);
+		EnclaveAccountOperator::<T>::remove(&enclave.enclave_address);
+		EnclaveUpdates::<T>::remove(operator_address);
+		EnclaveUpdateAttestations::<T>::remove(operator_address);
+		EnclaveHeartbeats::<T>::remove(operator_address);
+		MissedHeartbeats::<T>::remove(operator_address);
+		EnclaveUnregistrations::<T>::mutate(|x| x.retain(|x| x != operator_address));
+
+		if keep_registration {
+			EnclaveRegistrations::<T>::insert(operator_address, enclave);
+		} else {
+			EnclaveAttestations::<T>::remove(operator_address);
+		}
+
+		Self::deposit_event(Event::EnclaveUnassigned {
+			operator_address: operator_address.clone(),
+		});
 	}
 
 	fn clear_old_era(old_era: EraIndex) {
@@ -1576,6 +2877,21 @@ impl<T: Config> Pallet<T> {
 
 		cursor = MetricsReports::<T>::clear_prefix(old_era, u32::MAX, None);
 		debug_assert!(cursor.maybe_cursor.is_none());
+
+		cursor = MetricsServerOutliers::<T>::clear_prefix(old_era, u32::MAX, None);
+		debug_assert!(cursor.maybe_cursor.is_none());
+
+		cursor = ErasOperatorStakes::<T>::clear_prefix(old_era, u32::MAX, None);
+		debug_assert!(cursor.maybe_cursor.is_none());
+
+		cursor = ErasRewardPoints::<T>::clear_prefix(old_era, u32::MAX, None);
+		debug_assert!(cursor.maybe_cursor.is_none());
+
+		cursor = ErasNominators::<T>::clear_prefix(old_era, u32::MAX, None);
+		debug_assert!(cursor.maybe_cursor.is_none());
+
+		ErasRewardPool::<T>::remove(old_era);
+		ErasTotalRewardPoints::<T>::remove(old_era);
 	}
 
 }
@@ -1595,6 +2911,19 @@ impl<T: Config> traits::TEEExt for Pallet<T> {
 		result
 	}
 
+	fn is_enclave_active(operator_address: &Self::AccountId) -> bool {
+		Self::is_enclave_active(operator_address)
+	}
+
+	fn enclave_slot(operator_address: &Self::AccountId) -> Option<SlotId> {
+		let cluster_id = EnclaveClusterId::<T>::get(operator_address)?;
+		ClusterData::<T>::get(cluster_id)?
+			.enclaves
+			.iter()
+			.find(|(account_id, _slot_id)| account_id == operator_address)
+			.map(|(_account_id, slot_id)| *slot_id)
+	}
+
 	/// Register and assign an enclave in a cluster
 	fn register_and_assign_enclave(
 		operator_address: Self::AccountId,
@@ -16,11 +16,26 @@
 
 use super::{mock, mock::*};
 use crate::{
-	Cluster, ClusterData, ClusterType, Enclave, EnclaveAccountOperator, EnclaveClusterId,
-	EnclaveData, EnclaveRegistrations, EnclaveUpdates, Error, Event as TEEEvent, TeeStakingLedger,
+	AggregationRule, AllowUnverifiedQuotes, ApprovedMeasurements, AttestationQuote, ClaimedRewards,
+	Cluster, ClusterData, ClusterType, Commissions, Enclave, EnclaveAccountOperator,
+	EnclaveAttestations, EnclaveClusterId, EnclaveData, EnclaveHeartbeats, EnclaveMeasurement,
+	EnclaveRegistrations, EnclaveUpdateAttestations, EnclaveUpdates, ErasOperatorStakes,
+	ErasRewardPoints, ErasRewardPool, ErasTotalRewardPoints, Error, Event as TEEEvent,
+	MeasurementPolicy, Metric, MetricId, Metrics, MetricsReports, MetricsServer,
+	MetricsServerOutliers, MetricsServerReport, MinClusterBacking, MissedHeartbeats,
+	NextSlashBlock, NominatorLedger, NominatorLedgers, Nominators, PendingSlash, PendingSlashes,
+	ReportQuorum, RewardDestination, RewardDestinations, SlashReason, SlashSchedule, TeeKind,
+	TeeStakingLedger, UnbondingNominators,
+};
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::Pays,
+	error::BadOrigin,
+	traits::{Currency, Get, Hooks},
+	BoundedVec,
 };
-use frame_support::{assert_noop, assert_ok, error::BadOrigin, BoundedVec};
 use frame_system::RawOrigin;
+use sp_runtime::{Perbill, Percent};
 
 fn origin(account: u64) -> mock::RuntimeOrigin {
 	RawOrigin::Signed(account).into()
@@ -29,6 +44,39 @@ fn root() -> mock::RuntimeOrigin {
 	RawOrigin::Root.into()
 }
 
+/// Quotes of the CHARLIE enclave in the mock verifier format, signed by the mock root of trust.
+/// They are not DCAP quotes and do not exercise any vendor certificate chain.
+const QUOTE_VALID: &[u8] = include_bytes!("fixtures/mock_quote_valid.bin");
+const QUOTE_BAD_SIGNATURE: &[u8] = include_bytes!("fixtures/mock_quote_bad_signature.bin");
+const QUOTE_UNAPPROVED: &[u8] = include_bytes!("fixtures/mock_quote_unapproved.bin");
+const UNAPPROVED_MEASUREMENT: EnclaveMeasurement =
+	EnclaveMeasurement { mr_enclave: [3; 32], mr_signer: [4; 32] };
+
+fn prepare_assigned_enclave() {
+	let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
+	start_active_era(1);
+
+	assert_ok!(TEE::register_enclave(origin(ALICE), CHARLIE, api_uri, attestation(CHARLIE)));
+	assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
+	assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
+}
+
+fn set_active_era(index: sp_staking::EraIndex) {
+	pallet_staking::ActiveEra::<Test>::put(pallet_staking::ActiveEraInfo { index, start: None });
+}
+
+fn metric(id: MetricId, weight: u8, aggregation: AggregationRule) -> Metric<MaxMetricNameLen> {
+	Metric::new(id, b"metric".to_vec().try_into().unwrap(), weight, aggregation)
+}
+
+fn report(values: Vec<(MetricId, u8)>, submitted_by: u64) -> MetricsServerReport<u64, MaxMetrics> {
+	MetricsServerReport::new(values.try_into().unwrap(), submitted_by)
+}
+
+fn fixture(quote: &[u8], measurement: EnclaveMeasurement) -> AttestationQuote<MaxQuoteLen> {
+	AttestationQuote::new(TeeKind::Sgx, measurement, quote.to_vec().try_into().unwrap())
+}
+
 mod register_enclave {
 	use super::*;
 
@@ -41,10 +89,16 @@ mod register_enclave {
 				let alice: mock::RuntimeOrigin = origin(ALICE);
 				let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
 
-				assert_ok!(TEE::register_enclave(alice.clone(), CHARLIE, api_uri.clone()));
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
 
 				let expected = Enclave::new(CHARLIE, api_uri.clone());
 				assert_eq!(EnclaveRegistrations::<Test>::get(ALICE), Some(expected));
+				assert_eq!(EnclaveAttestations::<Test>::get(ALICE), Some(APPROVED_MEASUREMENT));
 				assert!(EnclaveData::<Test>::get(ALICE).is_none());
 				assert!(EnclaveAccountOperator::<Test>::get(ALICE).is_none());
 
@@ -62,6 +116,171 @@ mod register_enclave {
 			})
 	}
 
+	#[test]
+	fn register_enclave_with_fixture_quote() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				let alice: mock::RuntimeOrigin = origin(ALICE);
+				let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
+				let attestation = fixture(QUOTE_VALID, APPROVED_MEASUREMENT);
+
+				assert_ok!(TEE::register_enclave(alice, CHARLIE, api_uri, attestation));
+				assert_eq!(EnclaveAttestations::<Test>::get(ALICE), Some(APPROVED_MEASUREMENT));
+			})
+	}
+
+	#[test]
+	fn register_enclave_with_unsupported_quote() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				let alice: mock::RuntimeOrigin = origin(ALICE);
+				let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
+				let attestation = AttestationQuote::new(
+					TeeKind::SevSnp,
+					APPROVED_MEASUREMENT,
+					b"quote".to_vec().try_into().unwrap(),
+				);
+				assert_ok!(TEE::set_allow_unverified_quotes(root(), true));
+
+				assert_ok!(TEE::register_enclave(alice, CHARLIE, api_uri, attestation));
+
+				let expected = Enclave::new(CHARLIE, b"test".to_vec().try_into().unwrap());
+				assert_eq!(EnclaveRegistrations::<Test>::get(ALICE), Some(expected));
+				assert!(EnclaveAttestations::<Test>::get(ALICE).is_none());
+				let event = RuntimeEvent::TEE(TEEEvent::EnclaveAttestationUnverified {
+					operator_address: ALICE,
+					enclave_address: CHARLIE,
+				});
+				System::assert_has_event(event);
+			})
+	}
+
+	#[test]
+	fn unsupported_quote_not_allowed() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				let alice: mock::RuntimeOrigin = origin(ALICE);
+				let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
+				let attestation = AttestationQuote::new(
+					TeeKind::SevSnp,
+					APPROVED_MEASUREMENT,
+					b"quote".to_vec().try_into().unwrap(),
+				);
+
+				assert_noop!(
+					TEE::register_enclave(alice, CHARLIE, api_uri, attestation),
+					Error::<Test>::InvalidAttestationQuote
+				);
+			})
+	}
+
+	#[test]
+	fn unsupported_quote_with_unapproved_measurement() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				let alice: mock::RuntimeOrigin = origin(ALICE);
+				let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
+				let attestation = AttestationQuote::new(
+					TeeKind::SevSnp,
+					UNAPPROVED_MEASUREMENT,
+					b"quote".to_vec().try_into().unwrap(),
+				);
+				assert_ok!(TEE::set_allow_unverified_quotes(root(), true));
+
+				assert_noop!(
+					TEE::register_enclave(alice, CHARLIE, api_uri, attestation),
+					Error::<Test>::MeasurementNotApproved
+				);
+			})
+	}
+
+	#[test]
+	fn invalid_attestation_quote() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				let alice: mock::RuntimeOrigin = origin(ALICE);
+				let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
+				let attestation = fixture(QUOTE_BAD_SIGNATURE, APPROVED_MEASUREMENT);
+
+				assert_noop!(
+					TEE::register_enclave(alice, CHARLIE, api_uri, attestation),
+					Error::<Test>::InvalidAttestationQuote
+				);
+			})
+	}
+
+	#[test]
+	fn attestation_measurement_mismatch() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				let alice: mock::RuntimeOrigin = origin(ALICE);
+				let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
+				let attestation = fixture(QUOTE_VALID, UNAPPROVED_MEASUREMENT);
+
+				assert_noop!(
+					TEE::register_enclave(alice, CHARLIE, api_uri, attestation),
+					Error::<Test>::AttestationMeasurementMismatch
+				);
+			})
+	}
+
+	#[test]
+	fn attestation_not_bound_to_enclave() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (DAVE, 100)])
+			.build()
+			.execute_with(|| {
+				let alice: mock::RuntimeOrigin = origin(ALICE);
+				let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
+				let attestation = fixture(QUOTE_VALID, APPROVED_MEASUREMENT);
+
+				assert_noop!(
+					TEE::register_enclave(alice, DAVE, api_uri, attestation),
+					Error::<Test>::AttestationNotBoundToEnclave
+				);
+			})
+	}
+
+	#[test]
+	fn measurement_not_approved() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				let alice: mock::RuntimeOrigin = origin(ALICE);
+				let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
+				let attestation = fixture(QUOTE_UNAPPROVED, UNAPPROVED_MEASUREMENT);
+
+				assert_noop!(
+					TEE::register_enclave(
+						alice.clone(),
+						CHARLIE,
+						api_uri.clone(),
+						attestation.clone()
+					),
+					Error::<Test>::MeasurementNotApproved
+				);
+
+				// Approving the signer allows any enclave it signed.
+				let policy = MeasurementPolicy::MrSigner(UNAPPROVED_MEASUREMENT.mr_signer);
+				assert_ok!(TEE::approve_measurement(root(), policy));
+				assert_ok!(TEE::register_enclave(alice, CHARLIE, api_uri, attestation));
+				assert_eq!(EnclaveAttestations::<Test>::get(ALICE), Some(UNAPPROVED_MEASUREMENT));
+			})
+	}
+
 	#[test]
 	fn operator_and_enclave_are_the_same() {
 		ExtBuilder::default()
@@ -72,7 +291,7 @@ mod register_enclave {
 				let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
 
 				assert_noop!(
-					TEE::register_enclave(alice.clone(), ALICE, api_uri),
+					TEE::register_enclave(alice.clone(), ALICE, api_uri, attestation(ALICE)),
 					Error::<Test>::OperatorAndEnclaveAreSame
 				);
 			})
@@ -87,10 +306,15 @@ mod register_enclave {
 				let alice: mock::RuntimeOrigin = origin(ALICE);
 				let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
 
-				assert_ok!(TEE::register_enclave(alice.clone(), CHARLIE, api_uri.clone()));
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
 
 				assert_noop!(
-					TEE::register_enclave(alice.clone(), CHARLIE, api_uri),
+					TEE::register_enclave(alice.clone(), CHARLIE, api_uri, attestation(CHARLIE)),
 					Error::<Test>::RegistrationAlreadyExists
 				);
 			})
@@ -106,12 +330,22 @@ mod register_enclave {
 				let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
 				start_active_era(1);
 
-				assert_ok!(TEE::register_enclave(alice.clone(), CHARLIE, api_uri.clone()));
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
 				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
 				assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
 
 				assert_noop!(
-					TEE::register_enclave(alice.clone(), CHARLIE, api_uri.clone()),
+					TEE::register_enclave(
+						alice.clone(),
+						CHARLIE,
+						api_uri.clone(),
+						attestation(CHARLIE)
+					),
 					Error::<Test>::OperatorAlreadyExists
 				);
 			})
@@ -130,8 +364,18 @@ mod register_enclave {
 
 				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
 
-				assert_ok!(TEE::register_enclave(alice.clone(), CHARLIE, api_uri.clone()));
-				assert_ok!(TEE::register_enclave(bob.clone(), CHARLIE, api_uri.clone()));
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
+				assert_ok!(TEE::register_enclave(
+					bob.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
 
 				assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
 				assert_noop!(
@@ -154,10 +398,16 @@ mod unregister_enclave {
 				let alice: mock::RuntimeOrigin = origin(ALICE);
 				let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
 
-				assert_ok!(TEE::register_enclave(alice.clone(), CHARLIE, api_uri.clone()));
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
 				assert_ok!(TEE::unregister_enclave(alice.clone()));
 
 				assert!(EnclaveRegistrations::<Test>::get(ALICE).is_none());
+				assert!(EnclaveAttestations::<Test>::get(ALICE).is_none());
 
 				let event =
 					RuntimeEvent::TEE(TEEEvent::Withdrawn { operator_address: ALICE, amount: 20 });
@@ -175,7 +425,12 @@ mod unregister_enclave {
 				let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
 				start_active_era(1);
 
-				assert_ok!(TEE::register_enclave(alice.clone(), CHARLIE, api_uri.clone()));
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
 				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
 				assert_ok!(TEE::assign_enclave(root(), ALICE.clone(), 0, 0));
 
@@ -213,15 +468,29 @@ mod update_enclave {
 					"new_api_uri".as_bytes().to_vec().try_into().unwrap();
 				start_active_era(1);
 
-				assert_ok!(TEE::register_enclave(alice.clone(), CHARLIE, api_uri.clone()));
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
 				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
 				assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
 
-				assert_ok!(TEE::update_enclave(alice.clone(), BOB, new_api_uri.clone()));
+				assert_ok!(TEE::update_enclave(
+					alice.clone(),
+					BOB,
+					new_api_uri.clone(),
+					attestation(BOB)
+				));
 
 				let updated_enclave = Enclave::new(BOB, new_api_uri.clone());
 
 				assert_eq!(EnclaveUpdates::<Test>::get(ALICE).unwrap(), updated_enclave);
+				assert_eq!(
+					EnclaveUpdateAttestations::<Test>::get(ALICE),
+					Some(APPROVED_MEASUREMENT)
+				);
 
 				let event = RuntimeEvent::TEE(TEEEvent::MovedForUpdate {
 					operator_address: ALICE,
@@ -243,7 +512,12 @@ mod update_enclave {
 					"new_api_uri".as_bytes().to_vec().try_into().unwrap();
 
 				assert_noop!(
-					TEE::update_enclave(alice.clone(), ALICE, new_api_uri.clone()),
+					TEE::update_enclave(
+						alice.clone(),
+						ALICE,
+						new_api_uri.clone(),
+						attestation(ALICE)
+					),
 					Error::<Test>::OperatorAndEnclaveAreSame
 				);
 			})
@@ -260,12 +534,22 @@ mod update_enclave {
 					"new_api_uri".as_bytes().to_vec().try_into().unwrap();
 				start_active_era(1);
 
-				assert_ok!(TEE::register_enclave(alice.clone(), CHARLIE, new_api_uri.clone()));
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					CHARLIE,
+					new_api_uri.clone(),
+					attestation(CHARLIE)
+				));
 				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
 				assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
-				assert_ok!(TEE::update_enclave(alice.clone(), BOB, new_api_uri.clone()));
+				assert_ok!(TEE::update_enclave(
+					alice.clone(),
+					BOB,
+					new_api_uri.clone(),
+					attestation(BOB)
+				));
 
-				let err = TEE::update_enclave(alice.clone(), BOB, new_api_uri);
+				let err = TEE::update_enclave(alice.clone(), BOB, new_api_uri, attestation(BOB));
 				assert_noop!(err, Error::<Test>::UpdateRequestAlreadyExists);
 			})
 	}
@@ -281,7 +565,7 @@ mod update_enclave {
 					"new_api_uri".as_bytes().to_vec().try_into().unwrap();
 
 				assert_noop!(
-					TEE::update_enclave(alice.clone(), BOB, new_api_uri.clone()),
+					TEE::update_enclave(alice.clone(), BOB, new_api_uri.clone(), attestation(BOB)),
 					Error::<Test>::UpdateProhibitedForUnassignedEnclave
 				);
 			})
@@ -300,18 +584,55 @@ mod update_enclave {
 
 				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
 
-				assert_ok!(TEE::register_enclave(alice.clone(), CHARLIE, api_uri.clone()));
-				assert_ok!(TEE::register_enclave(eve.clone(), DAVE, api_uri.clone()));
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
+				assert_ok!(TEE::register_enclave(
+					eve.clone(),
+					DAVE,
+					api_uri.clone(),
+					attestation(DAVE)
+				));
 
 				assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
-				assert_ok!(TEE::assign_enclave(root(), EVE.clone(), 0, 0));
+				assert_ok!(TEE::assign_enclave(root(), EVE.clone(), 0, 1));
 
 				assert_noop!(
-					TEE::update_enclave(alice.clone(), DAVE, api_uri.clone()),
+					TEE::update_enclave(alice.clone(), DAVE, api_uri.clone(), attestation(DAVE)),
 					Error::<Test>::EnclaveAddressAlreadyExists
 				);
 			})
 	}
+
+	#[test]
+	fn attestation_not_bound_to_enclave() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				let alice: mock::RuntimeOrigin = origin(ALICE);
+				let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
+				start_active_era(1);
+
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
+				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
+				assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
+
+				// The quote of the current enclave cannot be reused for the new one.
+				assert_noop!(
+					TEE::update_enclave(alice, BOB, api_uri, attestation(CHARLIE)),
+					Error::<Test>::AttestationNotBoundToEnclave
+				);
+			})
+	}
 }
 
 mod cancel_update {
@@ -328,9 +649,19 @@ mod cancel_update {
 				start_active_era(1);
 
 				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
-				assert_ok!(TEE::register_enclave(alice.clone(), CHARLIE, api_uri.clone()));
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
 				assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
-				assert_ok!(TEE::update_enclave(alice.clone(), CHARLIE, api_uri));
+				assert_ok!(TEE::update_enclave(
+					alice.clone(),
+					CHARLIE,
+					api_uri,
+					attestation(CHARLIE)
+				));
 
 				assert_ok!(TEE::cancel_update(alice.clone()));
 
@@ -369,7 +700,12 @@ mod assign_enclave {
 				let cluster_id = 0u32;
 
 				start_active_era(1);
-				assert_ok!(TEE::register_enclave(alice.clone(), CHARLIE, api_uri.clone()));
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
 				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
 				assert_ok!(TEE::assign_enclave(root(), ALICE, cluster_id, 0));
 
@@ -405,8 +741,18 @@ mod assign_enclave {
 
 				start_active_era(1);
 
-				assert_ok!(TEE::register_enclave(alice.clone(), CHARLIE, api_uri.clone()));
-				assert_ok!(TEE::register_enclave(bob.clone(), CHARLIE, api_uri.clone()));
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
+				assert_ok!(TEE::register_enclave(
+					bob.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
 				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
 				assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
 				assert_noop!(
@@ -434,7 +780,12 @@ mod assign_enclave {
 			let api_uri: BoundedVec<u8, _> = BoundedVec::<u8, _>::try_from(api_uri_data)
 				.expect("API URI exceeds maximum length");
 
-			assert_ok!(TEE::register_enclave(alice.clone(), CHARLIE, api_uri.clone()));
+			assert_ok!(TEE::register_enclave(
+				alice.clone(),
+				CHARLIE,
+				api_uri.clone(),
+				attestation(CHARLIE)
+			));
 			assert_noop!(TEE::assign_enclave(root(), ALICE, 0, 0), Error::<Test>::ClusterNotFound);
 		})
 	}
@@ -452,9 +803,24 @@ mod assign_enclave {
 
 				start_active_era(1);
 
-				assert_ok!(TEE::register_enclave(alice.clone(), ALICE_ENCLAVE, api_uri.clone()));
-				assert_ok!(TEE::register_enclave(bob.clone(), BOB_ENCLAVE, api_uri.clone()));
-				assert_ok!(TEE::register_enclave(charlie.clone(), CHARLIE_ENCLAVE, api_uri));
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					ALICE_ENCLAVE,
+					api_uri.clone(),
+					attestation(ALICE_ENCLAVE)
+				));
+				assert_ok!(TEE::register_enclave(
+					bob.clone(),
+					BOB_ENCLAVE,
+					api_uri.clone(),
+					attestation(BOB_ENCLAVE)
+				));
+				assert_ok!(TEE::register_enclave(
+					charlie.clone(),
+					CHARLIE_ENCLAVE,
+					api_uri,
+					attestation(CHARLIE_ENCLAVE)
+				));
 				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
 				assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
 				assert_ok!(TEE::assign_enclave(root(), BOB, 0, 1));
@@ -464,6 +830,162 @@ mod assign_enclave {
 				);
 			})
 	}
+
+	#[test]
+	fn slot_id_already_taken() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (BOB, 1000)])
+			.build()
+			.execute_with(|| {
+				let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
+				start_active_era(1);
+				assert_ok!(TEE::register_enclave(
+					origin(ALICE),
+					ALICE_ENCLAVE,
+					api_uri.clone(),
+					attestation(ALICE_ENCLAVE)
+				));
+				assert_ok!(TEE::register_enclave(
+					origin(BOB),
+					BOB_ENCLAVE,
+					api_uri,
+					attestation(BOB_ENCLAVE)
+				));
+				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
+				assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
+				assert_noop!(
+					TEE::assign_enclave(root(), BOB, 0, 0),
+					Error::<Test>::SlotIdAlreadyTaken
+				);
+			})
+	}
+}
+
+mod auto_assign_enclave {
+	use super::*;
+
+	#[test]
+	fn auto_assign_enclave() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)])
+			.build()
+			.execute_with(|| {
+				let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
+				start_active_era(1);
+				for (operator, enclave) in
+					[(ALICE, ALICE_ENCLAVE), (BOB, BOB_ENCLAVE), (CHARLIE, CHARLIE_ENCLAVE)]
+				{
+					assert_ok!(TEE::register_enclave(
+						origin(operator),
+						enclave,
+						api_uri.clone(),
+						attestation(enclave)
+					));
+				}
+				assert_ok!(TEE::create_cluster(root(), ClusterType::Private));
+				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
+				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
+
+				// Both public clusters are empty, the lowest id is used
+				assert_ok!(TEE::auto_assign_enclave(root(), ALICE, ClusterType::Public));
+				assert_eq!(EnclaveClusterId::<Test>::get(ALICE), Some(1));
+				let event = RuntimeEvent::TEE(TEEEvent::EnclaveAutoAssigned {
+					operator_address: ALICE,
+					cluster_id: 1,
+					slot_id: 0,
+				});
+				System::assert_last_event(event);
+
+				// The second public cluster is now the least full
+				assert_ok!(TEE::auto_assign_enclave(root(), BOB, ClusterType::Public));
+				assert_eq!(EnclaveClusterId::<Test>::get(BOB), Some(2));
+				assert_eq!(ClusterData::<Test>::get(2).unwrap().enclaves.to_vec(), vec![(BOB, 0)]);
+
+				assert_ok!(TEE::auto_assign_enclave(root(), CHARLIE, ClusterType::Public));
+				assert_eq!(
+					ClusterData::<Test>::get(1).unwrap().enclaves.to_vec(),
+					vec![(ALICE, 0), (CHARLIE, 1)]
+				);
+				assert!(ClusterData::<Test>::get(0).unwrap().enclaves.is_empty());
+			})
+	}
+
+	#[test]
+	fn no_cluster_available() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 1000)])
+			.build()
+			.execute_with(|| {
+				let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
+				start_active_era(1);
+				for (operator, enclave) in
+					[(ALICE, ALICE_ENCLAVE), (BOB, BOB_ENCLAVE), (CHARLIE, CHARLIE_ENCLAVE)]
+				{
+					assert_ok!(TEE::register_enclave(
+						origin(operator),
+						enclave,
+						api_uri.clone(),
+						attestation(enclave)
+					));
+				}
+				assert_ok!(TEE::create_cluster(root(), ClusterType::Private));
+				assert_noop!(
+					TEE::auto_assign_enclave(root(), ALICE, ClusterType::Public),
+					Error::<Test>::NoClusterAvailable
+				);
+
+				// Full clusters are skipped
+				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
+				assert_ok!(TEE::auto_assign_enclave(root(), ALICE, ClusterType::Public));
+				assert_ok!(TEE::auto_assign_enclave(root(), BOB, ClusterType::Public));
+				assert_noop!(
+					TEE::auto_assign_enclave(root(), CHARLIE, ClusterType::Public),
+					Error::<Test>::NoClusterAvailable
+				);
+			})
+	}
+
+	#[test]
+	fn bad_origin() {
+		ExtBuilder::default().tokens(vec![(ALICE, 1000)]).build().execute_with(|| {
+			assert_noop!(
+				TEE::auto_assign_enclave(origin(ALICE), ALICE, ClusterType::Public),
+				BadOrigin
+			);
+		})
+	}
+}
+
+mod assign_enclave_backing {
+	use super::*;
+
+	#[test]
+	fn insufficient_backing() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
+				start_active_era(1);
+				assert_ok!(TEE::register_enclave(
+					origin(ALICE),
+					CHARLIE,
+					api_uri,
+					attestation(CHARLIE)
+				));
+				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
+				assert_ok!(TEE::set_min_cluster_backing(root(), 50));
+
+				assert_noop!(
+					TEE::assign_enclave(root(), ALICE, 0, 0),
+					Error::<Test>::InsufficientBacking
+				);
+
+				// Own stake of 20 and a nomination of 30
+				assert_ok!(TEE::nominate(origin(BOB), ALICE, 30));
+				assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
+			})
+	}
 }
 
 mod remove_registration {
@@ -477,7 +999,12 @@ mod remove_registration {
 			.execute_with(|| {
 				let alice: mock::RuntimeOrigin = origin(ALICE);
 				let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
-				assert_ok!(TEE::register_enclave(alice.clone(), CHARLIE, api_uri.clone()));
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
 				assert!(EnclaveRegistrations::<Test>::get(ALICE).is_some());
 				assert_ok!(TEE::remove_registration(root(), ALICE));
 				assert!(EnclaveRegistrations::<Test>::get(ALICE).is_none());
@@ -510,11 +1037,21 @@ mod reject_update {
 					"new_api_uri".as_bytes().to_vec().try_into().unwrap();
 				start_active_era(1);
 
-				assert_ok!(TEE::register_enclave(alice.clone(), CHARLIE, api_uri.clone()));
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
 				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
 				assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
 
-				assert_ok!(TEE::update_enclave(alice.clone(), BOB, new_api_uri.clone()));
+				assert_ok!(TEE::update_enclave(
+					alice.clone(),
+					BOB,
+					new_api_uri.clone(),
+					attestation(BOB)
+				));
 
 				assert_ok!(TEE::reject_update(root(), ALICE));
 				assert!(EnclaveUpdates::<Test>::get(ALICE).is_none());
@@ -544,11 +1081,21 @@ mod reject_update {
 					"new_api_uri".as_bytes().to_vec().try_into().unwrap();
 				start_active_era(1);
 
-				assert_ok!(TEE::register_enclave(alice.clone(), CHARLIE, api_uri.clone()));
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
 				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
 				assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
 
-				assert_ok!(TEE::update_enclave(alice.clone(), BOB, new_api_uri.clone()));
+				assert_ok!(TEE::update_enclave(
+					alice.clone(),
+					BOB,
+					new_api_uri.clone(),
+					attestation(BOB)
+				));
 
 				assert_noop!(TEE::reject_update(root(), BOB), Error::<Test>::UpdateRequestNotFound);
 				assert!(EnclaveUpdates::<Test>::get(ALICE).is_some());
@@ -577,7 +1124,12 @@ mod approve_enclave_unregistration {
 				let new_api_uri: BoundedVec<u8, MaxUriLen> =
 					"new_api_uri".as_bytes().to_vec().try_into().unwrap();
 				start_active_era(1);
-				assert_ok!(TEE::register_enclave(alice.clone(), CHARLIE, api_uri.clone()));
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
 				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
 				assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
 
@@ -585,7 +1137,12 @@ mod approve_enclave_unregistration {
 				assert!(EnclaveClusterId::<Test>::get(ALICE).is_some());
 				assert!(ClusterData::<Test>::get(0).unwrap().enclaves.get(0).is_some());
 
-				assert_ok!(TEE::update_enclave(alice.clone(), BOB, new_api_uri.clone()));
+				assert_ok!(TEE::update_enclave(
+					alice.clone(),
+					BOB,
+					new_api_uri.clone(),
+					attestation(BOB)
+				));
 				assert_ok!(TEE::unregister_enclave(alice.clone()));
 
 				assert!(EnclaveUpdates::<Test>::get(ALICE).is_some());
@@ -619,7 +1176,12 @@ mod force_remove_enclave {
 				let new_api_uri: BoundedVec<u8, MaxUriLen> =
 					"new_api_uri".as_bytes().to_vec().try_into().unwrap();
 				start_active_era(1);
-				assert_ok!(TEE::register_enclave(alice.clone(), CHARLIE, api_uri.clone()));
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
 				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
 				assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
 
@@ -627,7 +1189,12 @@ mod force_remove_enclave {
 				assert!(EnclaveClusterId::<Test>::get(ALICE).is_some());
 				assert!(ClusterData::<Test>::get(0).unwrap().enclaves.get(0).is_some());
 
-				assert_ok!(TEE::update_enclave(alice.clone(), BOB, new_api_uri.clone()));
+				assert_ok!(TEE::update_enclave(
+					alice.clone(),
+					BOB,
+					new_api_uri.clone(),
+					attestation(BOB)
+				));
 				assert_ok!(TEE::unregister_enclave(alice.clone()));
 
 				assert!(EnclaveUpdates::<Test>::get(ALICE).is_some());
@@ -645,6 +1212,42 @@ mod force_remove_enclave {
 				System::assert_last_event(event);
 			})
 	}
+
+	#[test]
+	fn fill_slot_holes() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (BOB, 1000)])
+			.build()
+			.execute_with(|| {
+				let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
+				start_active_era(1);
+				assert_ok!(TEE::register_enclave(
+					origin(ALICE),
+					ALICE_ENCLAVE,
+					api_uri.clone(),
+					attestation(ALICE_ENCLAVE)
+				));
+				assert_ok!(TEE::register_enclave(
+					origin(BOB),
+					BOB_ENCLAVE,
+					api_uri,
+					attestation(BOB_ENCLAVE)
+				));
+				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
+				assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
+				assert_ok!(TEE::assign_enclave(root(), BOB, 0, 1));
+
+				assert_ok!(TEE::force_remove_enclave(root(), ALICE));
+
+				assert_eq!(ClusterData::<Test>::get(0).unwrap().enclaves.to_vec(), vec![(BOB, 0)]);
+				System::assert_has_event(RuntimeEvent::TEE(TEEEvent::EnclaveSlotChanged {
+					operator_address: BOB,
+					cluster_id: 0,
+					slot_id: 0,
+				}));
+			})
+	}
+
 	#[test]
 	fn bad_origin() {
 		ExtBuilder::default().tokens(vec![(ALICE, 1000)]).build().execute_with(|| {
@@ -662,7 +1265,12 @@ mod force_remove_enclave {
 				let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
 				start_active_era(1);
 
-				assert_ok!(TEE::register_enclave(alice.clone(), CHARLIE, api_uri.clone()));
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
 				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
 				assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
 
@@ -684,7 +1292,12 @@ mod force_remove_enclave {
 				let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
 				start_active_era(1);
 
-				assert_ok!(TEE::register_enclave(alice.clone(), CHARLIE, api_uri.clone()));
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
 				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
 				assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
 
@@ -698,6 +1311,76 @@ mod force_remove_enclave {
 	}
 }
 
+mod approve_update_enclave {
+	use super::*;
+
+	#[test]
+	fn approve_update_enclave() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				let alice: mock::RuntimeOrigin = origin(ALICE);
+				let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
+				start_active_era(1);
+
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
+				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
+				assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
+				assert_ok!(TEE::update_enclave(alice, BOB, api_uri.clone(), attestation(BOB)));
+
+				assert_ok!(TEE::approve_update_enclave(root(), ALICE));
+
+				assert_eq!(EnclaveData::<Test>::get(ALICE), Some(Enclave::new(BOB, api_uri)));
+				assert_eq!(EnclaveAttestations::<Test>::get(ALICE), Some(APPROVED_MEASUREMENT));
+				assert!(EnclaveUpdates::<Test>::get(ALICE).is_none());
+				assert!(EnclaveUpdateAttestations::<Test>::get(ALICE).is_none());
+			})
+	}
+
+	#[test]
+	fn unattested_update() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				let alice: mock::RuntimeOrigin = origin(ALICE);
+				let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
+				start_active_era(1);
+
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
+				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
+				assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
+				let unsupported = AttestationQuote::new(
+					TeeKind::SevSnp,
+					APPROVED_MEASUREMENT,
+					b"quote".to_vec().try_into().unwrap(),
+				);
+				assert_ok!(TEE::set_allow_unverified_quotes(root(), true));
+				assert_ok!(TEE::update_enclave(alice, BOB, api_uri, unsupported));
+
+				assert_ok!(TEE::approve_update_enclave(root(), ALICE));
+
+				assert!(EnclaveAttestations::<Test>::get(ALICE).is_none());
+				let event = RuntimeEvent::TEE(TEEEvent::EnclaveAttestationUnverified {
+					operator_address: ALICE,
+					enclave_address: BOB,
+				});
+				System::assert_has_event(event);
+			})
+	}
+}
+
 mod force_update_enclave {
 	use super::*;
 
@@ -713,10 +1396,20 @@ mod force_update_enclave {
 					"new_api_uri".as_bytes().to_vec().try_into().unwrap();
 				start_active_era(1);
 
-				assert_ok!(TEE::register_enclave(alice.clone(), CHARLIE, api_uri.clone()));
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
 				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
 				assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
-				assert_ok!(TEE::update_enclave(alice.clone(), BOB, new_api_uri.clone()));
+				assert_ok!(TEE::update_enclave(
+					alice.clone(),
+					BOB,
+					new_api_uri.clone(),
+					attestation(BOB)
+				));
 				assert!(EnclaveUpdates::<Test>::get(ALICE).is_some());
 				assert_ok!(TEE::force_update_enclave(
 					root(),
@@ -728,6 +1421,8 @@ mod force_update_enclave {
 				let updated_record = Enclave::new(BOB, new_api_uri.clone());
 				assert_eq!(EnclaveData::<Test>::get(ALICE).unwrap(), updated_record);
 				assert!(EnclaveUpdates::<Test>::get(ALICE).is_none());
+				assert!(EnclaveUpdateAttestations::<Test>::get(ALICE).is_none());
+				assert!(EnclaveAttestations::<Test>::get(ALICE).is_none());
 
 				let event = RuntimeEvent::TEE(TEEEvent::EnclaveForceUpdated {
 					operator_address: ALICE,
@@ -762,8 +1457,18 @@ mod force_update_enclave {
 
 				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
 
-				assert_ok!(TEE::register_enclave(alice.clone(), CHARLIE, api_uri.clone()));
-				assert_ok!(TEE::register_enclave(bob.clone(), EVE, api_uri.clone()));
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
+				assert_ok!(TEE::register_enclave(
+					bob.clone(),
+					EVE,
+					api_uri.clone(),
+					attestation(EVE)
+				));
 
 				assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
 				assert_ok!(TEE::assign_enclave(root(), BOB, 0, 1));
@@ -787,7 +1492,12 @@ mod force_update_enclave {
 					"new_api_uri".as_bytes().to_vec().try_into().unwrap();
 				start_active_era(1);
 
-				assert_ok!(TEE::register_enclave(alice.clone(), CHARLIE, api_uri.clone()));
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
 				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
 				assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
 
@@ -815,7 +1525,12 @@ mod force_update_enclave {
 					"new_api_uri".as_bytes().to_vec().try_into().unwrap();
 				start_active_era(1);
 
-				assert_ok!(TEE::register_enclave(alice.clone(), CHARLIE, api_uri.clone()));
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
 				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
 				assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
 
@@ -936,7 +1651,12 @@ mod remove_cluster {
 				let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
 				start_active_era(1);
 
-				assert_ok!(TEE::register_enclave(alice.clone(), CHARLIE, api_uri.clone()));
+				assert_ok!(TEE::register_enclave(
+					alice.clone(),
+					CHARLIE,
+					api_uri.clone(),
+					attestation(CHARLIE)
+				));
 				assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
 				assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
 
@@ -944,3 +1664,1329 @@ mod remove_cluster {
 			})
 	}
 }
+
+mod approve_measurement {
+	use super::*;
+
+	#[test]
+	fn approve_measurement() {
+		ExtBuilder::default().build().execute_with(|| {
+			let policy = MeasurementPolicy::MrSigner(UNAPPROVED_MEASUREMENT.mr_signer);
+			assert_ok!(TEE::approve_measurement(root(), policy.clone()));
+
+			assert!(ApprovedMeasurements::<Test>::get(&policy).is_some());
+			let event = RuntimeEvent::TEE(TEEEvent::MeasurementApproved { policy });
+			System::assert_last_event(event);
+		})
+	}
+
+	#[test]
+	fn bad_origin() {
+		ExtBuilder::default().build().execute_with(|| {
+			let policy = MeasurementPolicy::MrSigner(UNAPPROVED_MEASUREMENT.mr_signer);
+			assert_noop!(TEE::approve_measurement(origin(ALICE), policy), BadOrigin);
+		})
+	}
+
+	#[test]
+	fn measurement_already_approved() {
+		ExtBuilder::default().build().execute_with(|| {
+			let policy = MeasurementPolicy::MrEnclave(APPROVED_MEASUREMENT.mr_enclave);
+			assert_noop!(
+				TEE::approve_measurement(root(), policy),
+				Error::<Test>::MeasurementAlreadyApproved
+			);
+		})
+	}
+}
+
+mod revoke_measurement {
+	use super::*;
+
+	#[test]
+	fn revoke_measurement() {
+		ExtBuilder::default().tokens(vec![(ALICE, 1000)]).build().execute_with(|| {
+			let policy = MeasurementPolicy::MrEnclave(APPROVED_MEASUREMENT.mr_enclave);
+			assert_ok!(TEE::revoke_measurement(root(), policy.clone()));
+
+			assert!(ApprovedMeasurements::<Test>::get(&policy).is_none());
+			let event = RuntimeEvent::TEE(TEEEvent::MeasurementRevoked { policy });
+			System::assert_last_event(event);
+
+			// New registrations with the revoked measurement are rejected.
+			let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
+			assert_noop!(
+				TEE::register_enclave(origin(ALICE), CHARLIE, api_uri, attestation(CHARLIE)),
+				Error::<Test>::MeasurementNotApproved
+			);
+		})
+	}
+
+	#[test]
+	fn measurement_not_found() {
+		ExtBuilder::default().build().execute_with(|| {
+			let policy = MeasurementPolicy::MrSigner(UNAPPROVED_MEASUREMENT.mr_signer);
+			assert_noop!(
+				TEE::revoke_measurement(root(), policy),
+				Error::<Test>::MeasurementNotFound
+			);
+		})
+	}
+}
+
+mod slash_operator {
+	use super::*;
+
+	#[test]
+	fn slash_operator() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_assigned_enclave();
+				let fraction = Perbill::from_percent(10);
+				let apply_at = System::block_number() + SlashDeferDuration::get() as u64;
+
+				assert_ok!(TEE::slash_operator(root(), ALICE, fraction, SlashReason::Offline));
+
+				let expected = PendingSlash::new(ALICE, fraction, SlashReason::Offline, apply_at);
+				assert_eq!(PendingSlashes::<Test>::get(0), Some(expected));
+				assert!(SlashSchedule::<Test>::get(apply_at, 0).is_some());
+				assert_eq!(TEE::tee_staking_ledger(ALICE).unwrap().staked_amount, 20);
+				let event = RuntimeEvent::TEE(TEEEvent::SlashReported {
+					slash_id: 0,
+					operator_address: ALICE,
+					fraction,
+					reason: SlashReason::Offline,
+					apply_at,
+				});
+				System::assert_last_event(event);
+
+				System::set_block_number(apply_at);
+				TEE::on_initialize(apply_at);
+
+				assert!(PendingSlashes::<Test>::get(0).is_none());
+				assert_eq!(TEE::tee_staking_ledger(ALICE).unwrap().staked_amount, 18);
+				assert_eq!(Balances::free_balance(ALICE), 998);
+				assert_eq!(Balances::free_balance(SLASH_RECEIVER), 2);
+				let event = RuntimeEvent::TEE(TEEEvent::OperatorSlashed {
+					operator_address: ALICE,
+					amount: 2,
+					reason: SlashReason::Offline,
+				});
+				System::assert_has_event(event);
+
+				// The remaining stake is too low, the enclave goes back to the registrations.
+				assert!(EnclaveData::<Test>::get(ALICE).is_none());
+				assert!(EnclaveAccountOperator::<Test>::get(CHARLIE).is_none());
+				assert!(EnclaveClusterId::<Test>::get(ALICE).is_none());
+				assert!(ClusterData::<Test>::get(0).unwrap().enclaves.is_empty());
+				assert_eq!(
+					EnclaveRegistrations::<Test>::get(ALICE),
+					Some(Enclave::new(CHARLIE, b"test".to_vec().try_into().unwrap()))
+				);
+				let event =
+					RuntimeEvent::TEE(TEEEvent::EnclaveUnassigned { operator_address: ALICE });
+				System::assert_last_event(event);
+			})
+	}
+
+	#[test]
+	fn slash_nominators() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_assigned_enclave();
+				assert_ok!(TEE::nominate(origin(BOB), ALICE, 100));
+				let fraction = Perbill::from_percent(10);
+				let apply_at = System::block_number() + SlashDeferDuration::get() as u64;
+				assert_ok!(TEE::slash_operator(root(), ALICE, fraction, SlashReason::Offline));
+
+				System::set_block_number(apply_at);
+				TEE::on_initialize(apply_at);
+
+				assert_eq!(Balances::free_balance(BOB), 990);
+				assert_eq!(Balances::free_balance(SLASH_RECEIVER), 12);
+				assert_eq!(Nominators::<Test>::get(ALICE).into_inner(), vec![(BOB, 90)]);
+				assert_eq!(NominatorLedgers::<Test>::get(BOB).unwrap().amount, 90);
+				let event = RuntimeEvent::TEE(TEEEvent::NominatorSlashed {
+					nominator: BOB,
+					operator_address: ALICE,
+					amount: 10,
+				});
+				System::assert_has_event(event);
+			})
+	}
+
+	#[test]
+	fn slash_unbonding_nominators() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_assigned_enclave();
+				assert_ok!(TEE::nominate(origin(BOB), ALICE, 100));
+				let fraction = Perbill::from_percent(10);
+				let apply_at = System::block_number() + SlashDeferDuration::get() as u64;
+				assert_ok!(TEE::slash_operator(root(), ALICE, fraction, SlashReason::Offline));
+
+				// Unbonding does not escape the pending slash.
+				assert_ok!(TEE::unbond_nomination(origin(BOB)));
+				System::set_block_number(apply_at);
+				TEE::on_initialize(apply_at);
+
+				assert_eq!(Balances::free_balance(BOB), 990);
+				assert_eq!(UnbondingNominators::<Test>::get(ALICE).into_inner(), vec![(BOB, 90)]);
+				assert_eq!(NominatorLedgers::<Test>::get(BOB).unwrap().amount, 90);
+				let event = RuntimeEvent::TEE(TEEEvent::NominatorSlashed {
+					nominator: BOB,
+					operator_address: ALICE,
+					amount: 10,
+				});
+				System::assert_has_event(event);
+			})
+	}
+
+	#[test]
+	fn slash_by_metrics_server() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_assigned_enclave();
+				let metrics_server = MetricsServer::new(BOB, ClusterType::Public);
+				assert_ok!(TEE::register_metrics_server(root(), metrics_server));
+
+				assert_ok!(TEE::slash_operator(
+					origin(BOB),
+					ALICE,
+					Perbill::from_percent(10),
+					SlashReason::Misbehaviour
+				));
+				assert!(PendingSlashes::<Test>::get(0).is_some());
+			})
+	}
+
+	#[test]
+	fn metrics_server_address_not_found() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_assigned_enclave();
+
+				assert_noop!(
+					TEE::slash_operator(
+						origin(BOB),
+						ALICE,
+						Perbill::from_percent(10),
+						SlashReason::Misbehaviour
+					),
+					Error::<Test>::MetricsServerAddressNotFound
+				);
+			})
+	}
+
+	#[test]
+	fn staking_not_found() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_noop!(
+				TEE::slash_operator(root(), ALICE, Perbill::from_percent(10), SlashReason::Offline),
+				Error::<Test>::StakingNotFound
+			);
+		})
+	}
+
+	#[test]
+	fn slashes_over_the_block_limit() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_assigned_enclave();
+				let fraction = Perbill::from_percent(10);
+				let apply_at = System::block_number() + SlashDeferDuration::get() as u64;
+				for _ in 0..3 {
+					assert_ok!(TEE::slash_operator(root(), ALICE, fraction, SlashReason::Offline));
+				}
+
+				System::set_block_number(apply_at);
+				TEE::on_initialize(apply_at);
+
+				// Only two slashes fit in the block, the last one is left for the next block.
+				assert_eq!(PendingSlashes::<Test>::iter().count(), 1);
+				assert_eq!(NextSlashBlock::<Test>::get(), Some(apply_at));
+
+				System::set_block_number(apply_at + 1);
+				TEE::on_initialize(apply_at + 1);
+
+				assert_eq!(PendingSlashes::<Test>::iter().count(), 0);
+				assert_eq!(NextSlashBlock::<Test>::get(), Some(apply_at + 2));
+			})
+	}
+}
+
+mod cancel_slash {
+	use super::*;
+
+	#[test]
+	fn cancel_slash() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_assigned_enclave();
+				let fraction = Perbill::from_percent(10);
+				let apply_at = System::block_number() + SlashDeferDuration::get() as u64;
+				assert_ok!(TEE::slash_operator(root(), ALICE, fraction, SlashReason::Offline));
+
+				assert_ok!(TEE::cancel_slash(root(), 0));
+
+				assert!(PendingSlashes::<Test>::get(0).is_none());
+				assert!(SlashSchedule::<Test>::get(apply_at, 0).is_none());
+				let event = RuntimeEvent::TEE(TEEEvent::SlashCancelled { slash_id: 0 });
+				System::assert_last_event(event);
+
+				System::set_block_number(apply_at);
+				TEE::on_initialize(apply_at);
+				assert_eq!(TEE::tee_staking_ledger(ALICE).unwrap().staked_amount, 20);
+				assert!(EnclaveData::<Test>::get(ALICE).is_some());
+			})
+	}
+
+	#[test]
+	fn bad_origin() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_noop!(TEE::cancel_slash(origin(ALICE), 0), BadOrigin);
+		})
+	}
+
+	#[test]
+	fn slash_not_found() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_noop!(TEE::cancel_slash(root(), 0), Error::<Test>::SlashNotFound);
+		})
+	}
+}
+
+mod set_metrics {
+	use super::*;
+
+	#[test]
+	fn set_metrics() {
+		ExtBuilder::default().build().execute_with(|| {
+			let metrics: BoundedVec<_, MaxMetrics> =
+				vec![metric(0, 20, AggregationRule::Max), metric(1, 30, AggregationRule::Median)]
+					.try_into()
+					.unwrap();
+			assert_ok!(TEE::set_metrics(root(), metrics.clone()));
+
+			assert_eq!(Metrics::<Test>::get(), metrics);
+			let event = RuntimeEvent::TEE(TEEEvent::MetricsSet { metrics });
+			System::assert_last_event(event);
+		})
+	}
+
+	#[test]
+	fn bad_origin() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_noop!(TEE::set_metrics(origin(ALICE), BoundedVec::default()), BadOrigin);
+		})
+	}
+
+	#[test]
+	fn duplicate_metric() {
+		ExtBuilder::default().build().execute_with(|| {
+			let metrics: BoundedVec<_, MaxMetrics> =
+				vec![metric(0, 20, AggregationRule::Max), metric(0, 30, AggregationRule::Mean)]
+					.try_into()
+					.unwrap();
+			assert_noop!(TEE::set_metrics(root(), metrics), Error::<Test>::DuplicateMetric);
+		})
+	}
+}
+
+mod submit_metrics_server_report {
+	use super::*;
+
+	fn prepare_metrics() {
+		prepare_assigned_enclave();
+		let metrics = vec![
+			metric(0, 10, AggregationRule::Max),
+			metric(1, 20, AggregationRule::Median),
+			metric(2, 30, AggregationRule::Mean),
+			metric(3, 1, AggregationRule::TrimmedMean(Percent::from_percent(25))),
+		];
+		assert_ok!(TEE::set_metrics(root(), metrics.try_into().unwrap()));
+		let metrics_server = MetricsServer::new(BOB, ClusterType::Public);
+		assert_ok!(TEE::register_metrics_server(root(), metrics_server));
+	}
+
+	#[test]
+	fn submit_metrics_server_report() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_metrics();
+				let metrics_server_report = report(vec![(0, 50), (2, 70)], BOB);
+
+				assert_ok!(TEE::submit_metrics_server_report(
+					origin(BOB),
+					ALICE,
+					metrics_server_report.clone()
+				));
+
+				let reports = MetricsReports::<Test>::get(0, ALICE).unwrap();
+				assert_eq!(reports, vec![metrics_server_report.clone()]);
+				let event = RuntimeEvent::TEE(TEEEvent::MetricsServerReportSubmitted {
+					era: 0,
+					operator_address: ALICE,
+					metrics_server_report,
+				});
+				System::assert_last_event(event);
+			})
+	}
+
+	#[test]
+	fn weighted_sum_aggregates_reports() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_metrics();
+				let reports: BoundedVec<_, ListSizeLimit> = vec![
+					report(vec![(0, 10), (1, 10), (2, 10)], BOB),
+					report(vec![(0, 40), (1, 20), (2, 20)], DAVE),
+					report(vec![(0, 20), (1, 90)], EVE),
+				]
+				.try_into()
+				.unwrap();
+
+				// Max 40 * 10 + median 20 * 20 + mean 15 * 30
+				let aggregated = TEE::aggregate_reports(&reports);
+				assert_eq!(TEE::calculate_weighted_sum(&aggregated), 400 + 400 + 450);
+			})
+	}
+
+	#[test]
+	fn trimmed_mean_drops_extremes() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_metrics();
+				let reports: BoundedVec<_, ListSizeLimit> = vec![
+					report(vec![(3, 0)], BOB),
+					report(vec![(3, 50)], DAVE),
+					report(vec![(3, 60)], EVE),
+					report(vec![(3, 100)], CHARLIE),
+				]
+				.try_into()
+				.unwrap();
+
+				let aggregated = TEE::aggregate_reports(&reports);
+				assert_eq!(
+					aggregated,
+					vec![(
+						metric(3, 1, AggregationRule::TrimmedMean(Percent::from_percent(25))),
+						55
+					)]
+				);
+			})
+	}
+
+	#[test]
+	fn record_outliers() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_metrics();
+				let reports: BoundedVec<_, ListSizeLimit> = vec![
+					report(vec![(1, 20)], BOB),
+					report(vec![(1, 25)], DAVE),
+					report(vec![(1, 90)], EVE),
+				]
+				.try_into()
+				.unwrap();
+
+				let aggregated = TEE::aggregate_reports(&reports);
+				TEE::record_outliers(0, &ALICE, &reports, &aggregated);
+
+				assert_eq!(MetricsServerOutliers::<Test>::get(0, BOB), 0);
+				assert_eq!(MetricsServerOutliers::<Test>::get(0, DAVE), 0);
+				assert_eq!(MetricsServerOutliers::<Test>::get(0, EVE), 1);
+				let event = RuntimeEvent::TEE(TEEEvent::MetricsServerOutlier {
+					era: 0,
+					metrics_server_address: EVE,
+					operator_address: ALICE,
+				});
+				System::assert_last_event(event);
+			})
+	}
+
+	#[test]
+	fn outliers_recorded_at_era_end() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_metrics();
+				let reports: BoundedVec<_, ListSizeLimit> = vec![
+					report(vec![(1, 20)], BOB),
+					report(vec![(1, 25)], DAVE),
+					report(vec![(1, 90)], EVE),
+				]
+				.try_into()
+				.unwrap();
+				// Alice has no stake in the era but the reports on her still count.
+				MetricsReports::<Test>::insert(1, ALICE, reports);
+				ErasRewardPool::<Test>::insert(1, 100);
+
+				set_active_era(3);
+				TEE::on_initialize(System::block_number());
+				assert_eq!(MetricsServerOutliers::<Test>::get(1, BOB), 0);
+				assert_eq!(MetricsServerOutliers::<Test>::get(1, DAVE), 0);
+				assert_eq!(MetricsServerOutliers::<Test>::get(1, EVE), 1);
+
+				// The outliers of an era are recorded once.
+				TEE::on_initialize(System::block_number());
+				assert_eq!(MetricsServerOutliers::<Test>::get(1, EVE), 1);
+			})
+	}
+
+	#[test]
+	fn metrics_report_submitter_mismatch() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_metrics();
+
+				assert_noop!(
+					TEE::submit_metrics_server_report(
+						origin(BOB),
+						ALICE,
+						report(vec![(0, 50)], DAVE)
+					),
+					Error::<Test>::MetricsReportSubmitterMismatch
+				);
+			})
+	}
+
+	#[test]
+	fn unknown_metric() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_metrics();
+
+				assert_noop!(
+					TEE::submit_metrics_server_report(
+						origin(BOB),
+						ALICE,
+						report(vec![(3, 50)], BOB)
+					),
+					Error::<Test>::UnknownMetric
+				);
+			})
+	}
+
+	#[test]
+	fn duplicate_metric() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_metrics();
+				let metrics_server_report = report(vec![(0, 50), (0, 60)], BOB);
+
+				assert_noop!(
+					TEE::submit_metrics_server_report(origin(BOB), ALICE, metrics_server_report),
+					Error::<Test>::DuplicateMetric
+				);
+			})
+	}
+}
+
+mod set_report_quorum {
+	use super::*;
+
+	#[test]
+	fn set_report_quorum() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(TEE::set_report_quorum(root(), 3));
+
+			assert_eq!(ReportQuorum::<Test>::get(), 3);
+			let event = RuntimeEvent::TEE(TEEEvent::ReportQuorumSet { quorum: 3 });
+			System::assert_last_event(event);
+		})
+	}
+
+	#[test]
+	fn bad_origin() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_noop!(TEE::set_report_quorum(origin(ALICE), 3), BadOrigin);
+		})
+	}
+}
+
+mod set_allow_unverified_quotes {
+	use super::*;
+
+	#[test]
+	fn set_allow_unverified_quotes() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(TEE::set_allow_unverified_quotes(root(), true));
+
+			assert!(AllowUnverifiedQuotes::<Test>::get());
+			let event = RuntimeEvent::TEE(TEEEvent::AllowUnverifiedQuotesSet { allow: true });
+			System::assert_last_event(event);
+		})
+	}
+
+	#[test]
+	fn bad_origin() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_noop!(TEE::set_allow_unverified_quotes(origin(ALICE), true), BadOrigin);
+		})
+	}
+}
+
+mod claim_rewards {
+	use super::*;
+
+	fn prepare_era_rewards(dave_score: u8) {
+		let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
+		Balances::make_free_balance_be(&TEE::account_id(), 10_000);
+		set_active_era(1);
+
+		assert_ok!(TEE::register_enclave(
+			origin(ALICE),
+			CHARLIE,
+			api_uri.clone(),
+			attestation(CHARLIE)
+		));
+		assert_ok!(TEE::register_enclave(origin(DAVE), EVE, api_uri, attestation(EVE)));
+		assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
+		assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
+		assert_ok!(TEE::assign_enclave(root(), DAVE, 0, 1));
+
+		let metrics = vec![metric(0, 100, AggregationRule::Max)];
+		assert_ok!(TEE::set_metrics(root(), metrics.try_into().unwrap()));
+		let metrics_server = MetricsServer::new(BOB, ClusterType::Public);
+		assert_ok!(TEE::register_metrics_server(root(), metrics_server));
+
+		// Reports submitted during era 2 are accounted to era 1.
+		set_active_era(2);
+		assert_ok!(TEE::submit_metrics_server_report(
+			origin(BOB),
+			ALICE,
+			report(vec![(0, 100)], BOB)
+		));
+		assert_ok!(TEE::submit_metrics_server_report(
+			origin(BOB),
+			DAVE,
+			report(vec![(0, dave_score)], BOB)
+		));
+		TEE::snapshot_era(1);
+		TEE::compute_era_reward_points(1);
+
+		set_active_era(4);
+	}
+
+	#[test]
+	fn claim_rewards() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100), (DAVE, 1000), (EVE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_era_rewards(25);
+				let pool = InitalDailyRewardPool::get();
+				assert_eq!(ErasRewardPool::<Test>::get(1), Some(pool));
+				assert_eq!(ErasOperatorStakes::<Test>::get(1, ALICE), Some(20));
+
+				// Same stake, scores of 100% and 25%
+				assert_ok!(TEE::claim_rewards(origin(ALICE), 1));
+				assert_ok!(TEE::claim_rewards(origin(DAVE), 1));
+
+				assert_eq!(ClaimedRewards::<Test>::get(1, ALICE), Some(pool * 4 / 5));
+				assert_eq!(ClaimedRewards::<Test>::get(1, DAVE), Some(pool / 5));
+				let event = RuntimeEvent::TEE(TEEEvent::RewardsClaimed {
+					era: 1,
+					operator_address: DAVE,
+					amount: pool / 5,
+				});
+				System::assert_last_event(event);
+			})
+	}
+
+	#[test]
+	fn non_performing_share_is_redistributed() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100), (DAVE, 1000), (EVE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_era_rewards(0);
+				let pool = InitalDailyRewardPool::get();
+
+				assert_ok!(TEE::claim_rewards(origin(ALICE), 1));
+				assert_ok!(TEE::claim_rewards(origin(DAVE), 1));
+
+				assert_eq!(ClaimedRewards::<Test>::get(1, ALICE), Some(pool));
+				assert_eq!(ClaimedRewards::<Test>::get(1, DAVE), Some(0));
+			})
+	}
+
+	#[test]
+	fn operator_without_reports_gets_no_score() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100), (DAVE, 1000), (EVE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_era_rewards(100);
+				MetricsReports::<Test>::remove(1, DAVE);
+				TEE::compute_era_reward_points(1);
+				let pool = InitalDailyRewardPool::get();
+
+				assert_ok!(TEE::claim_rewards(origin(ALICE), 1));
+				assert_ok!(TEE::claim_rewards(origin(DAVE), 1));
+
+				assert_eq!(ClaimedRewards::<Test>::get(1, ALICE), Some(pool));
+				assert_eq!(ClaimedRewards::<Test>::get(1, DAVE), Some(0));
+			})
+	}
+
+	#[test]
+	fn operator_below_report_quorum_gets_no_score() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100), (DAVE, 1000), (EVE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_era_rewards(100);
+				assert_ok!(TEE::set_report_quorum(root(), 2));
+				TEE::compute_era_reward_points(1);
+
+				assert_ok!(TEE::claim_rewards(origin(ALICE), 1));
+				assert_ok!(TEE::claim_rewards(origin(DAVE), 1));
+
+				assert_eq!(ClaimedRewards::<Test>::get(1, ALICE), Some(0));
+				assert_eq!(ClaimedRewards::<Test>::get(1, DAVE), Some(0));
+			})
+	}
+
+	#[test]
+	fn reward_points_computed_after_reports() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100), (DAVE, 1000), (EVE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_era_rewards(25);
+				ErasRewardPoints::<Test>::remove(1, ALICE);
+				ErasTotalRewardPoints::<Test>::remove(1);
+
+				// The reports of era 1 can still be submitted during era 2.
+				set_active_era(2);
+				TEE::on_initialize(System::block_number());
+				assert_eq!(ErasTotalRewardPoints::<Test>::get(1), None);
+
+				set_active_era(3);
+				TEE::on_initialize(System::block_number());
+				assert_eq!(ErasRewardPoints::<Test>::get(1, ALICE), 20);
+				assert_eq!(ErasTotalRewardPoints::<Test>::get(1), Some(25));
+			})
+	}
+
+	#[test]
+	fn reward_points_not_computed() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100), (DAVE, 1000), (EVE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_era_rewards(25);
+				ErasTotalRewardPoints::<Test>::remove(1);
+
+				assert_noop!(
+					TEE::claim_rewards(origin(ALICE), 1),
+					Error::<Test>::EraRewardPointsNotComputed
+				);
+			})
+	}
+
+	#[test]
+	fn claim_rewards_of_era_before_snapshots() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100), (DAVE, 1000), (EVE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_era_rewards(25);
+				ErasRewardPool::<Test>::remove(1);
+				ErasOperatorStakes::<Test>::remove(1, ALICE);
+				ErasOperatorStakes::<Test>::remove(1, DAVE);
+				let pool = InitalDailyRewardPool::get();
+
+				// The pool is shared equally and weighted by the reports.
+				assert_ok!(TEE::claim_rewards(origin(ALICE), 1));
+				assert_ok!(TEE::claim_rewards(origin(DAVE), 1));
+
+				assert_eq!(ClaimedRewards::<Test>::get(1, ALICE), Some(pool / 2));
+				assert_eq!(ClaimedRewards::<Test>::get(1, DAVE), Some(pool / 8));
+			})
+	}
+
+	#[test]
+	fn not_eligible_for_rewards() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100), (DAVE, 1000), (EVE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_era_rewards(25);
+				ErasOperatorStakes::<Test>::remove(1, DAVE);
+
+				assert_noop!(
+					TEE::claim_rewards(origin(DAVE), 1),
+					Error::<Test>::NotEligibleForRewards
+				);
+			})
+	}
+
+	#[test]
+	fn claim_rewards_for_eras() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100), (DAVE, 1000), (EVE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_era_rewards(25);
+				// No reports for era 2, no operator gets a score.
+				TEE::snapshot_era(2);
+				TEE::compute_era_reward_points(2);
+				set_active_era(5);
+				let pool = InitalDailyRewardPool::get();
+
+				let eras: BoundedVec<_, TeeHistoryDepth> = vec![1, 2].try_into().unwrap();
+				assert_ok!(TEE::claim_rewards_for_eras(origin(ALICE), eras));
+
+				assert_eq!(ClaimedRewards::<Test>::get(1, ALICE), Some(pool * 4 / 5));
+				assert_eq!(ClaimedRewards::<Test>::get(2, ALICE), Some(0));
+			})
+	}
+
+	#[test]
+	fn claim_rewards_for_eras_fails_as_a_whole() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100), (DAVE, 1000), (EVE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_era_rewards(25);
+
+				let eras: BoundedVec<_, TeeHistoryDepth> = vec![1, 3].try_into().unwrap();
+				assert_noop!(
+					TEE::claim_rewards_for_eras(origin(ALICE), eras),
+					Error::<Test>::InvalidEraToClaimRewards
+				);
+			})
+	}
+
+	#[test]
+	fn payout_operator() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100), (DAVE, 1000), (EVE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_era_rewards(25);
+				let pool = InitalDailyRewardPool::get();
+				let alice_balance = Balances::free_balance(ALICE);
+
+				assert_ok!(TEE::payout_operator(origin(BOB), ALICE, 1));
+
+				assert_eq!(Balances::free_balance(ALICE), alice_balance + pool * 4 / 5);
+				assert_noop!(
+					TEE::payout_operator(origin(BOB), ALICE, 1),
+					Error::<Test>::RewardsAlreadyClaimedForEra
+				);
+			})
+	}
+
+	#[test]
+	fn payout_to_reward_destination() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100), (DAVE, 1000), (EVE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_era_rewards(25);
+				let pool = InitalDailyRewardPool::get();
+				let destination = RewardDestination::Account(BOB);
+				assert_ok!(TEE::set_reward_destination(origin(ALICE), destination));
+				let alice_balance = Balances::free_balance(ALICE);
+
+				assert_ok!(TEE::payout_operator(origin(DAVE), ALICE, 1));
+
+				assert_eq!(Balances::free_balance(ALICE), alice_balance);
+				assert_eq!(Balances::free_balance(BOB), pool * 4 / 5);
+			})
+	}
+
+	#[test]
+	fn nominators_share_rewards() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 100), (DAVE, 1000), (EVE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_era_rewards(0);
+				assert_ok!(TEE::nominate(origin(BOB), ALICE, 20));
+				assert_ok!(TEE::set_commission(origin(ALICE), Perbill::from_percent(10)));
+				TEE::snapshot_era(1);
+				TEE::compute_era_reward_points(1);
+				let pool = InitalDailyRewardPool::get();
+				let bob_balance = Balances::free_balance(BOB);
+
+				assert_ok!(TEE::claim_rewards(origin(ALICE), 1));
+
+				// Half of the backing comes from BOB, ALICE keeps 10% of BOB's share.
+				let bob_reward = pool / 2 - pool / 20;
+				assert_eq!(Balances::free_balance(BOB), bob_balance + bob_reward);
+				assert_eq!(ClaimedRewards::<Test>::get(1, ALICE), Some(pool - bob_reward));
+				let event = RuntimeEvent::TEE(TEEEvent::NominatorRewarded {
+					era: 1,
+					operator_address: ALICE,
+					nominator: BOB,
+					amount: bob_reward,
+				});
+				System::assert_has_event(event);
+			})
+	}
+
+	#[test]
+	fn unpayable_nominator_share_is_skipped() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 100), (DAVE, 1000), (EVE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_era_rewards(0);
+				assert_ok!(TEE::nominate(origin(BOB), ALICE, 60));
+				TEE::snapshot_era(1);
+				TEE::compute_era_reward_points(1);
+				let pool = InitalDailyRewardPool::get();
+				let bob_balance = Balances::free_balance(BOB);
+
+				// The pot can pay ALICE's share but not BOB's.
+				Balances::make_free_balance_be(&TEE::account_id(), pool / 2);
+				assert_ok!(TEE::claim_rewards(origin(ALICE), 1));
+
+				assert_eq!(Balances::free_balance(BOB), bob_balance);
+				assert_eq!(ClaimedRewards::<Test>::get(1, ALICE), Some(pool / 4));
+				let event = RuntimeEvent::TEE(TEEEvent::NominatorRewardSkipped {
+					era: 1,
+					operator_address: ALICE,
+					nominator: BOB,
+					amount: pool * 3 / 4,
+				});
+				System::assert_has_event(event);
+			})
+	}
+
+	#[test]
+	fn rewards_already_claimed_for_era() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100), (DAVE, 1000), (EVE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_era_rewards(25);
+				assert_ok!(TEE::claim_rewards(origin(ALICE), 1));
+
+				assert_noop!(
+					TEE::claim_rewards(origin(ALICE), 1),
+					Error::<Test>::RewardsAlreadyClaimedForEra
+				);
+			})
+	}
+}
+
+mod set_reward_destination {
+	use super::*;
+
+	#[test]
+	fn set_reward_destination() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_assigned_enclave();
+				let destination = RewardDestination::Account(BOB);
+
+				assert_ok!(TEE::set_reward_destination(origin(ALICE), destination.clone()));
+
+				assert_eq!(RewardDestinations::<Test>::get(ALICE), destination);
+				let event = RuntimeEvent::TEE(TEEEvent::RewardDestinationSet {
+					operator_address: ALICE,
+					destination,
+				});
+				System::assert_last_event(event);
+			})
+	}
+
+	#[test]
+	fn staking_not_found() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_noop!(
+				TEE::set_reward_destination(origin(ALICE), RewardDestination::Operator),
+				Error::<Test>::StakingNotFound
+			);
+		})
+	}
+}
+
+mod nominate {
+	use super::*;
+
+	#[test]
+	fn nominate() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_assigned_enclave();
+
+				assert_ok!(TEE::nominate(origin(BOB), ALICE, 100));
+
+				assert_eq!(Nominators::<Test>::get(ALICE).into_inner(), vec![(BOB, 100)]);
+				let nomination = NominatorLedger::new(ALICE, 100, false, 0);
+				assert_eq!(NominatorLedgers::<Test>::get(BOB), Some(nomination));
+				assert_eq!(Balances::usable_balance(BOB), 900);
+				let event = RuntimeEvent::TEE(TEEEvent::Nominated {
+					nominator: BOB,
+					operator_address: ALICE,
+					amount: 100,
+				});
+				System::assert_last_event(event);
+			})
+	}
+
+	#[test]
+	fn already_nominating() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_assigned_enclave();
+				assert_ok!(TEE::nominate(origin(BOB), ALICE, 100));
+
+				assert_noop!(
+					TEE::nominate(origin(BOB), ALICE, 100),
+					Error::<Test>::AlreadyNominating
+				);
+			})
+	}
+
+	#[test]
+	fn nomination_below_minimum() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_assigned_enclave();
+				let amount = MinNominationBond::get() - 1;
+
+				assert_noop!(
+					TEE::nominate(origin(BOB), ALICE, amount),
+					Error::<Test>::NominationBelowMinimum
+				);
+			})
+	}
+
+	#[test]
+	fn nomination_target_not_found() {
+		ExtBuilder::default().tokens(vec![(BOB, 1000)]).build().execute_with(|| {
+			assert_noop!(
+				TEE::nominate(origin(BOB), ALICE, 100),
+				Error::<Test>::NominationTargetNotFound
+			);
+		})
+	}
+
+	#[test]
+	fn insufficient_balance_to_bond() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (BOB, 50), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_assigned_enclave();
+
+				assert_noop!(
+					TEE::nominate(origin(BOB), ALICE, 100),
+					Error::<Test>::InsufficientBalanceToBond
+				);
+			})
+	}
+
+	#[test]
+	fn nominators_limit_reached() {
+		let nominators: Vec<u64> = (10..10 + MaxNominators::get() as u64 + 1).collect();
+		let mut tokens: Vec<_> = nominators.iter().map(|n| (*n, 1000)).collect();
+		tokens.extend([(ALICE, 1000), (CHARLIE, 100)]);
+		ExtBuilder::default().tokens(tokens).build().execute_with(|| {
+			prepare_assigned_enclave();
+			let (last, others) = nominators.split_last().unwrap();
+			for nominator in others {
+				assert_ok!(TEE::nominate(origin(*nominator), ALICE, 100));
+			}
+
+			assert_noop!(
+				TEE::nominate(origin(*last), ALICE, 100),
+				Error::<Test>::NominatorsLimitReached
+			);
+		})
+	}
+}
+
+mod unbond_nomination {
+	use super::*;
+
+	#[test]
+	fn unbond_nomination() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_assigned_enclave();
+				assert_ok!(TEE::nominate(origin(BOB), ALICE, 100));
+
+				assert_ok!(TEE::unbond_nomination(origin(BOB)));
+
+				assert!(Nominators::<Test>::get(ALICE).is_empty());
+				assert_eq!(UnbondingNominators::<Test>::get(ALICE).into_inner(), vec![(BOB, 100)]);
+				let nomination = NominatorLedger::new(ALICE, 100, true, System::block_number());
+				assert_eq!(NominatorLedgers::<Test>::get(BOB), Some(nomination));
+				let event = RuntimeEvent::TEE(TEEEvent::NominationUnbonded {
+					nominator: BOB,
+					operator_address: ALICE,
+					amount: 100,
+				});
+				System::assert_last_event(event);
+			})
+	}
+
+	#[test]
+	fn nomination_not_found() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_noop!(TEE::unbond_nomination(origin(BOB)), Error::<Test>::NominationNotFound);
+		})
+	}
+
+	#[test]
+	fn nomination_already_unbonding() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_assigned_enclave();
+				assert_ok!(TEE::nominate(origin(BOB), ALICE, 100));
+				assert_ok!(TEE::unbond_nomination(origin(BOB)));
+
+				assert_noop!(
+					TEE::unbond_nomination(origin(BOB)),
+					Error::<Test>::NominationAlreadyUnbonding
+				);
+			})
+	}
+}
+
+mod withdraw_nomination {
+	use super::*;
+
+	#[test]
+	fn withdraw_nomination() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_assigned_enclave();
+				assert_ok!(TEE::nominate(origin(BOB), ALICE, 100));
+				assert_ok!(TEE::unbond_nomination(origin(BOB)));
+				let now = System::block_number();
+				System::set_block_number(now + TeeBondingDuration::get() as u64);
+
+				assert_ok!(TEE::withdraw_nomination(origin(BOB)));
+
+				assert!(NominatorLedgers::<Test>::get(BOB).is_none());
+				assert!(UnbondingNominators::<Test>::get(ALICE).is_empty());
+				assert_eq!(Balances::usable_balance(BOB), 1000);
+				let event = RuntimeEvent::TEE(TEEEvent::NominationWithdrawn {
+					nominator: BOB,
+					amount: 100,
+				});
+				System::assert_last_event(event);
+			})
+	}
+
+	#[test]
+	fn unbonding_not_started() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_assigned_enclave();
+				assert_ok!(TEE::nominate(origin(BOB), ALICE, 100));
+
+				assert_noop!(
+					TEE::withdraw_nomination(origin(BOB)),
+					Error::<Test>::UnbondingNotStarted
+				);
+			})
+	}
+
+	#[test]
+	fn withdraw_prohibited() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (BOB, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_assigned_enclave();
+				assert_ok!(TEE::nominate(origin(BOB), ALICE, 100));
+				assert_ok!(TEE::unbond_nomination(origin(BOB)));
+
+				assert_noop!(
+					TEE::withdraw_nomination(origin(BOB)),
+					Error::<Test>::WithdrawProhibited
+				);
+			})
+	}
+}
+
+mod set_commission {
+	use super::*;
+
+	#[test]
+	fn set_commission() {
+		ExtBuilder::default()
+			.tokens(vec![(ALICE, 1000), (CHARLIE, 100)])
+			.build()
+			.execute_with(|| {
+				prepare_assigned_enclave();
+				let commission = Perbill::from_percent(10);
+
+				assert_ok!(TEE::set_commission(origin(ALICE), commission));
+
+				assert_eq!(Commissions::<Test>::get(ALICE), commission);
+				let event = RuntimeEvent::TEE(TEEEvent::CommissionSet {
+					operator_address: ALICE,
+					commission,
+				});
+				System::assert_last_event(event);
+			})
+	}
+
+	#[test]
+	fn staking_not_found() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_noop!(
+				TEE::set_commission(origin(ALICE), Perbill::from_percent(10)),
+				Error::<Test>::StakingNotFound
+			);
+		})
+	}
+}
+
+mod set_min_cluster_backing {
+	use super::*;
+
+	#[test]
+	fn set_min_cluster_backing() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_ok!(TEE::set_min_cluster_backing(root(), 50));
+
+			assert_eq!(MinClusterBacking::<Test>::get(), 50);
+			let event = RuntimeEvent::TEE(TEEEvent::MinClusterBackingSet { amount: 50 });
+			System::assert_last_event(event);
+		})
+	}
+
+	#[test]
+	fn bad_origin() {
+		ExtBuilder::default().build().execute_with(|| {
+			assert_noop!(TEE::set_min_cluster_backing(origin(ALICE), 50), BadOrigin);
+		})
+	}
+}
+
+mod heartbeat {
+	use super::*;
+
+	fn prepare_enclave() {
+		let api_uri: BoundedVec<u8, MaxUriLen> = b"test".to_vec().try_into().unwrap();
+		set_active_era(1);
+		assert_ok!(TEE::register_enclave(
+			origin(ALICE),
+			ALICE_ENCLAVE,
+			api_uri,
+			attestation(ALICE_ENCLAVE)
+		));
+		assert_ok!(TEE::create_cluster(root(), ClusterType::Public));
+		assert_ok!(TEE::assign_enclave(root(), ALICE, 0, 0));
+	}
+
+	#[test]
+	fn heartbeat() {
+		ExtBuilder::default().tokens(vec![(ALICE, 1000)]).build().execute_with(|| {
+			prepare_enclave();
+			MissedHeartbeats::<Test>::insert(ALICE, 2);
+
+			let info = TEE::heartbeat(origin(ALICE_ENCLAVE)).unwrap();
+			assert_eq!(info.pays_fee, Pays::No);
+			assert_eq!(EnclaveHeartbeats::<Test>::get(ALICE), Some(1));
+			assert_eq!(MissedHeartbeats::<Test>::get(ALICE), 0);
+
+			let event =
+				RuntimeEvent::TEE(TEEEvent::HeartbeatReceived { operator_address: ALICE, era: 1 });
+			System::assert_last_event(event);
+		})
+	}
+
+	#[test]
+	fn heartbeat_already_sent() {
+		ExtBuilder::default().tokens(vec![(ALICE, 1000)]).build().execute_with(|| {
+			prepare_enclave();
+			assert_ok!(TEE::heartbeat(origin(ALICE_ENCLAVE)));
+			assert_noop!(
+				TEE::heartbeat(origin(ALICE_ENCLAVE)),
+				Error::<Test>::HeartbeatAlreadySent
+			);
+
+			set_active_era(2);
+			assert_ok!(TEE::heartbeat(origin(ALICE_ENCLAVE)));
+		})
+	}
+
+	#[test]
+	fn enclave_address_not_found() {
+		ExtBuilder::default().tokens(vec![(ALICE, 1000)]).build().execute_with(|| {
+			prepare_enclave();
+			assert_noop!(TEE::heartbeat(origin(ALICE)), Error::<Test>::EnclaveAddressNotFound);
+		})
+	}
+
+	#[test]
+	fn missed_heartbeats() {
+		ExtBuilder::default().tokens(vec![(ALICE, 1000)]).build().execute_with(|| {
+			prepare_enclave();
+			assert_ok!(TEE::heartbeat(origin(ALICE_ENCLAVE)));
+
+			// Eras 2 to 4 go by without heartbeat
+			for era in 2..=5 {
+				set_active_era(era);
+				TEE::on_initialize(System::block_number());
+				assert_eq!(MissedHeartbeats::<Test>::get(ALICE), era - 2);
+			}
+			assert!(!TEE::is_enclave_active(&ALICE));
+			System::assert_has_event(RuntimeEvent::TEE(TEEEvent::EnclaveMarkedInactive {
+				operator_address: ALICE,
+			}));
+
+			assert_ok!(TEE::heartbeat(origin(ALICE_ENCLAVE)));
+			assert!(TEE::is_enclave_active(&ALICE));
+		})
+	}
+}
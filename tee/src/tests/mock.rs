@@ -17,10 +17,11 @@
 use frame_election_provider_support::{onchain, SequentialPhragmen};
 use frame_support::{
 	parameter_types,
-	traits::{ConstU32, ConstU64, Contains, Get, Hooks},
+	traits::{ConstU32, ConstU64, Contains, Currency, Get, Hooks, OnUnbalanced},
 	PalletId,
 };
-use sp_core::H256;
+use parity_scale_codec::{Decode, Encode};
+use sp_core::{ed25519, hashing::blake2_256, Pair, H256};
 use sp_runtime::{
 	curve::PiecewiseLinear,
 	testing::Header,
@@ -36,7 +37,11 @@ type AccountId = u64;
 pub const INIT_TIMESTAMP: u64 = 30_000;
 pub const BLOCK_TIME: u64 = 1000;
 
-use crate::{self as tee, Config};
+use crate::{
+	self as tee, ApprovedMeasurements, AttestationQuote, AttestationVerifier, Config,
+	EnclaveMeasurement, MeasurementPolicy, NegativeImbalanceOf, QuoteVerification, TeeKind,
+	VerifiedQuote,
+};
 
 frame_support::construct_runtime!(
 	pub enum Test where
@@ -238,6 +243,16 @@ parameter_types! {
 	pub const InitalDailyRewardPool: Balance = 2000;
 	pub const TeePalletId: PalletId = PalletId(*b"tern/tee");
 	pub const TeeHistoryDepth: u32 = 50;
+	pub const MaxQuoteLen: u32 = 256;
+	pub const SlashDeferDuration: u32 = 10;
+	pub const MaxSlashesPerBlock: u32 = 2;
+	pub const MaxMetrics: u32 = 10;
+	pub const MaxMetricNameLen: u32 = 32;
+	pub const InitialReportQuorum: u32 = 1;
+	pub const OutlierThreshold: u8 = 20;
+	pub const MaxNominators: u32 = 4;
+	pub const MinNominationBond: Balance = 10;
+	pub const MaxMissedHeartbeats: u32 = 3;
 }
 
 impl Config for Test {
@@ -253,6 +268,79 @@ impl Config for Test {
 	type InitalDailyRewardPool = InitalDailyRewardPool;
 	type PalletId = TeePalletId;
 	type TeeHistoryDepth = TeeHistoryDepth;
+	type MaxQuoteLen = MaxQuoteLen;
+	type AttestationVerifier = MockAttestationVerifier;
+	type Slash = SlashReceiver;
+	type SlashDeferDuration = SlashDeferDuration;
+	type MaxSlashesPerBlock = MaxSlashesPerBlock;
+	type MaxMetrics = MaxMetrics;
+	type MaxMetricNameLen = MaxMetricNameLen;
+	type InitialReportQuorum = InitialReportQuorum;
+	type OutlierThreshold = OutlierThreshold;
+	type MaxNominators = MaxNominators;
+	type MinNominationBond = MinNominationBond;
+	type MaxMissedHeartbeats = MaxMissedHeartbeats;
+	type EnclaveSlotHandler = ();
+}
+
+/// Account receiving the slashed stakes.
+pub const SLASH_RECEIVER: u64 = 99;
+
+pub struct SlashReceiver;
+impl OnUnbalanced<NegativeImbalanceOf<Test>> for SlashReceiver {
+	fn on_nonzero_unbalanced(amount: NegativeImbalanceOf<Test>) {
+		Balances::resolve_creating(&SLASH_RECEIVER, amount);
+	}
+}
+
+/// Seed of the key acting as root of trust for the test quotes.
+pub const ATTESTATION_SIGNER_SEED: [u8; 32] = [7; 32];
+pub const APPROVED_MEASUREMENT: EnclaveMeasurement =
+	EnclaveMeasurement { mr_enclave: [1; 32], mr_signer: [2; 32] };
+
+/// Test quotes are a SCALE encoded `VerifiedQuote` followed by its ed25519 signature. They are
+/// not real DCAP quotes, only `Sgx` quotes are understood.
+pub struct MockAttestationVerifier;
+impl AttestationVerifier for MockAttestationVerifier {
+	fn verify_quote(kind: &TeeKind, quote: &[u8]) -> QuoteVerification {
+		if *kind != TeeKind::Sgx {
+			return QuoteVerification::Unsupported;
+		}
+		if quote.len() < 64 {
+			return QuoteVerification::Invalid;
+		}
+		let (body, signature) = quote.split_at(quote.len() - 64);
+		let signer = ed25519::Pair::from_seed(&ATTESTATION_SIGNER_SEED).public();
+		let verified = ed25519::Signature::from_slice(signature)
+			.filter(|signature| sp_io::crypto::ed25519_verify(signature, body, &signer))
+			.and_then(|_| VerifiedQuote::decode(&mut &body[..]).ok());
+		match verified {
+			Some(verified) => QuoteVerification::Verified(verified),
+			None => QuoteVerification::Invalid,
+		}
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn benchmark_quote(_kind: &TeeKind, verified: &VerifiedQuote) -> Vec<u8> {
+		sign_quote(verified)
+	}
+}
+
+pub fn sign_quote(verified: &VerifiedQuote) -> Vec<u8> {
+	let mut quote = verified.encode();
+	let signature = ed25519::Pair::from_seed(&ATTESTATION_SIGNER_SEED).sign(&quote);
+	quote.extend_from_slice(signature.as_ref());
+	quote
+}
+
+/// Returns a valid attestation of an enclave running the approved measurement.
+pub fn attestation(enclave_address: u64) -> AttestationQuote<MaxQuoteLen> {
+	let verified = VerifiedQuote {
+		measurement: APPROVED_MEASUREMENT,
+		report_data: blake2_256(&enclave_address.encode()),
+	};
+	let quote = sign_quote(&verified).try_into().unwrap();
+	AttestationQuote::new(TeeKind::Sgx, APPROVED_MEASUREMENT, quote)
 }
 
 // Do not use the `0` account id since this would be the default value
@@ -292,7 +380,11 @@ impl ExtBuilder {
 			.unwrap();
 
 		let mut ext = sp_io::TestExternalities::new(t);
-		ext.execute_with(|| System::set_block_number(1));
+		ext.execute_with(|| {
+			System::set_block_number(1);
+			let policy = MeasurementPolicy::MrEnclave(APPROVED_MEASUREMENT.mr_enclave);
+			ApprovedMeasurements::<Test>::insert(policy, ());
+		});
 		ext
 	}
 }
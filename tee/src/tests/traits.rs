@@ -39,8 +39,18 @@ fn ensure_enclave() {
 			start_active_era(1);
 
 			assert_ok!(TEE::create_cluster(root(), crate::ClusterType::Public));
-			assert_ok!(TEE::register_enclave(alice.clone(), ALICE_ENCLAVE, api_uri.clone()));
-			assert_ok!(TEE::register_enclave(bob.clone(), BOB_ENCLAVE, api_uri.clone()));
+			assert_ok!(TEE::register_enclave(
+				alice.clone(),
+				ALICE_ENCLAVE,
+				api_uri.clone(),
+				attestation(ALICE_ENCLAVE)
+			));
+			assert_ok!(TEE::register_enclave(
+				bob.clone(),
+				BOB_ENCLAVE,
+				api_uri.clone(),
+				attestation(BOB_ENCLAVE)
+			));
 			assert_ok!(TEE::assign_enclave(root(), ALICE, cluster_id, 0));
 			assert_ok!(TEE::assign_enclave(root(), BOB, cluster_id, 1));
 
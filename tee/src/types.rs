@@ -18,8 +18,8 @@ use frame_support::{traits::Get, BoundedVec, CloneNoBound, PartialEqNoBound, Run
 use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
 use primitives::tee::SlotId;
 use scale_info::TypeInfo;
-use sp_arithmetic::traits::AtLeast32BitUnsigned;
-use sp_std::fmt::Debug;
+use sp_arithmetic::{traits::AtLeast32BitUnsigned, PerThing, Perbill, Percent};
+use sp_std::{fmt::Debug, vec::Vec};
 
 #[derive(
 	Encode, Decode, CloneNoBound, PartialEqNoBound, Eq, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen,
@@ -79,6 +79,31 @@ where
 	) -> Self {
 		Self { enclaves, cluster_type }
 	}
+
+	/// Lowest slot not used by an enclave of the cluster.
+	pub fn free_slot(&self) -> SlotId {
+		(0..=self.enclaves.len() as SlotId)
+			.find(|slot| !self.enclaves.iter().any(|(_, slot_id)| slot_id == slot))
+			.unwrap_or_default()
+	}
+
+	/// Move the enclaves whose slot is out of the `0..len` range into the free slots below, so
+	/// that the used slots stay contiguous. Returns the moved enclaves with their old and new slot.
+	pub fn fill_slot_holes(&mut self) -> Vec<(AccountId, SlotId, SlotId)> {
+		let len = self.enclaves.len() as SlotId;
+		let mut moved = Vec::new();
+		for index in 0..self.enclaves.len() {
+			if self.enclaves[index].1 >= len {
+				let slot_id = self.free_slot();
+				if let Some(enclave) = self.enclaves.get_mut(index) {
+					moved.push((enclave.0.clone(), enclave.1, slot_id));
+					enclave.1 = slot_id;
+				}
+			}
+		}
+
+		moved
+	}
 }
 
 /// The ledger of a (bonded) operator.
@@ -117,67 +142,113 @@ where
 		Self { operator, staked_amount, is_unlocking, unbonded_at }
 	}
 }
-// #[derive(Clone, Eq, PartialEq, Encode, Decode, Default, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub type MetricId = u32;
+
+/// Rule used to combine the values reported by the metrics servers for a metric.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen)]
+pub enum AggregationRule {
+	Max,
+	Median,
+	Mean,
+	/// Mean of the values once the given share of the lowest and of the highest got dropped.
+	TrimmedMean(Percent),
+}
+
+impl AggregationRule {
+	/// Combine the given values, returns `None` if there are none.
+	pub fn aggregate(&self, mut values: Vec<u8>) -> Option<u8> {
+		if values.is_empty() {
+			return None
+		}
+
+		let result = match self {
+			AggregationRule::Max => values.iter().copied().max().unwrap_or_default(),
+			AggregationRule::Median => {
+				values.sort_unstable();
+				let middle = values.len() / 2;
+				if values.len() % 2 == 0 {
+					((values[middle - 1] as u16 + values[middle] as u16) / 2) as u8
+				} else {
+					values[middle]
+				}
+			},
+			AggregationRule::Mean => Self::mean(&values),
+			AggregationRule::TrimmedMean(trim) => {
+				values.sort_unstable();
+				// Always keep at least one value.
+				let count = trim.mul_floor(values.len() as u32) as usize;
+				let count = count.min((values.len() - 1) / 2);
+				Self::mean(&values[count..values.len() - count])
+			},
+		};
+
+		Some(result)
+	}
+
+	fn mean(values: &[u8]) -> u8 {
+		let sum: u32 = values.iter().map(|x| *x as u32).sum();
+		(sum / values.len() as u32) as u8
+	}
+}
 
+/// Metric registered by governance that metrics servers report on.
 #[derive(
-	Encode,
-	Decode,
-	CloneNoBound,
-	PartialEqNoBound,
-	Eq,
-	RuntimeDebugNoBound,
-	TypeInfo,
-	MaxEncodedLen,
-	Default,
+	Encode, Decode, CloneNoBound, PartialEqNoBound, Eq, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen,
 )]
-#[scale_info(skip_type_params(ListSizeLimit))]
-#[codec(mel_bound(AccountId: MaxEncodedLen))]
-pub struct MetricsServerReport<AccountId>
+#[scale_info(skip_type_params(MaxNameLen))]
+pub struct Metric<MaxNameLen>
 where
-	AccountId: Clone + PartialEq + Debug,
+	MaxNameLen: Get<u32>,
 {
-	pub param_1: u8,
-	pub param_2: u8,
-	pub param_3: u8,
-	pub param_4: u8,
-	pub param_5: u8,
-	pub submitted_by: AccountId,
+	pub id: MetricId,
+	pub name: BoundedVec<u8, MaxNameLen>,
+	/// Weightage of the metric in the rewards computation.
+	pub weight: u8,
+	pub aggregation: AggregationRule,
+}
+
+impl<MaxNameLen> Metric<MaxNameLen>
+where
+	MaxNameLen: Get<u32>,
+{
+	pub fn new(
+		id: MetricId,
+		name: BoundedVec<u8, MaxNameLen>,
+		weight: u8,
+		aggregation: AggregationRule,
+	) -> Self {
+		Self { id, name, weight, aggregation }
+	}
 }
 
-/// Report Parameters weightage
 #[derive(
-	PartialEqNoBound, CloneNoBound, Encode, Decode, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen,
+	Encode, Decode, CloneNoBound, PartialEqNoBound, Eq, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen,
 )]
-pub struct ReportParamsWeightage {
-	pub param_1_weightage: u8,
-	pub param_2_weightage: u8,
-	pub param_3_weightage: u8,
-	pub param_4_weightage: u8,
-	pub param_5_weightage: u8,
+#[scale_info(skip_type_params(MaxMetrics))]
+#[codec(mel_bound(AccountId: MaxEncodedLen))]
+pub struct MetricsServerReport<AccountId, MaxMetrics>
+where
+	AccountId: Clone + PartialEq + Debug,
+	MaxMetrics: Get<u32>,
+{
+	/// Reported value of each metric.
+	pub values: BoundedVec<(MetricId, u8), MaxMetrics>,
+	pub submitted_by: AccountId,
 }
 
-impl Default for ReportParamsWeightage {
-	fn default() -> Self {
-		Self {
-			param_1_weightage: 0,
-			param_2_weightage: 0,
-			param_3_weightage: 0,
-			param_4_weightage: 0,
-			param_5_weightage: 0,
-		}
+impl<AccountId, MaxMetrics> MetricsServerReport<AccountId, MaxMetrics>
+where
+	AccountId: Clone + PartialEq + Debug,
+	MaxMetrics: Get<u32>,
+{
+	pub fn new(values: BoundedVec<(MetricId, u8), MaxMetrics>, submitted_by: AccountId) -> Self {
+		Self { values, submitted_by }
 	}
-}
 
-/// Report Parameters weightage
-#[derive(
-	PartialEqNoBound, CloneNoBound, Encode, Decode, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen,
-)]
-pub struct HighestParamsResponse {
-	pub param_1: u8,
-	pub param_2: u8,
-	pub param_3: u8,
-	pub param_4: u8,
-	pub param_5: u8,
+	/// Returns the reported value of a metric.
+	pub fn value(&self, metric_id: MetricId) -> Option<u8> {
+		self.values.iter().find(|(id, _)| *id == metric_id).map(|(_, value)| *value)
+	}
 }
 
 #[derive(
@@ -200,3 +271,246 @@ where
 		Self { metrics_server_address, supported_cluster_type }
 	}
 }
+
+/// Raw 256-bit enclave measurement (MRENCLAVE / MRSIGNER or their SEV-SNP counterparts).
+pub type Measurement = [u8; 32];
+
+/// Kind of trusted execution environment that produced an attestation quote.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen)]
+pub enum TeeKind {
+	Sgx,
+	SevSnp,
+}
+
+/// Identity of the code running inside an enclave.
+#[derive(
+	PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen,
+)]
+pub struct EnclaveMeasurement {
+	/// Hash of the enclave code and initial data.
+	pub mr_enclave: Measurement,
+	/// Hash of the key that signed the enclave.
+	pub mr_signer: Measurement,
+}
+
+/// Entry of the allow-list of approved enclaves managed by governance.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen)]
+pub enum MeasurementPolicy {
+	/// Only enclaves with this exact MRENCLAVE are approved.
+	MrEnclave(Measurement),
+	/// Any enclave signed by this MRSIGNER is approved.
+	MrSigner(Measurement),
+}
+
+/// Attestation payload submitted by an operator along with an enclave registration.
+#[derive(
+	Encode, Decode, CloneNoBound, PartialEqNoBound, Eq, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(MaxQuoteLen))]
+pub struct AttestationQuote<MaxQuoteLen>
+where
+	MaxQuoteLen: Get<u32>,
+{
+	pub kind: TeeKind,
+	/// Measurement the operator claims the enclave is running.
+	pub measurement: EnclaveMeasurement,
+	/// Raw quote bytes, including the signature chain.
+	pub quote: BoundedVec<u8, MaxQuoteLen>,
+}
+
+impl<MaxQuoteLen> AttestationQuote<MaxQuoteLen>
+where
+	MaxQuoteLen: Get<u32>,
+{
+	pub fn new(
+		kind: TeeKind,
+		measurement: EnclaveMeasurement,
+		quote: BoundedVec<u8, MaxQuoteLen>,
+	) -> Self {
+		Self { kind, measurement, quote }
+	}
+}
+
+/// Content of a quote whose signature chain was successfully verified.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen)]
+pub struct VerifiedQuote {
+	pub measurement: EnclaveMeasurement,
+	/// Data committed by the enclave in its report, expected to be the hash of its address.
+	pub report_data: [u8; 32],
+}
+
+/// Outcome of the verification of a raw attestation quote.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen)]
+pub enum QuoteVerification {
+	/// The signature chain of the quote is valid.
+	Verified(VerifiedQuote),
+	/// The signature chain of the quote is invalid.
+	Invalid,
+	/// The verifier cannot check quotes of this kind.
+	Unsupported,
+}
+
+/// Verifies the signature chain of raw attestation quotes.
+///
+/// This is an extension point only: the pallet does not ship a verifier for the vendor formats
+/// (SGX DCAP certificate chain, SEV-SNP VCEK chain). Runtimes have to provide one. Quotes that
+/// the configured verifier reports as `Unsupported` are rejected unless governance allows
+/// unverified quotes, in which case the enclave is flagged as unattested and the root assignment
+/// stays the only trust gate for it.
+pub trait AttestationVerifier {
+	/// Checks the signature chain of the quote and returns its content if it is valid.
+	fn verify_quote(kind: &TeeKind, quote: &[u8]) -> QuoteVerification;
+
+	/// Returns a quote that passes verification, used to benchmark registrations.
+	#[cfg(feature = "runtime-benchmarks")]
+	fn benchmark_quote(kind: &TeeKind, verified: &VerifiedQuote) -> Vec<u8>;
+}
+
+/// Stub verifier that checks nothing, registrations are rejected unless governance allows
+/// unverified quotes.
+impl AttestationVerifier for () {
+	fn verify_quote(_kind: &TeeKind, _quote: &[u8]) -> QuoteVerification {
+		QuoteVerification::Unsupported
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn benchmark_quote(_kind: &TeeKind, _verified: &VerifiedQuote) -> Vec<u8> {
+		Vec::new()
+	}
+}
+
+pub type SlashId = u32;
+
+/// Reason given for slashing an operator.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen)]
+pub enum SlashReason {
+	/// The enclave did not answer for a significant amount of time.
+	Offline,
+	/// The enclave or its operator did not follow the protocol.
+	Misbehaviour,
+}
+
+/// A slash that will be applied once its cancellation window is over.
+#[derive(
+	Encode, Decode, CloneNoBound, PartialEqNoBound, Eq, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen,
+)]
+#[codec(mel_bound(AccountId: MaxEncodedLen, BlockNumber: MaxEncodedLen))]
+pub struct PendingSlash<AccountId, BlockNumber>
+where
+	AccountId: Clone + PartialEq + Debug,
+	BlockNumber: Clone + PartialEq + Debug,
+{
+	pub operator_address: AccountId,
+	/// Fraction of the staked amount to slash.
+	pub fraction: Perbill,
+	pub reason: SlashReason,
+	/// Block number at which the slash gets applied.
+	pub apply_at: BlockNumber,
+}
+
+impl<AccountId, BlockNumber> PendingSlash<AccountId, BlockNumber>
+where
+	AccountId: Clone + PartialEq + Debug,
+	BlockNumber: Clone + PartialEq + Debug,
+{
+	pub fn new(
+		operator_address: AccountId,
+		fraction: Perbill,
+		reason: SlashReason,
+		apply_at: BlockNumber,
+	) -> Self {
+		Self { operator_address, fraction, reason, apply_at }
+	}
+}
+
+/// Account receiving the rewards of an operator.
+#[derive(
+	Encode, Decode, CloneNoBound, PartialEqNoBound, Eq, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen,
+)]
+#[codec(mel_bound(AccountId: MaxEncodedLen))]
+pub enum RewardDestination<AccountId>
+where
+	AccountId: Clone + PartialEq + Debug,
+{
+	/// Pay into the operator account.
+	Operator,
+	/// Pay into a specified account.
+	Account(AccountId),
+}
+
+impl<AccountId> Default for RewardDestination<AccountId>
+where
+	AccountId: Clone + PartialEq + Debug,
+{
+	fn default() -> Self {
+		RewardDestination::Operator
+	}
+}
+
+/// The ledger of an account backing an operator.
+#[derive(
+	PartialEqNoBound, CloneNoBound, Encode, Decode, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen,
+)]
+#[codec(mel_bound(AccountId: MaxEncodedLen, BlockNumber: MaxEncodedLen, Balance: MaxEncodedLen))]
+pub struct NominatorLedger<AccountId, BlockNumber, Balance>
+where
+	AccountId: Clone + PartialEq + Debug,
+	BlockNumber: Clone + PartialEq + Debug,
+	Balance: Clone + PartialEq + Debug,
+{
+	/// The operator backed by the nominator.
+	pub operator: AccountId,
+	/// The nominated amount
+	pub amount: Balance,
+	/// State variable to know whether the nominated amount is unbonded
+	pub is_unlocking: bool,
+	/// Block Number of when unbonded happened
+	pub unbonded_at: BlockNumber,
+}
+
+impl<AccountId, BlockNumber, Balance> NominatorLedger<AccountId, BlockNumber, Balance>
+where
+	AccountId: Clone + PartialEq + Debug,
+	BlockNumber: Clone + PartialEq + Debug,
+	Balance: Clone + PartialEq + Debug,
+{
+	pub fn new(
+		operator: AccountId,
+		amount: Balance,
+		is_unlocking: bool,
+		unbonded_at: BlockNumber,
+	) -> Self {
+		Self { operator, amount, is_unlocking, unbonded_at }
+	}
+}
+
+/// The nominators backing an operator during an era.
+#[derive(
+	PartialEqNoBound, CloneNoBound, Encode, Decode, RuntimeDebugNoBound, TypeInfo, MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(MaxNominators))]
+#[codec(mel_bound(AccountId: MaxEncodedLen, Balance: MaxEncodedLen))]
+pub struct NominatorsExposure<AccountId, Balance, MaxNominators>
+where
+	AccountId: Clone + PartialEq + Debug,
+	Balance: Clone + PartialEq + Debug,
+	MaxNominators: Get<u32>,
+{
+	/// Share of the nominators rewards kept by the operator.
+	pub commission: Perbill,
+	pub nominators: BoundedVec<(AccountId, Balance), MaxNominators>,
+}
+
+impl<AccountId, Balance, MaxNominators> NominatorsExposure<AccountId, Balance, MaxNominators>
+where
+	AccountId: Clone + PartialEq + Debug,
+	Balance: Clone + PartialEq + Debug,
+	MaxNominators: Get<u32>,
+{
+	pub fn new(
+		commission: Perbill,
+		nominators: BoundedVec<(AccountId, Balance), MaxNominators>,
+	) -> Self {
+		Self { commission, nominators }
+	}
+}
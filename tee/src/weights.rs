@@ -34,13 +34,28 @@ pub trait WeightInfo {
 	fn unregister_metrics_server() -> Weight;
 	fn force_update_metrics_server_type() -> Weight;
 	fn submit_metrics_server_report() -> Weight;
-	fn set_report_params_weightage() -> Weight;
+	fn set_metrics() -> Weight;
 	fn set_staking_amount() -> Weight;
 	fn set_daily_reward_pool() -> Weight;
 	fn claim_rewards() -> Weight;
 	fn update_operator_assigned_era() -> Weight;
 	fn bond_extra() -> Weight;
 	fn refund_excess() -> Weight;
+	fn approve_measurement() -> Weight;
+	fn revoke_measurement() -> Weight;
+	fn slash_operator() -> Weight;
+	fn cancel_slash() -> Weight;
+	fn set_report_quorum() -> Weight;
+	fn set_reward_destination() -> Weight;
+	fn payout_operator() -> Weight;
+	fn nominate() -> Weight;
+	fn unbond_nomination() -> Weight;
+	fn withdraw_nomination() -> Weight;
+	fn set_commission() -> Weight;
+	fn set_min_cluster_backing() -> Weight;
+	fn auto_assign_enclave() -> Weight;
+	fn heartbeat() -> Weight;
+	fn set_allow_unverified_quotes() -> Weight;
 }
 
 impl WeightInfo for () {
@@ -95,7 +110,7 @@ impl WeightInfo for () {
 	fn submit_metrics_server_report() -> Weight {
 		Weight::from_parts(10_000_000, 0)
 	}
-	fn set_report_params_weightage() -> Weight {
+	fn set_metrics() -> Weight {
 		Weight::from_parts(10_000_000, 0)
 	}
 	fn set_staking_amount() -> Weight {
@@ -116,4 +131,49 @@ impl WeightInfo for () {
 	fn refund_excess() -> Weight {
 		Weight::from_parts(10_000_000, 0)
 	}
+	fn approve_measurement() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
+	fn revoke_measurement() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
+	fn slash_operator() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
+	fn cancel_slash() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
+	fn set_report_quorum() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
+	fn set_reward_destination() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
+	fn payout_operator() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
+	fn nominate() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
+	fn unbond_nomination() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
+	fn withdraw_nomination() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
+	fn set_commission() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
+	fn set_min_cluster_backing() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
+	fn auto_assign_enclave() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
+	fn heartbeat() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
+	fn set_allow_unverified_quotes() -> Weight {
+		Weight::from_parts(10_000_000, 0)
+	}
 }